@@ -2,9 +2,10 @@
 //!
 //! 展示如何使用 memory-core 库进行记忆存储和检索
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use memory_core::{MemorySystem, Message, QueryParams};
+use memory_core::graph::{ExportFormat, ExportOptions, TraversalOptions};
+use memory_core::{MemorySystem, Message, NodeType, QueryParams};
 use std::io::{self, Write};
 
 #[derive(Parser)]
@@ -60,6 +61,60 @@ enum Commands {
         /// 服务端URL
         url: String,
     },
+
+    /// 导出知识图谱
+    Export {
+        /// 导出格式
+        #[arg(short, long, value_enum, default_value = "graphml")]
+        format: ExportFormatArg,
+
+        /// 输出文件（默认输出到标准输出）
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 只导出这些节点周围的子图（可重复）
+        #[arg(long)]
+        seed: Vec<uuid::Uuid>,
+
+        /// 子图扩展跳数
+        #[arg(long, default_value = "2")]
+        hops: usize,
+
+        /// 只导出这些类型的节点：entity / event / concept（可重复）
+        #[arg(long)]
+        node_type: Vec<String>,
+
+        /// 只导出这些关系类型的边（可重复）
+        #[arg(long)]
+        relation: Vec<String>,
+    },
+
+    /// 图模式查询（类 Cypher 语法）
+    Match {
+        /// 查询语句，如 MATCH (m:entity {content: "妈妈"})-->(e:event) RETURN e
+        query: String,
+    },
+
+    /// 查看记忆统计
+    Stats,
+}
+
+/// 导出格式参数
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormatArg {
+    Graphml,
+    Dot,
+    Json,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(arg: ExportFormatArg) -> Self {
+        match arg {
+            ExportFormatArg::Graphml => ExportFormat::GraphMl,
+            ExportFormatArg::Dot => ExportFormat::Dot,
+            ExportFormatArg::Json => ExportFormat::JsonNodeLink,
+        }
+    }
 }
 
 #[tokio::main]
@@ -141,6 +196,90 @@ async fn main() -> anyhow::Result<()> {
             system.set_server_url(url).await;
             println!("{}", "✓ 服务端URL已设置".green());
         }
+
+        Commands::Export { format, output, seed, hops, node_type, relation } => {
+            let mut system = MemorySystem::new(Some(&cli.db_path))?;
+            system.initialize().await?;
+
+            let mut traversal = TraversalOptions::default();
+            if !node_type.is_empty() {
+                traversal = traversal.with_node_types(parse_node_types(&node_type)?);
+            }
+            if !relation.is_empty() {
+                traversal = traversal.with_relations(relation);
+            }
+            let options = ExportOptions {
+                traversal,
+                seed_ids: seed,
+                hop_depth: hops,
+            };
+
+            let exported = system.export_graph(format.into(), &options).await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, exported)?;
+                    println!("{} 图已导出到 {}", "✓".green(), path);
+                }
+                None => println!("{}", exported),
+            }
+        }
+
+        Commands::Match { query } => {
+            let mut system = MemorySystem::new(Some(&cli.db_path))?;
+            system.initialize().await?;
+
+            let result = system.pattern_query(&query).await?;
+            if result.rows.is_empty() {
+                println!("{}", "没有匹配的结果".yellow());
+            } else {
+                println!("{}", result.columns.join(" | ").cyan());
+                for row in &result.rows {
+                    let cells: Vec<String> = row.iter()
+                        .map(|n| format!("[{}] {}", n.node_type.as_str(), n.content.chars().take(30).collect::<String>()))
+                        .collect();
+                    println!("{}", cells.join(" | "));
+                }
+                println!("\n共 {} 行", result.rows.len());
+            }
+        }
+
+        Commands::Stats => {
+            let mut system = MemorySystem::new(Some(&cli.db_path))?;
+            system.initialize().await?;
+
+            let stats = system.stats().await?;
+            println!("{}", "节点".cyan());
+            println!("  总数: {}", stats.node_count);
+            for (node_type, count) in &stats.nodes_by_type {
+                println!("  {}: {}", node_type, count);
+            }
+            for (entity_type, count) in &stats.entities_by_type {
+                println!("    entity/{}: {}", entity_type, count);
+            }
+            println!("  缺少嵌入: {}", stats.nodes_without_embedding);
+
+            println!("{}", "关系".cyan());
+            println!("  总数: {}", stats.edge_count);
+            for (relation, count) in &stats.edges_by_relation {
+                println!("  {}: {}", relation, count);
+            }
+
+            println!("{}", "图结构".cyan());
+            println!("  平均度数: {:.2}", stats.average_degree);
+            println!("  连通分量: {}", stats.component_count);
+            let degrees: Vec<String> = stats.degree_distribution.iter()
+                .map(|(degree, count)| format!("{}:{}", degree, count))
+                .collect();
+            println!("  度分布 (度数:节点数): {}", degrees.join(" "));
+
+            println!("{}", "其他".cyan());
+            println!("  概念池: {}", stats.concept_pool_size);
+            println!("  磁盘占用: {:.2} MB", stats.disk_size_bytes as f64 / (1024.0 * 1024.0));
+            match (&stats.oldest_event_time, &stats.newest_event_time) {
+                (Some(oldest), Some(newest)) => println!("  事件时间: {} ~ {}", oldest, newest),
+                _ => println!("  事件时间: {}", "暂无事件".yellow()),
+            }
+        }
     }
 
     Ok(())
@@ -152,6 +291,18 @@ fn parse_messages(content: &str) -> anyhow::Result<Vec<Message>> {
     Ok(messages)
 }
 
+/// 解析节点类型参数
+fn parse_node_types(values: &[String]) -> anyhow::Result<Vec<NodeType>> {
+    values.iter()
+        .map(|v| match v.to_lowercase().as_str() {
+            "entity" => Ok(NodeType::Entity),
+            "event" => Ok(NodeType::Event),
+            "concept" => Ok(NodeType::Concept),
+            other => Err(anyhow::anyhow!("未知的节点类型: {}", other)),
+        })
+        .collect()
+}
+
 /// 运行交互式对话模式
 async fn run_interactive_chat(db_path: &str) -> anyhow::Result<()> {
     println!("{}", "记忆系统交互式对话模式".cyan().bold());
@@ -0,0 +1,80 @@
+//! 知识图谱批量删除基准
+//!
+//! 在 10 万节点的图上删除 1% / 10% 的节点，验证删除开销只与被删节点的度数相关
+//!
+//! 运行：`cargo bench -p memory-core --bench graph_removal`
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use memory_core::graph::{relation_types, KnowledgeGraph};
+use memory_core::Edge;
+use uuid::Uuid;
+
+const NODE_COUNT: usize = 100_000;
+const EDGES_PER_NODE: usize = 3;
+
+fn node_id(i: usize) -> Uuid {
+    Uuid::from_u128(i as u128)
+}
+
+/// 第 i 个节点的第 step 条出边
+fn edge_id(i: usize, step: usize) -> Uuid {
+    Uuid::from_u128((NODE_COUNT + i * EDGES_PER_NODE + step) as u128)
+}
+
+/// 构建每个节点连向若干其他节点的稀疏图（ID 固定，便于在每轮重建后定位删除目标）
+fn build_graph() -> KnowledgeGraph {
+    let mut graph = KnowledgeGraph::new();
+    for i in 0..NODE_COUNT {
+        graph.add_node(node_id(i));
+        for step in 0..EDGES_PER_NODE {
+            let target = node_id((i + (step + 1) * 7919) % NODE_COUNT);
+            let mut edge = Edge::new(node_id(i), target, relation_types::RELATES_TO.to_string());
+            edge.id = edge_id(i, step);
+            graph.add_edge(edge);
+        }
+    }
+    graph
+}
+
+fn bench_remove_nodes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove_nodes_100k");
+    group.sample_size(10);
+
+    for percent in [1usize, 10] {
+        let victims: Vec<Uuid> = (0..NODE_COUNT).step_by(100 / percent).map(node_id).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{}%", percent)), &victims, |b, victims| {
+            b.iter_batched(
+                build_graph,
+                |mut graph| {
+                    graph.remove_nodes(victims);
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_remove_edges(c: &mut Criterion) {
+    let victims: Vec<Uuid> = (0..NODE_COUNT).step_by(10).map(|i| edge_id(i, 0)).collect();
+
+    let mut group = c.benchmark_group("remove_edges_100k");
+    group.sample_size(10);
+    group.bench_function("10%_of_nodes", |b| {
+        b.iter_batched(
+            build_graph,
+            |mut graph| {
+                for id in &victims {
+                    graph.remove_edge(id);
+                }
+                graph
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_remove_nodes, bench_remove_edges);
+criterion_main!(benches);
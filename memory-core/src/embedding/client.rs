@@ -5,6 +5,7 @@
 
 use crate::error::{MemoryError, Result};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// 服务端配置
@@ -103,7 +104,7 @@ struct EmbeddingUsage {
 /// 负责将文本转换为向量表示，通过服务端 API 转发
 pub struct EmbeddingClient {
     client: reqwest::Client,
-    server_config: tokio::sync::RwLock<ServerConfig>,
+    server_config: Arc<tokio::sync::RwLock<ServerConfig>>,
     embedding_config: EmbeddingConfig,
 }
 
@@ -125,7 +126,7 @@ impl EmbeddingClient {
 
         Ok(Self {
             client,
-            server_config: tokio::sync::RwLock::new(server_config),
+            server_config: Arc::new(tokio::sync::RwLock::new(server_config)),
             embedding_config,
         })
     }
@@ -309,6 +310,11 @@ impl EmbeddingClient {
         Ok(embeddings)
     }
 
+    /// 共享的服务端配置（对话模型等其他服务端调用复用同一份 URL 与 Token）
+    pub fn shared_server_config(&self) -> Arc<tokio::sync::RwLock<ServerConfig>> {
+        self.server_config.clone()
+    }
+
     /// 获取当前配置（克隆返回）
     pub async fn get_config(&self) -> (ServerConfig, EmbeddingConfig) {
         let server_config = self.server_config.read().await.clone();
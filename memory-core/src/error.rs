@@ -17,6 +17,10 @@ pub enum MemoryError {
     #[error("Retrieval error: {0}")]
     Retrieval(String),
 
+    /// 信息抽取错误
+    #[error("Extraction error: {0}")]
+    Extraction(String),
+
     /// 图操作错误
     #[error("Graph error: {0}")]
     Graph(String),
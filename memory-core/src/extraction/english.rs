@@ -0,0 +1,265 @@
+//! 语言识别与英文实体抽取
+//!
+//! 每条消息先按是否含有汉字与拉丁字母判断语言：中文走分词与词性标注，
+//! 英文走本模块的规则（大写开头的名词短语、物主代词后的名词、人物/地点/时间词表），
+//! 中英混合的消息两条路径都走
+
+use super::segment::is_cjk;
+use crate::graph;
+use crate::types::EntityType;
+use std::collections::HashSet;
+
+/// 名词短语最多包含的单词数
+const MAX_PHRASE_WORDS: usize = 4;
+
+/// 人称代词（不作为实体）
+const PRONOUNS: &[&str] = &[
+    "i", "me", "you", "he", "him", "she", "it", "we", "us", "they", "them", "myself",
+    "yourself", "himself", "herself", "itself", "ourselves", "themselves", "mine", "yours",
+    "hers", "ours", "theirs", "its", "i'm", "i've", "i'll", "i'd", "you're", "he's", "she's",
+    "it's", "we're", "they're",
+];
+
+/// 物主限定词（其后的名词作为物品）
+const POSSESSIVES: &[&str] = &["my", "our", "your", "his", "her", "their"];
+
+/// 句首常见的非实体大写词及物主限定词后常见的修饰语
+const STOPWORDS: &[&str] = &[
+    "the", "a", "an", "this", "that", "these", "those", "there", "here", "then", "so", "and",
+    "but", "or", "also", "just", "yes", "no", "ok", "okay", "please", "thanks", "thank", "hi",
+    "hello", "hey", "what", "when", "where", "why", "how", "who", "which", "let's", "can",
+    "could", "would", "will", "should", "did", "do", "does", "is", "are", "was", "were", "am",
+    "have", "has", "had", "got", "went", "not", "maybe", "after", "before", "last", "next",
+    "every", "some", "all", "new", "old", "own", "best", "favorite", "favourite", "little",
+    "big", "first", "second", "other", "dear",
+];
+
+/// 其后的大写短语视为地点的介词
+const PLACE_PREPOSITIONS: &[&str] = &["in", "at", "to", "from", "near", "into", "visit", "visited", "visiting"];
+
+/// 混合文本中其后的英文短语视为地点的汉字
+const PLACE_VERBS_ZH: &str = "去到在从回";
+
+/// 消息的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// 中文
+    Chinese,
+    /// 英文
+    English,
+    /// 中英混合
+    Mixed,
+}
+
+/// 按汉字与拉丁字母判断文本语言（两者都没有时视为中文）
+pub fn detect_language(text: &str) -> Language {
+    let cjk = text.chars().filter(|c| is_cjk(*c)).count();
+    let latin = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    match (cjk, latin) {
+        (0, 0) => Language::Chinese,
+        (0, _) => Language::English,
+        (_, 0) => Language::Chinese,
+        _ => Language::Mixed,
+    }
+}
+
+/// 从英文文本中抽取实体
+///
+/// - 连续的大写单词组成名词短语：能按词表推断类型的按词表，前面是 in/at/to 等介词的视为地点，
+///   其余视为人名；句首单词的大写不说明词性（"Sorry""Honestly"），
+///   只有在文中其他句子的句中也大写出现时才作为短语开头，否则按小写词处理
+/// - 小写的人物/地点/时间词按词表类型抽取
+/// - 物主限定词（my/our...）后跳过修饰语的第一个名词视为物品
+///
+/// # Returns
+/// 按出现顺序去重（不区分大小写）后的 (实体名, 实体类型)
+pub fn extract_english_entities(text: &str) -> Vec<(String, EntityType)> {
+    let mut entities: Vec<(String, EntityType)> = Vec::new();
+    let mut push = |name: String, entity_type: EntityType| {
+        if name.chars().count() >= 2 && !entities.iter().any(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+            entities.push((name, entity_type));
+        }
+    };
+
+    let sentences = sentences(text);
+    let mid_sentence_capitals: HashSet<&str> = sentences.iter()
+        .flat_map(|sentence| sentence.iter().skip(1))
+        .map(|raw| strip_possessive(raw))
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_uppercase()))
+        .collect();
+
+    for sentence in sentences {
+        let mut phrase: Vec<&str> = Vec::new();
+        let mut phrase_after_preposition = false;
+        let mut previous: Option<String> = None;
+        let mut after_possessive = false;
+
+        let flush = |phrase: &mut Vec<&str>, after_preposition: bool, push: &mut dyn FnMut(String, EntityType)| {
+            if phrase.is_empty() {
+                return;
+            }
+            let name = phrase.join(" ");
+            let entity_type = match graph::infer_entity_type(&name) {
+                EntityType::Other if after_preposition => EntityType::Place,
+                EntityType::Other => EntityType::Person,
+                known => known,
+            };
+            push(name, entity_type);
+            phrase.clear();
+        };
+
+        for (index, raw) in sentence.iter().enumerate() {
+            if raw.starts_with(is_cjk) {
+                // 混合文本中的汉字片段：打断短语，"去了 Starbucks" 中的"去"视同介词
+                flush(&mut phrase, phrase_after_preposition, &mut push);
+                after_possessive = false;
+                previous = Some(if ends_with_place_verb(raw) { "at" } else { "" }.to_string());
+                continue;
+            }
+
+            let word = strip_possessive(raw);
+            let lower = word.to_ascii_lowercase();
+
+            if PRONOUNS.contains(&lower.as_str()) || POSSESSIVES.contains(&lower.as_str()) {
+                flush(&mut phrase, phrase_after_preposition, &mut push);
+                after_possessive = POSSESSIVES.contains(&lower.as_str());
+                previous = Some(lower);
+                continue;
+            }
+
+            let capitalized = word.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+                && !(index == 0
+                    && (STOPWORDS.contains(&lower.as_str()) || !mid_sentence_capitals.contains(word)));
+            if capitalized && phrase.len() < MAX_PHRASE_WORDS {
+                if phrase.is_empty() {
+                    phrase_after_preposition = previous
+                        .as_deref()
+                        .is_some_and(|p| PLACE_PREPOSITIONS.contains(&p));
+                }
+                phrase.push(word);
+                after_possessive = false;
+            } else {
+                flush(&mut phrase, phrase_after_preposition, &mut push);
+                let lexicon_type = graph::infer_entity_type(&lower);
+                if lexicon_type != EntityType::Other {
+                    push(word.to_string(), lexicon_type);
+                    after_possessive = false;
+                } else if after_possessive && !STOPWORDS.contains(&lower.as_str()) {
+                    if word.chars().all(|c| c.is_ascii_alphabetic()) {
+                        push(word.to_string(), EntityType::Object);
+                    }
+                    after_possessive = false;
+                }
+            }
+            previous = Some(lower);
+        }
+        flush(&mut phrase, phrase_after_preposition, &mut push);
+    }
+
+    entities
+}
+
+/// 按句末标点切分句子，每句切为英文单词与连续汉字片段
+fn sentences(text: &str) -> Vec<Vec<&str>> {
+    text.split(['.', '!', '?', ';', ':', '\n', '。', '！', '？', '；', '：'])
+        .map(words)
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+/// 切分英文单词与连续汉字片段，其余字符作为分隔
+fn words(sentence: &str) -> Vec<&str> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Cjk,
+        Separator,
+    }
+    let class = |c: char| {
+        if c.is_ascii_alphanumeric() || c == '\'' || c == '-' {
+            Class::Word
+        } else if is_cjk(c) {
+            Class::Cjk
+        } else {
+            Class::Separator
+        }
+    };
+
+    let mut result = Vec::new();
+    let mut start: Option<(usize, Class)> = None;
+    for (i, c) in sentence.char_indices().chain(std::iter::once((sentence.len(), ' '))) {
+        let current = class(c);
+        if start.as_ref().is_some_and(|(_, class)| *class == current) {
+            continue;
+        }
+        if let Some((begin, _)) = start.take() {
+            let word = sentence[begin..i].trim_matches(['\'', '-']);
+            if !word.is_empty() {
+                result.push(word);
+            }
+        }
+        if current != Class::Separator {
+            start = Some((i, current));
+        }
+    }
+    result
+}
+
+/// 汉字片段是否以"去、到、在"等引出地点的字结尾（忽略"了"）
+fn ends_with_place_verb(chunk: &str) -> bool {
+    chunk
+        .trim_end_matches('了')
+        .chars()
+        .last()
+        .is_some_and(|c| PLACE_VERBS_ZH.contains(c))
+}
+
+/// 去掉所有格后缀（"Alice's" → "Alice"）
+fn strip_possessive(word: &str) -> &str {
+    word.strip_suffix("'s").unwrap_or(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("我和妈妈去了医院"), Language::Chinese);
+        assert_eq!(detect_language("I went to the hospital with my mom"), Language::English);
+        assert_eq!(detect_language("今天和 Alice 去了 Starbucks"), Language::Mixed);
+        assert_eq!(detect_language("2024"), Language::Chinese);
+    }
+
+    #[test]
+    fn test_extract_english_entities() {
+        let entities = extract_english_entities(
+            "Yesterday I went to Central Park with my mom and Alice. We met Dr Smith in Seattle! \
+             He lost his new laptop at Blue Bottle.",
+        );
+        assert_eq!(entities, vec![
+            ("Yesterday".to_string(), EntityType::Time),
+            ("Central Park".to_string(), EntityType::Place),
+            ("mom".to_string(), EntityType::Person),
+            ("Alice".to_string(), EntityType::Person),
+            ("Dr Smith".to_string(), EntityType::Person),
+            ("Seattle".to_string(), EntityType::Place),
+            ("laptop".to_string(), EntityType::Object),
+            ("Blue Bottle".to_string(), EntityType::Place),
+        ]);
+    }
+
+    #[test]
+    fn test_sentence_initial_capitals_and_months() {
+        let entities = extract_english_entities(
+            "Honestly, I forgot. Sorry about that. May I call Alice tomorrow? \
+             Alice moved to Boston in March.",
+        );
+        assert_eq!(entities, vec![
+            ("Alice".to_string(), EntityType::Person),
+            ("tomorrow".to_string(), EntityType::Time),
+            ("Boston".to_string(), EntityType::Place),
+            ("March".to_string(), EntityType::Time),
+        ]);
+    }
+}
@@ -0,0 +1,364 @@
+//! 消息分组
+//!
+//! 把一段对话切分为若干组，每组生成一个事件：用户消息开启新的轮次，助手消息并入当前事件
+//! 或在没有进行中的事件时单独发起事件，系统消息、工具结果等作为上下文并入相邻的事件。
+//! 每个事件最多合并的用户轮次与相邻消息的最大间隔可配置，实体从哪些处理方式的消息中抽取也可配置
+
+use crate::types::Message;
+use std::collections::HashMap;
+
+/// 用户角色（总是开启新的轮次）
+pub const USER_ROLE: &str = "user";
+
+/// 消息角色的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleHandling {
+    /// 忽略
+    Skip,
+    /// 作为上下文并入当前事件；没有进行中的事件时并入下一个事件，对话末尾的上下文单独成为事件
+    Context,
+    /// 并入当前事件；没有进行中的事件时单独发起一个事件
+    Event,
+}
+
+impl RoleHandling {
+    /// 配置中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoleHandling::Skip => "skip",
+            RoleHandling::Context => "context",
+            RoleHandling::Event => "event",
+        }
+    }
+
+    /// 从名称解析（不区分大小写）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "skip" => Some(RoleHandling::Skip),
+            "context" => Some(RoleHandling::Context),
+            "event" => Some(RoleHandling::Event),
+            _ => None,
+        }
+    }
+}
+
+/// 消息分组配置
+#[derive(Debug, Clone)]
+pub struct MessageGrouping {
+    /// 各角色的处理方式（`user` 固定开启新轮次，不受此表影响）
+    pub roles: HashMap<String, RoleHandling>,
+    /// 未列出角色的处理方式
+    pub other: RoleHandling,
+    /// 每个事件最多合并的用户轮次
+    pub max_user_turns: usize,
+    /// 相邻消息时间间隔超过该秒数时开始新的事件（None 表示不按时间切分）
+    pub max_gap_secs: Option<i64>,
+    /// 除用户消息外，按这些方式处理的消息也参与实体抽取
+    pub entity_sources: Vec<RoleHandling>,
+}
+
+impl Default for MessageGrouping {
+    fn default() -> Self {
+        let roles = [
+            ("assistant", RoleHandling::Event),
+            ("system", RoleHandling::Context),
+            ("tool", RoleHandling::Context),
+            ("function", RoleHandling::Context),
+        ]
+        .into_iter()
+        .map(|(role, handling)| (role.to_string(), handling))
+        .collect();
+
+        Self {
+            roles,
+            other: RoleHandling::Context,
+            max_user_turns: 1,
+            max_gap_secs: Some(3600),
+            entity_sources: vec![RoleHandling::Event, RoleHandling::Context],
+        }
+    }
+}
+
+impl MessageGrouping {
+    /// 设置角色的处理方式
+    pub fn role(mut self, role: impl Into<String>, handling: RoleHandling) -> Self {
+        self.roles.insert(role.into(), handling);
+        self
+    }
+
+    /// 设置每个事件最多合并的用户轮次（至少为 1）
+    pub fn max_user_turns(mut self, turns: usize) -> Self {
+        self.max_user_turns = turns.max(1);
+        self
+    }
+
+    /// 设置相邻消息的最大时间间隔（秒）
+    pub fn max_gap_secs(mut self, secs: Option<i64>) -> Self {
+        self.max_gap_secs = secs;
+        self
+    }
+
+    /// 设置除用户消息外参与实体抽取的消息处理方式（例如只取 `Event` 时忽略系统提示与工具结果中的实体）
+    pub fn entity_sources(mut self, sources: Vec<RoleHandling>) -> Self {
+        self.entity_sources = sources;
+        self
+    }
+
+    /// 角色的处理方式
+    pub fn handling_of(&self, role: &str) -> RoleHandling {
+        if role == USER_ROLE {
+            return RoleHandling::Event;
+        }
+        self.roles.get(role).copied().unwrap_or(self.other)
+    }
+
+    /// 按配置把消息切分为事件分组（保持消息顺序）
+    pub fn group<'a>(&self, messages: &'a [Message]) -> Vec<MessageGroup<'a>> {
+        let mut groups = Vec::new();
+        let mut current: Option<MessageGroup<'a>> = None;
+        // 等待并入下一个事件的上下文消息
+        let mut pending: Vec<&'a Message> = Vec::new();
+
+        for msg in messages {
+            let handling = self.handling_of(&msg.role);
+            if handling == RoleHandling::Skip {
+                continue;
+            }
+
+            // 时间间隔过长或用户轮次已满时结束当前事件
+            if let Some(group) = &current {
+                let gap_exceeded = match (self.max_gap_secs, group.last_timestamp(), msg.timestamp) {
+                    (Some(max_gap), Some(last), Some(ts)) => ts - last > max_gap,
+                    _ => false,
+                };
+                let turns_full = msg.role == USER_ROLE && group.user_turns >= self.max_user_turns;
+                if gap_exceeded || turns_full {
+                    groups.extend(current.take());
+                }
+            }
+
+            match (&mut current, handling) {
+                (Some(group), _) => group.push(msg, handling),
+                (None, RoleHandling::Context) => pending.push(msg),
+                (None, _) => {
+                    let mut group = MessageGroup::new();
+                    for context in pending.drain(..) {
+                        group.push(context, RoleHandling::Context);
+                    }
+                    group.push(msg, handling);
+                    current = Some(group);
+                }
+            }
+        }
+
+        groups.extend(current);
+        if !pending.is_empty() {
+            let mut group = MessageGroup::new();
+            for context in pending {
+                group.push(context, RoleHandling::Context);
+            }
+            groups.push(group);
+        }
+        groups
+    }
+}
+
+/// 一个事件对应的消息分组
+///
+/// 只由 `MessageGrouping::group` 创建，至少包含一条消息
+#[derive(Debug, Clone)]
+pub struct MessageGroup<'a> {
+    messages: Vec<&'a Message>,
+    /// 各消息的处理方式（与 messages 一一对应）
+    handlings: Vec<RoleHandling>,
+    /// 发起事件的消息下标（只有上下文消息时为 None）
+    initiator: Option<usize>,
+    user_turns: usize,
+}
+
+impl<'a> MessageGroup<'a> {
+    fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            handlings: Vec::new(),
+            initiator: None,
+            user_turns: 0,
+        }
+    }
+
+    fn push(&mut self, msg: &'a Message, handling: RoleHandling) {
+        if handling == RoleHandling::Event && self.initiator.is_none() {
+            self.initiator = Some(self.messages.len());
+        }
+        if msg.role == USER_ROLE {
+            self.user_turns += 1;
+        }
+        self.messages.push(msg);
+        self.handlings.push(handling);
+    }
+
+    /// 组内消息（按对话顺序）
+    pub fn messages(&self) -> &[&'a Message] {
+        &self.messages
+    }
+
+    /// 用户轮次数
+    pub fn user_turns(&self) -> usize {
+        self.user_turns
+    }
+
+    /// 发起事件的消息（只有上下文消息时取第一条）
+    pub fn initiator(&self) -> &'a Message {
+        self.messages[self.initiator.unwrap_or(0)]
+    }
+
+    /// 组内出现的角色（发起事件的角色在前，其余按出现顺序）
+    pub fn roles(&self) -> Vec<String> {
+        let mut roles = vec![self.initiator().role.clone()];
+        for msg in &self.messages {
+            if !roles.contains(&msg.role) {
+                roles.push(msg.role.clone());
+            }
+        }
+        roles
+    }
+
+    /// 用于抽取实体、打分与确定事件时间的消息：有用户消息时取用户消息，否则取全部消息
+    pub fn primary_messages(&self) -> Vec<&'a Message> {
+        if self.user_turns > 0 {
+            self.messages.iter().copied().filter(|m| m.role == USER_ROLE).collect()
+        } else {
+            self.messages.clone()
+        }
+    }
+
+    /// 参与实体抽取的消息：用户消息，以及处理方式在 `sources` 中的其他消息
+    pub fn entity_messages(&self, sources: &[RoleHandling]) -> Vec<&'a Message> {
+        self.messages.iter()
+            .zip(&self.handlings)
+            .filter(|(msg, handling)| msg.role == USER_ROLE || sources.contains(handling))
+            .map(|(msg, _)| *msg)
+            .collect()
+    }
+
+    /// 事件内容：每条消息一行并标注角色（用户之后的助手消息标为回复）
+    pub fn content(&self) -> String {
+        let mut seen_user = false;
+        self.messages
+            .iter()
+            .map(|msg| {
+                let label = match msg.role.as_str() {
+                    USER_ROLE => {
+                        seen_user = true;
+                        "用户说".to_string()
+                    }
+                    "assistant" if seen_user => "回复".to_string(),
+                    "assistant" => "助手说".to_string(),
+                    "system" => "系统提示".to_string(),
+                    "tool" | "function" => "工具结果".to_string(),
+                    other => other.to_string(),
+                };
+                format!("{}：{}", label, msg.content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 最后一条带时间戳的消息时间
+    fn last_timestamp(&self) -> Option<i64> {
+        self.messages.iter().rev().find_map(|m| m.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str, timestamp: Option<i64>) -> Message {
+        Message {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp,
+        }
+    }
+
+    fn contents(groups: &[MessageGroup]) -> Vec<String> {
+        groups.iter().map(|g| g.content()).collect()
+    }
+
+    #[test]
+    fn test_default_role_handling() {
+        let messages = vec![
+            message("system", "用户偏好简短回答", None),
+            message("user", "明天提醒我交房租", None),
+            message("assistant", "好的，明早九点提醒你", None),
+            message("tool", "reminder_id=42", None),
+            message("user", "谢谢", None),
+            message("assistant", "另外推荐你试试自动转账", None),
+            message("narrator", "对话结束", None),
+        ];
+        let groups = MessageGrouping::default().group(&messages);
+
+        assert_eq!(contents(&groups), vec![
+            "系统提示：用户偏好简短回答\n用户说：明天提醒我交房租\n回复：好的，明早九点提醒你\n工具结果：reminder_id=42",
+            "用户说：谢谢\n回复：另外推荐你试试自动转账\nnarrator：对话结束",
+        ]);
+        assert_eq!(groups[0].roles(), vec!["user", "system", "assistant", "tool"]);
+        assert_eq!(groups[0].initiator().content, "明天提醒我交房租");
+
+        // 没有用户消息的助手消息、隔了很久的系统消息各自成为事件
+        let messages = vec![
+            message("assistant", "记得周五带伞", Some(0)),
+            message("system", "今天是用户生日", Some(10_000)),
+        ];
+        let groups = MessageGrouping::default().group(&messages);
+        assert_eq!(contents(&groups), vec!["助手说：记得周五带伞", "系统提示：今天是用户生日"]);
+        assert_eq!(groups[0].primary_messages().len(), 1);
+        assert_eq!(groups[1].roles(), vec!["system"]);
+    }
+
+    #[test]
+    fn test_multi_turn_window_and_gap() {
+        let messages = vec![
+            message("user", "我想学游泳", Some(0)),
+            message("assistant", "可以先报个班", Some(10)),
+            message("user", "周末有课吗", Some(20)),
+            message("user", "算了下次再说", Some(30)),
+            message("user", "换个话题", Some(10_000)),
+        ];
+        let grouping = MessageGrouping::default().max_user_turns(3).role("assistant", RoleHandling::Skip);
+        let groups = grouping.group(&messages);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].user_turns(), 3);
+        assert_eq!(groups[0].roles(), vec!["user"]);
+        assert_eq!(groups[1].content(), "用户说：换个话题");
+
+        let groups = grouping.max_gap_secs(None).group(&messages);
+        assert_eq!(groups.iter().map(|g| g.user_turns()).collect::<Vec<_>>(), vec![3, 1]);
+    }
+
+    #[test]
+    fn test_entity_messages_follow_sources() {
+        let messages = vec![
+            message("system", "用户住在杭州", None),
+            message("user", "推荐个周末去处", None),
+            message("assistant", "可以去西湖走走", None),
+        ];
+        let groups = MessageGrouping::default().group(&messages);
+        assert_eq!(groups.len(), 1);
+
+        let contents = |sources: &[RoleHandling]| -> Vec<&str> {
+            groups[0].entity_messages(sources).iter().map(|m| m.content.as_str()).collect()
+        };
+        assert_eq!(contents(&MessageGrouping::default().entity_sources), vec![
+            "用户住在杭州", "推荐个周末去处", "可以去西湖走走",
+        ]);
+        assert_eq!(contents(&[RoleHandling::Event]), vec!["推荐个周末去处", "可以去西湖走走"]);
+        assert_eq!(contents(&[]), vec!["推荐个周末去处"]);
+
+        assert_eq!(RoleHandling::parse("Context"), Some(RoleHandling::Context));
+        assert_eq!(RoleHandling::parse(RoleHandling::Skip.as_str()), Some(RoleHandling::Skip));
+        assert_eq!(RoleHandling::parse("ignore"), None);
+    }
+}
@@ -0,0 +1,604 @@
+//! 事件重要性评分
+//!
+//! 事件的 `importance` 影响检索排序与遗忘，由 `ImportanceScorer` 给出：
+//! 默认的 `RuleBasedScorer` 按情绪词、第一人称事实、偏好、计划、命名实体、
+//! 显式的"记住"请求与消息长度加权打分，权重可通过 `ImportanceWeights` 调整；
+//! `LlmScorer` 让对话模型为一批消息打分，失败时退回规则评分
+
+use super::llm::TalkClient;
+use super::segment::is_cjk;
+use super::{extract_english_entities, BoxFuture, PosTag, Segmenter};
+use crate::embedding::ServerConfig;
+use crate::error::{MemoryError, Result};
+use crate::types::EntityType;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 评分下限（再无信息量的消息也保留被检索到的可能）
+pub const MIN_IMPORTANCE: f32 = 0.05;
+
+/// 长度信号饱和的字数
+const SATURATED_CHARS: usize = 50;
+
+/// 命名实体信号饱和的个数
+const SATURATED_ENTITIES: usize = 3;
+
+/// 情绪词
+const EMOTION_WORDS_ZH: &[&str] = &[
+    "开心", "高兴", "快乐", "幸福", "激动", "兴奋", "感动", "骄傲", "满足", "难过", "伤心",
+    "痛苦", "难受", "失望", "沮丧", "委屈", "哭", "害怕", "担心", "焦虑", "紧张", "压力",
+    "崩溃", "生气", "愤怒", "烦", "孤独", "想念", "思念", "后悔", "愧疚", "心疼",
+];
+/// 含有情绪字但并不表达情绪的词（"麻烦你"、"哭笑不得"），匹配情绪词前先去掉
+const NON_EMOTION_PHRASES_ZH: &[&str] = &["麻烦", "哭笑不得"];
+const EMOTION_WORDS_EN: &[&str] = &[
+    "happy", "glad", "excited", "thrilled", "proud", "grateful", "sad", "upset", "cried",
+    "crying", "depressed", "angry", "mad", "furious", "worried", "anxious", "nervous",
+    "scared", "afraid", "stressed", "lonely", "miss", "heartbroken", "regret", "frustrated",
+];
+
+/// 第一人称事实（身份、家庭、工作、住址、健康等）
+const FIRST_PERSON_FACTS_ZH: &[&str] = &[
+    "我是", "我叫", "我的名字", "我的生日", "我今年", "我住", "我家", "我们家", "我有", "我在",
+    "我毕业", "我工作", "我老家", "我妈", "我爸", "我老婆", "我老公", "我太太", "我先生",
+    "我儿子", "我女儿", "我哥", "我姐", "我弟", "我妹", "过敏", "确诊", "怀孕",
+];
+const FIRST_PERSON_FACTS_EN: &[&str] = &[
+    "i am", "i'm", "my name", "i live", "i work", "i have", "i've got", "my wife",
+    "my husband", "my son", "my daughter", "my mom", "my dad", "my birthday", "i was born",
+];
+
+/// 偏好
+const PREFERENCE_WORDS_ZH: &[&str] = &[
+    "喜欢", "爱吃", "爱喝", "最爱", "讨厌", "不爱", "受不了", "偏好", "习惯", "过敏", "不吃",
+    "忌口", "口味",
+];
+const PREFERENCE_WORDS_EN: &[&str] = &[
+    "i like", "we like", "likes", "love", "loves", "prefer", "prefers", "favorite", "favourite", "hate",
+    "hates", "allergic", "can't stand", "enjoy", "enjoys",
+];
+
+/// 计划与约定
+const PLAN_WORDS_ZH: &[&str] = &[
+    "打算", "计划", "准备", "将要", "要去", "想去", "约了", "预约", "安排", "目标", "下周",
+    "下个月", "明年", "明天", "后天",
+];
+const PLAN_WORDS_EN: &[&str] = &[
+    "plan", "plans", "planning", "going to", "gonna", "tomorrow", "next week",
+    "next month", "next year", "schedule", "scheduled", "appointment", "intend",
+];
+
+/// 显式要求记住
+const REMEMBER_WORDS_ZH: &[&str] = &[
+    "记住", "记得", "别忘", "不要忘", "记一下", "帮我记", "提醒我", "请牢记",
+];
+const REMEMBER_WORDS_EN: &[&str] = &[
+    "remember", "don't forget", "do not forget", "remind me", "keep in mind", "note that",
+    "make a note",
+];
+
+/// 否定词（"不记得"、"没记住" 不是要求记住）
+const NEGATIONS_ZH: &str = "不没";
+const NEGATIONS_EN: &[&str] = &["not", "don't", "dont", "didn't", "doesn't", "never", "can't", "cannot", "won't"];
+
+/// 句末疑问语气词（"还记得吗" 是提问而不是要求记住）
+const QUESTION_PARTICLES_ZH: &str = "吗么";
+
+/// 寒暄与应答（整条消息只由这些词构成时降低重要性）
+const SMALL_TALK_WORDS: &[&str] = &[
+    "好", "好的", "嗯", "嗯嗯", "哦", "哈", "哈哈", "哈哈哈", "谢谢", "收到", "可以", "行",
+    "对", "是的", "你好", "早", "早上好", "晚安", "再见", "ok", "okay", "yes", "no", "thanks",
+    "thank", "you", "hi", "hello", "hey", "lol", "sure", "bye", "good", "night", "morning",
+    "cool", "nice",
+];
+
+/// 重要性评分器
+///
+/// 输入为一批待评分的文本（通常是事件内容），输出等长、取值 0~1 的分数
+pub trait ImportanceScorer: Send + Sync {
+    /// 评分器名称（用于日志）
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// 为每条文本打分
+    fn score<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<f32>>>;
+}
+
+/// 规则评分的各项权重
+///
+/// 分数 = 基础分 + Σ 权重 × 信号强度（0~1） − 寒暄惩罚，截断到 0.05~1
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportanceWeights {
+    /// 基础分
+    pub base: f32,
+    /// 消息长度（50 字饱和）
+    pub length: f32,
+    /// 情绪词（2 个饱和）
+    pub emotion: f32,
+    /// 第一人称事实
+    pub first_person_fact: f32,
+    /// 偏好
+    pub preference: f32,
+    /// 计划与约定
+    pub plan: f32,
+    /// 人名、地名、机构名（3 个饱和）
+    pub named_entity: f32,
+    /// 显式要求记住
+    pub remember_request: f32,
+    /// 寒暄与应答的惩罚
+    pub small_talk_penalty: f32,
+}
+
+impl Default for ImportanceWeights {
+    fn default() -> Self {
+        Self {
+            base: 0.3,
+            length: 0.1,
+            emotion: 0.25,
+            first_person_fact: 0.15,
+            preference: 0.2,
+            plan: 0.2,
+            named_entity: 0.15,
+            remember_request: 0.4,
+            small_talk_penalty: 0.2,
+        }
+    }
+}
+
+/// 从一条文本中识别出的重要性信号
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportanceSignals {
+    /// 字数
+    pub chars: usize,
+    /// 情绪词个数
+    pub emotion: usize,
+    /// 是否陈述第一人称事实
+    pub first_person_fact: bool,
+    /// 是否表达偏好
+    pub preference: bool,
+    /// 是否提到计划或约定
+    pub plan: bool,
+    /// 人名、地名、机构名个数
+    pub named_entities: usize,
+    /// 是否显式要求记住
+    pub remember_request: bool,
+    /// 是否只是寒暄或应答
+    pub small_talk: bool,
+}
+
+/// 基于规则的重要性评分器（默认）
+#[derive(Debug, Clone)]
+pub struct RuleBasedScorer {
+    weights: ImportanceWeights,
+    segmenter: Arc<Segmenter>,
+}
+
+impl Default for RuleBasedScorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuleBasedScorer {
+    /// 使用默认权重与内置词典创建评分器
+    pub fn new() -> Self {
+        Self::with_weights(ImportanceWeights::default())
+    }
+
+    /// 使用指定权重创建评分器
+    pub fn with_weights(weights: ImportanceWeights) -> Self {
+        Self {
+            weights,
+            segmenter: Segmenter::shared(),
+        }
+    }
+
+    /// 使用指定分词器（例如加载了用户词典的分词器）
+    pub fn segmenter(mut self, segmenter: Arc<Segmenter>) -> Self {
+        self.segmenter = segmenter;
+        self
+    }
+
+    /// 当前权重
+    pub fn weights(&self) -> &ImportanceWeights {
+        &self.weights
+    }
+
+    /// 识别文本中的重要性信号
+    pub fn signals(&self, text: &str) -> ImportanceSignals {
+        let lower = text.to_lowercase();
+        let words = english_words(&lower);
+        let has_any = |zh: &[&str], en: &[&str]| {
+            zh.iter().any(|w| text.contains(w)) || en.iter().any(|w| contains_phrase(&words, w))
+        };
+
+        let emotion_text = NON_EMOTION_PHRASES_ZH.iter().fold(text.to_string(), |t, p| t.replace(p, " "));
+        let emotion = EMOTION_WORDS_ZH.iter().filter(|w| emotion_text.contains(*w)).count()
+            + EMOTION_WORDS_EN.iter().filter(|w| contains_phrase(&words, w)).count();
+
+        let mut named: Vec<String> = self.segmenter
+            .segment(text)
+            .into_iter()
+            .filter(|t| matches!(t.tag, PosTag::PersonName | PosTag::PlaceName | PosTag::Organization))
+            .map(|t| t.word)
+            .collect();
+        named.extend(
+            extract_english_entities(text)
+                .into_iter()
+                .filter(|(_, t)| matches!(t, EntityType::Person | EntityType::Place))
+                .map(|(name, _)| name),
+        );
+        named.sort();
+        named.dedup();
+
+        ImportanceSignals {
+            chars: text.chars().filter(|c| !c.is_whitespace()).count(),
+            emotion,
+            first_person_fact: has_any(FIRST_PERSON_FACTS_ZH, FIRST_PERSON_FACTS_EN),
+            preference: has_any(PREFERENCE_WORDS_ZH, PREFERENCE_WORDS_EN),
+            plan: has_any(PLAN_WORDS_ZH, PLAN_WORDS_EN),
+            named_entities: named.len(),
+            remember_request: is_remember_request(text, &lower),
+            small_talk: is_small_talk(&lower),
+        }
+    }
+
+    /// 按权重合成信号得分
+    pub fn score_signals(&self, signals: &ImportanceSignals) -> f32 {
+        let w = &self.weights;
+        let flag = |on: bool| if on { 1.0 } else { 0.0 };
+        let ratio = |n: usize, cap: usize| n.min(cap) as f32 / cap as f32;
+
+        let score = w.base
+            + w.length * ratio(signals.chars, SATURATED_CHARS)
+            + w.emotion * ratio(signals.emotion, 2)
+            + w.first_person_fact * flag(signals.first_person_fact)
+            + w.preference * flag(signals.preference)
+            + w.plan * flag(signals.plan)
+            + w.named_entity * ratio(signals.named_entities, SATURATED_ENTITIES)
+            + w.remember_request * flag(signals.remember_request)
+            - w.small_talk_penalty * flag(signals.small_talk);
+        score.clamp(MIN_IMPORTANCE, 1.0)
+    }
+
+    /// 为单条文本打分
+    pub fn score_text(&self, text: &str) -> f32 {
+        self.score_signals(&self.signals(text))
+    }
+}
+
+impl ImportanceScorer for RuleBasedScorer {
+    fn name(&self) -> &str {
+        "rule"
+    }
+
+    fn score<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<f32>>> {
+        let scores = texts.iter().map(|t| self.score_text(t)).collect();
+        Box::pin(futures::future::ready(Ok(scores)))
+    }
+}
+
+/// 把评分器给出的分数截断到 `MIN_IMPORTANCE`~1（NaN 返回 None，由调用方保留原重要性）
+pub fn clamp_importance(score: f32) -> Option<f32> {
+    (!score.is_nan()).then(|| score.clamp(MIN_IMPORTANCE, 1.0))
+}
+
+/// 评分提示词
+const SCORING_PROMPT: &str = "你是一个记忆助手。请为下面每条对话记录评估\"值得长期记住的程度\"，\
+取值 0 到 1：寒暄和应答接近 0；日常琐事约 0.3；个人信息、偏好、计划、强烈情绪约 0.7；\
+用户明确要求记住的内容接近 1。只输出一个与记录条数相同、按顺序排列的 JSON 数字数组，例如 [0.2, 0.8]，不要输出其他内容。";
+
+/// 大模型评分配置
+#[derive(Debug, Clone)]
+pub struct LlmScorerConfig {
+    /// 服务端对话模型名称（格式: provider-modelName）
+    pub model: String,
+    /// 采样温度
+    pub temperature: f32,
+    /// 请求超时时间（毫秒）
+    pub timeout_ms: u64,
+    /// 重试次数
+    pub max_retries: u32,
+    /// 重试间隔（毫秒）
+    pub retry_delay_ms: u64,
+    /// 调用或解析失败时是否退回规则评分
+    pub fallback_to_rules: bool,
+}
+
+impl Default for LlmScorerConfig {
+    fn default() -> Self {
+        Self {
+            model: "siliconflow-Qwen/Qwen2.5-7B-Instruct".to_string(),
+            temperature: 0.0,
+            timeout_ms: 30000,
+            max_retries: 1,
+            retry_delay_ms: 1000,
+            fallback_to_rules: true,
+        }
+    }
+}
+
+/// 基于大模型的重要性评分器
+pub struct LlmScorer {
+    talk: TalkClient,
+    config: LlmScorerConfig,
+    fallback: RuleBasedScorer,
+}
+
+impl LlmScorer {
+    /// 使用独立的服务端配置创建评分器
+    pub fn new(server_config: ServerConfig, config: LlmScorerConfig) -> Result<Self> {
+        Self::with_shared_config(Arc::new(RwLock::new(server_config)), config)
+    }
+
+    /// 与嵌入客户端共享服务端配置创建评分器（登录后设置的 Token 同时生效）
+    pub fn with_shared_config(
+        server_config: Arc<RwLock<ServerConfig>>,
+        config: LlmScorerConfig,
+    ) -> Result<Self> {
+        let talk = TalkClient::new(
+            server_config,
+            config.model.clone(),
+            config.temperature,
+            config.timeout_ms,
+            config.max_retries,
+            config.retry_delay_ms,
+        )?;
+
+        Ok(Self {
+            talk,
+            config,
+            fallback: RuleBasedScorer::new(),
+        })
+    }
+
+    /// 退回规则评分时使用的评分器
+    pub fn fallback(mut self, fallback: RuleBasedScorer) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// 只用大模型评分（不退回规则评分）
+    pub async fn score_with_llm(&self, texts: &[String]) -> Result<Vec<f32>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let records = texts.iter()
+            .enumerate()
+            .map(|(i, text)| format!("{}. {}", i + 1, text.replace('\n', " ")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let content = self.talk
+            .chat(SCORING_PROMPT, format!("共 {} 条记录：\n{}", texts.len(), records))
+            .await?;
+        parse_scores(&content, texts.len())
+    }
+}
+
+impl ImportanceScorer for LlmScorer {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    fn score<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<f32>>> {
+        Box::pin(async move {
+            match self.score_with_llm(texts).await {
+                Ok(scores) => Ok(scores),
+                Err(e) if self.config.fallback_to_rules => {
+                    log::warn!("大模型评分失败，退回规则评分: {}", e);
+                    Ok(texts.iter().map(|t| self.fallback.score_text(t)).collect())
+                }
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
+
+/// 解析模型输出的分数数组（允许前后有多余文字或代码块标记）
+fn parse_scores(content: &str, expected: usize) -> Result<Vec<f32>> {
+    let start = content.find('[');
+    let end = content.rfind(']');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(MemoryError::Extraction("模型输出中没有分数数组".to_string())),
+    };
+
+    let scores: Vec<f32> = serde_json::from_str(json)
+        .map_err(|e| MemoryError::Extraction(format!("解析分数失败: {}", e)))?;
+    if scores.len() != expected {
+        return Err(MemoryError::Extraction(
+            format!("分数个数 {} 与记录条数 {} 不一致", scores.len(), expected)
+        ));
+    }
+    if scores.iter().any(|s| !s.is_finite()) {
+        return Err(MemoryError::Extraction("分数不是有限数值".to_string()));
+    }
+
+    Ok(scores.into_iter().map(|s| s.clamp(MIN_IMPORTANCE, 1.0)).collect())
+}
+
+/// 小写文本中的英文单词（保留撇号）
+fn english_words(lower: &str) -> Vec<&str> {
+    lower
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// 单词序列中是否出现（可能由多个单词组成的）短语
+fn contains_phrase(words: &[&str], phrase: &str) -> bool {
+    phrase_positions(words, phrase).next().is_some()
+}
+
+/// 短语在单词序列中出现的位置
+fn phrase_positions<'a>(words: &'a [&str], phrase: &'a str) -> impl Iterator<Item = usize> + 'a {
+    let parts: Vec<&str> = phrase.split(' ').collect();
+    words.windows(parts.len())
+        .enumerate()
+        .filter(move |(_, window)| *window == parts.as_slice())
+        .map(|(i, _)| i)
+}
+
+/// 是否显式要求记住
+///
+/// 前面带否定词（"不记得"、"don't remember"）或所在句子是疑问句（"还记得吗"、
+/// "do you remember...?"）的出现不算
+fn is_remember_request(text: &str, lower: &str) -> bool {
+    let is_terminator = |c: char| matches!(c, '。' | '！' | '？' | '!' | '?' | '\n');
+
+    let chinese = REMEMBER_WORDS_ZH.iter().any(|word| {
+        text.match_indices(word).any(|(i, _)| {
+            let negated = text[..i].chars().next_back().is_some_and(|c| NEGATIONS_ZH.contains(c));
+            let rest = &text[i..];
+            let end = rest.find(is_terminator).unwrap_or(rest.len());
+            let question = rest[end..].starts_with(['？', '?'])
+                || rest[..end].trim_end().ends_with(|c: char| QUESTION_PARTICLES_ZH.contains(c));
+            !negated && !question
+        })
+    });
+    if chinese {
+        return true;
+    }
+
+    lower.split_inclusive(is_terminator)
+        .filter(|sentence| !sentence.trim_end().ends_with(['?', '？']))
+        .any(|sentence| {
+            let words = english_words(sentence);
+            REMEMBER_WORDS_EN.iter().any(|phrase| {
+                phrase_positions(&words, phrase)
+                    .any(|i| i == 0 || !NEGATIONS_EN.contains(&words[i - 1]))
+            })
+        })
+}
+
+/// 整条消息是否只由寒暄与应答词构成（空白与标点不计）
+fn is_small_talk(lower: &str) -> bool {
+    let is_separator = |c: char| !c.is_alphanumeric() && !is_cjk(c);
+    let mut rest = lower;
+    loop {
+        rest = rest.trim_start_matches(is_separator);
+        if rest.is_empty() {
+            return true;
+        }
+        // 按最长匹配逐个剥离寒暄词
+        match SMALL_TALK_WORDS.iter().filter(|w| rest.starts_with(*w)).max_by_key(|w| w.len()) {
+            Some(word) => rest = &rest[word.len()..],
+            None => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    enum Label {
+        Low,
+        Medium,
+        High,
+    }
+
+    /// 人工标注的样例消息
+    const SAMPLES: &[(&str, Label)] = &[
+        ("好的", Label::Low),
+        ("嗯嗯，谢谢", Label::Low),
+        ("哈哈哈", Label::Low),
+        ("ok thanks!", Label::Low),
+        ("今天天气一般", Label::Low),
+        ("刚吃完午饭", Label::Low),
+        ("你还记得吗？", Label::Low),
+        ("我不记得了", Label::Low),
+        ("I don't remember his name", Label::Low),
+        ("麻烦你帮我查一下", Label::Low),
+        ("我的天，太热了", Label::Low),
+        ("I'd like a coffee", Label::Low),
+        ("It will rain later", Label::Low),
+        ("我今天去超市买了点水果", Label::Medium),
+        ("I went to the gym after work", Label::Medium),
+        ("周末和张伟在北京吃了烤鸭", Label::Medium),
+        ("晚上和同事一起吃了火锅", Label::Medium),
+        ("好烦啊，又加班到十点", Label::Medium),
+        ("我女儿今年上小学了", Label::High),
+        ("我最近压力很大，工作上的事情让我特别焦虑", Label::High),
+        ("我对花生过敏，不能吃任何含花生的东西", Label::High),
+        ("记住，我妈妈的生日是五月十二号", Label::High),
+        ("下周三我约了王医生去协和医院复查", Label::High),
+        ("别忘了提醒我明天交房租", Label::High),
+        ("Please remember that I'm allergic to shellfish", Label::High),
+        ("I'm so excited, my daughter got into Stanford!", Label::High),
+    ];
+
+    #[test]
+    fn test_default_scores_follow_labels() {
+        let scorer = RuleBasedScorer::new();
+        let mut averages = [(0.0f32, 0usize); 3];
+        for (text, label) in SAMPLES {
+            let score = scorer.score_text(text);
+            match label {
+                Label::Low => assert!(score < 0.35, "{} 的分数 {} 应低于 0.35", text, score),
+                Label::Medium => assert!((0.35..0.55).contains(&score), "{} 的分数 {} 应在 0.35~0.55", text, score),
+                Label::High => assert!(score >= 0.55, "{} 的分数 {} 应不低于 0.55", text, score),
+            }
+            let slot = &mut averages[*label as usize];
+            slot.0 += score;
+            slot.1 += 1;
+        }
+        let mean = |i: usize| averages[i].0 / averages[i].1 as f32;
+        assert!(mean(0) < mean(1) && mean(1) < mean(2));
+    }
+
+    #[test]
+    fn test_signals_and_custom_weights() {
+        let scorer = RuleBasedScorer::new();
+        let signals = scorer.signals("记住，我喜欢喝美式，下周去上海出差");
+        assert!(signals.remember_request && signals.preference && signals.plan);
+        assert!(!signals.small_talk);
+        assert_eq!(signals.named_entities, 1);
+
+        let text = "我喜欢吃辣";
+        let preference_only = RuleBasedScorer::with_weights(ImportanceWeights {
+            base: 0.0,
+            length: 0.0,
+            first_person_fact: 0.0,
+            preference: 0.9,
+            ..ImportanceWeights::default()
+        });
+        assert!((preference_only.score_text(text) - 0.9).abs() < 1e-6);
+        assert!(preference_only.score_text("好的") <= MIN_IMPORTANCE);
+    }
+
+    #[test]
+    fn test_negation_questions_and_false_matches() {
+        let scorer = RuleBasedScorer::new();
+        let remember = |text: &str| scorer.signals(text).remember_request;
+        assert!(remember("记得明天带伞"));
+        assert!(remember("Remember to call mom."));
+        assert!(!remember("我不记得他叫什么"));
+        assert!(!remember("还记得我们第一次见面吗"));
+        assert!(!remember("I don't remember."));
+        assert!(!remember("Do you remember me?"));
+
+        assert_eq!(scorer.signals("麻烦你帮个忙").emotion, 0);
+        assert_eq!(scorer.signals("真是哭笑不得").emotion, 0);
+        assert_eq!(scorer.signals("烦死了").emotion, 1);
+        assert!(!scorer.signals("This looks like rain").preference);
+        assert!(scorer.signals("I like hiking").preference);
+        assert!(!scorer.signals("我的天").first_person_fact);
+    }
+
+    #[test]
+    fn test_parse_scores_validates_output() {
+        let scores = parse_scores("```json\n[0.2, 1.5, -1]\n```", 3).unwrap();
+        assert_eq!(scores, vec![0.2, 1.0, MIN_IMPORTANCE]);
+        assert!(parse_scores("[0.2, 0.8]", 3).is_err());
+        assert!(parse_scores("很重要", 1).is_err());
+        assert!(parse_scores("[\"high\"]", 1).is_err());
+
+        assert_eq!(clamp_importance(0.0), Some(MIN_IMPORTANCE));
+        assert_eq!(clamp_importance(f32::INFINITY), Some(1.0));
+        assert_eq!(clamp_importance(f32::NAN), None);
+    }
+}
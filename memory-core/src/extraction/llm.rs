@@ -0,0 +1,554 @@
+//! 基于大模型的抽取器
+//!
+//! 通过服务端的 OpenAI 兼容对话端点（`ServerConfig::talk_endpoint`）让模型按
+//! AutoSchemaKG 格式返回实体关系三元组与带时间的事件，解析校验后转换为节点与边；
+//! 调用或解析失败时可退回基于规则的抽取
+
+use super::{
+    resolve_event_time, BoxFuture, Extraction, Extractor, MessageGrouping, RoleHandling, RuleBasedExtractor,
+    USER_ROLE,
+};
+use crate::embedding::ServerConfig;
+use crate::error::{MemoryError, Result};
+use crate::types::*;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// 实体名称最大字符数，超出的三元组和实体被丢弃
+const MAX_ENTITY_CHARS: usize = 30;
+
+/// 抽取提示词
+const EXTRACTION_PROMPT: &str = "你是一个知识图谱构建助手。请从用户与助手的对话中抽取值得长期记住的信息，\
+只输出一个 JSON 对象，不要输出其他内容：
+{\"triples\": [{\"Head\": \"实体\", \"Relation\": \"关系\", \"Tail\": \"实体\"}],
+ \"events\": [{\"Event\": \"一句话描述的事件\", \"EventTime\": \"YYYY-MM-DD-HH-MM\", \"Entities\": [\"参与实体\"]}]}
+要求：
+1. 实体使用对话中的简短名称（如\"妈妈\"\"医院\"），关系使用简短的动词或短语；
+2. 事件以用户为视角描述，EventTime 根据当前时间换算\"昨天\"\"下周一\"等相对时间，无法确定时使用当前时间；
+3. 只抽取对话中明确出现的信息，没有可抽取的内容时返回空数组。";
+
+/// 大模型抽取配置
+#[derive(Debug, Clone)]
+pub struct LlmExtractorConfig {
+    /// 服务端对话模型名称（格式: provider-modelName）
+    pub model: String,
+    /// 采样温度
+    pub temperature: f32,
+    /// 请求超时时间（毫秒）
+    pub timeout_ms: u64,
+    /// 重试次数
+    pub max_retries: u32,
+    /// 重试间隔（毫秒）
+    pub retry_delay_ms: u64,
+    /// 单次最多保留的三元组数
+    pub max_triples: usize,
+    /// 单次最多保留的事件数
+    pub max_events: usize,
+    /// 调用或解析失败时是否退回基于规则的抽取
+    pub fallback_to_rules: bool,
+}
+
+impl Default for LlmExtractorConfig {
+    fn default() -> Self {
+        Self {
+            model: "siliconflow-Qwen/Qwen2.5-7B-Instruct".to_string(),
+            temperature: 0.1,
+            timeout_ms: 60000,
+            max_retries: 1,
+            retry_delay_ms: 1000,
+            max_triples: 50,
+            max_events: 20,
+            fallback_to_rules: true,
+        }
+    }
+}
+
+/// OpenAI 兼容的对话请求格式
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+/// OpenAI 兼容的对话响应格式
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// 模型输出
+#[derive(Debug, Deserialize)]
+struct LlmOutput {
+    #[serde(default)]
+    triples: Vec<RawTriple>,
+    #[serde(default)]
+    events: Vec<ExtractedEvent>,
+}
+
+/// 对话端点客户端（抽取与重要性评分共用）
+pub(super) struct TalkClient {
+    client: reqwest::Client,
+    server_config: Arc<RwLock<ServerConfig>>,
+    model: String,
+    temperature: f32,
+    max_retries: u32,
+    retry_delay_ms: u64,
+}
+
+impl TalkClient {
+    /// 创建客户端
+    pub(super) fn new(
+        server_config: Arc<RwLock<ServerConfig>>,
+        model: String,
+        temperature: f32,
+        timeout_ms: u64,
+        max_retries: u32,
+        retry_delay_ms: u64,
+    ) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(timeout_ms))
+            .build()
+            .map_err(|e| MemoryError::Extraction(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            server_config,
+            model,
+            temperature,
+            max_retries,
+            retry_delay_ms,
+        })
+    }
+
+    /// 调用对话端点（带重试），返回模型回复文本
+    pub(super) async fn chat(&self, system_prompt: &str, user_content: String) -> Result<String> {
+        let (auth_token, endpoint) = {
+            let config = self.server_config.read().await;
+            let auth_token = config.auth_token.clone()
+                .ok_or_else(|| MemoryError::Extraction("未登录账号系统，请先登录".to_string()))?;
+            (auth_token, format!("{}{}", config.server_url, config.talk_endpoint))
+        };
+
+        let request_body = ChatRequest {
+            model: self.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system",
+                    content: system_prompt.to_string(),
+                },
+                ChatMessage {
+                    role: "user",
+                    content: user_content,
+                },
+            ],
+            temperature: self.temperature,
+            stream: false,
+        };
+
+        let mut last_error = None;
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                log::debug!("对话请求重试 {}/{}", attempt, self.max_retries);
+                tokio::time::sleep(Duration::from_millis(self.retry_delay_ms)).await;
+            }
+
+            match self.send_request(&endpoint, &auth_token, &request_body).await {
+                Ok(content) => return Ok(content),
+                Err(e) => {
+                    log::warn!("对话 API 调用失败（第 {} 次）: {}", attempt + 1, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| MemoryError::Extraction("Unknown error".to_string())))
+    }
+
+    /// 发送单次请求，返回模型回复文本
+    async fn send_request(
+        &self,
+        endpoint: &str,
+        auth_token: &str,
+        request_body: &ChatRequest,
+    ) -> Result<String> {
+        let response = self.client
+            .post(endpoint)
+            .header("Authorization", format!("Bearer {}", auth_token))
+            .header("Content-Type", "application/json")
+            .json(request_body)
+            .send()
+            .await
+            .map_err(|e| MemoryError::Extraction(format!("请求失败: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(MemoryError::Extraction(
+                format!("对话 API 调用失败: {} - {}", status, error_text)
+            ));
+        }
+
+        let data: ChatResponse = response.json().await
+            .map_err(|e| MemoryError::Extraction(format!("解析响应失败: {}", e)))?;
+
+        data.choices.into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .filter(|content| !content.trim().is_empty())
+            .ok_or_else(|| MemoryError::Extraction("对话 API 返回空内容".to_string()))
+    }
+}
+
+/// 基于大模型的抽取器
+pub struct LlmExtractor {
+    talk: TalkClient,
+    config: LlmExtractorConfig,
+    grouping: MessageGrouping,
+    fallback: RuleBasedExtractor,
+}
+
+impl LlmExtractor {
+    /// 使用独立的服务端配置创建抽取器
+    pub fn new(server_config: ServerConfig, config: LlmExtractorConfig) -> Result<Self> {
+        Self::with_shared_config(Arc::new(RwLock::new(server_config)), config)
+    }
+
+    /// 与嵌入客户端共享服务端配置创建抽取器（登录后设置的 Token 同时生效）
+    pub fn with_shared_config(
+        server_config: Arc<RwLock<ServerConfig>>,
+        config: LlmExtractorConfig,
+    ) -> Result<Self> {
+        let talk = TalkClient::new(
+            server_config,
+            config.model.clone(),
+            config.temperature,
+            config.timeout_ms,
+            config.max_retries,
+            config.retry_delay_ms,
+        )?;
+
+        Ok(Self {
+            talk,
+            config,
+            grouping: MessageGrouping::default(),
+            fallback: RuleBasedExtractor::new(),
+        })
+    }
+
+    /// 使用指定的消息分组配置：设为忽略的角色不写入对话记录，退回规则抽取时按它分组
+    pub fn with_grouping(mut self, grouping: MessageGrouping) -> Self {
+        self.fallback = self.fallback.with_grouping(grouping.clone());
+        self.grouping = grouping;
+        self
+    }
+
+    /// 只用大模型抽取（不退回规则抽取）
+    pub async fn extract_with_llm(&self, messages: &[Message]) -> Result<Extraction> {
+        let conversation_time = conversation_time(messages);
+        let transcript = format_transcript(messages, &self.grouping);
+        if transcript.is_empty() {
+            return Ok(Extraction::new());
+        }
+
+        let content = self.talk
+            .chat(EXTRACTION_PROMPT, format!("当前时间：{}\n对话：\n{}", conversation_time, transcript))
+            .await?;
+        let (events, triples) = parse_llm_output(&content, &conversation_time, &self.config)?;
+        log::debug!("大模型抽取到 {} 个事件、{} 个三元组", events.len(), triples.len());
+        Ok(Extraction::from_extracted(&events, &triples))
+    }
+}
+
+impl Extractor for LlmExtractor {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    fn extract<'a>(&'a self, messages: &'a [Message]) -> BoxFuture<'a, Result<Extraction>> {
+        Box::pin(async move {
+            match self.extract_with_llm(messages).await {
+                Ok(extraction) => Ok(extraction),
+                Err(e) if self.config.fallback_to_rules => {
+                    log::warn!("大模型抽取失败，退回规则抽取: {}", e);
+                    Ok(self.fallback.extract_sync(messages))
+                }
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
+
+/// 对话时间：最后一条带时间戳的消息时间，都没有时取当前时间
+fn conversation_time(messages: &[Message]) -> String {
+    messages.iter()
+        .rev()
+        .find_map(|m| m.timestamp)
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.format("%Y-%m-%d-%H-%M").to_string())
+        .unwrap_or_else(utils::now_event_time)
+}
+
+/// 拼接对话文本（系统消息与工具结果一并提供给模型作为上下文）
+fn format_transcript(messages: &[Message], grouping: &MessageGrouping) -> String {
+    messages.iter()
+        .filter(|m| grouping.handling_of(&m.role) != RoleHandling::Skip)
+        .map(|m| {
+            let label = match m.role.as_str() {
+                USER_ROLE => "用户",
+                "assistant" => "助手",
+                "system" => "系统",
+                "tool" | "function" => "工具结果",
+                other => other,
+            };
+            format!("{}：{}", label, m.content.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 解析并校验模型输出
+///
+/// 容忍 Markdown 代码块与前后多余文字；丢弃字段为空、实体过长或头尾相同的三元组，
+/// 事件时间无法解析时先从事件描述中的时间表达推断，仍没有时改为对话时间。三元组和事件都为空时视为失败
+fn parse_llm_output(
+    content: &str,
+    conversation_time: &str,
+    config: &LlmExtractorConfig,
+) -> Result<(Vec<ExtractedEvent>, Vec<RawTriple>)> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(MemoryError::Extraction("模型输出中没有 JSON 对象".to_string())),
+    };
+    let output: LlmOutput = serde_json::from_str(json)
+        .map_err(|e| MemoryError::Extraction(format!("模型输出不是有效的抽取结果: {}", e)))?;
+
+    let valid_entity = |name: &str| !name.is_empty() && name.chars().count() <= MAX_ENTITY_CHARS;
+
+    let triples: Vec<RawTriple> = output.triples.into_iter()
+        .map(|t| RawTriple {
+            head: t.head.trim().to_string(),
+            relation: t.relation.trim().to_string(),
+            tail: t.tail.trim().to_string(),
+        })
+        .filter(|t| valid_entity(&t.head) && valid_entity(&t.tail))
+        .filter(|t| !t.relation.is_empty() && t.head != t.tail)
+        .take(config.max_triples)
+        .collect();
+
+    let events: Vec<ExtractedEvent> = output.events.into_iter()
+        .filter(|e| !e.event.trim().is_empty())
+        .map(|e| {
+            let event_time = e.event_time.trim();
+            ExtractedEvent {
+                event: e.event.trim().to_string(),
+                event_time: if utils::parse_event_time(event_time).is_some() {
+                    event_time.to_string()
+                } else {
+                    utils::parse_event_time(conversation_time)
+                        .and_then(|reference| resolve_event_time(&e.event, reference))
+                        .unwrap_or_else(|| conversation_time.to_string())
+                },
+                entities: e.entities.into_iter()
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| valid_entity(name))
+                    .collect(),
+            }
+        })
+        .take(config.max_events)
+        .collect();
+
+    if triples.is_empty() && events.is_empty() {
+        return Err(MemoryError::Extraction("模型没有返回有效的三元组或事件".to_string()));
+    }
+    Ok((events, triples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn message(role: &str, content: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: Some(1714554000),
+        }
+    }
+
+    /// 本地模拟对话端点：依次返回给定的 (状态码, 响应体)，并记录收到的请求体
+    async fn mock_server(responses: Vec<(u16, String)>) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let request = loop {
+                    let n = socket.read(&mut chunk).await.unwrap_or(0);
+                    if n == 0 {
+                        break String::from_utf8_lossy(&buffer).to_string();
+                    }
+                    buffer.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&buffer).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end].lines()
+                            .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if buffer.len() >= header_end + 4 + content_length {
+                            break text[header_end + 4..].to_string();
+                        }
+                    }
+                };
+                recorded.lock().unwrap().push(request);
+
+                let response = format!(
+                    "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn chat_body(content: &str) -> String {
+        serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": content } }]
+        })
+        .to_string()
+    }
+
+    fn extractor(url: String, fallback_to_rules: bool) -> LlmExtractor {
+        let server_config = ServerConfig {
+            server_url: url,
+            auth_token: Some("test-token".to_string()),
+            ..ServerConfig::default()
+        };
+        let config = LlmExtractorConfig {
+            max_retries: 0,
+            fallback_to_rules,
+            ..LlmExtractorConfig::default()
+        };
+        LlmExtractor::new(server_config, config).unwrap()
+    }
+
+    #[test]
+    fn test_parse_llm_output_validates_fields() {
+        let content = r#"好的，结果如下：
+```json
+{"triples": [
+    {"Head": "妈妈", "Relation": "喜欢", "Tail": "玫瑰"},
+    {"Head": "妈妈", "Relation": "", "Tail": "玫瑰"},
+    {"Head": "妈妈", "Relation": "是", "Tail": "妈妈"}
+ ],
+ "events": [
+    {"Event": "昨天陪妈妈去医院复查", "EventTime": "昨天", "Entities": ["妈妈", "医院", ""]},
+    {"Event": " ", "EventTime": "2024-05-01-09-00", "Entities": []}
+ ]}
+```"#;
+        let (events, triples) = parse_llm_output(content, "2024-05-01-09-00", &LlmExtractorConfig::default()).unwrap();
+
+        assert_eq!(triples.len(), 1);
+        assert_eq!(triples[0].tail, "玫瑰");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_time, "2024-04-30-00-00");
+        assert_eq!(events[0].entities, vec!["妈妈", "医院"]);
+
+        assert!(parse_llm_output("抱歉，无法抽取", "2024-05-01-09-00", &LlmExtractorConfig::default()).is_err());
+        assert!(parse_llm_output(r#"{"triples": [], "events": []}"#, "2024-05-01-09-00", &LlmExtractorConfig::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_extracts_from_mock_talk_endpoint() {
+        let content = r#"{"triples": [{"Head": "妈妈", "Relation": "喜欢", "Tail": "玫瑰"}],
+            "events": [{"Event": "陪妈妈去医院", "EventTime": "2024-04-30-10-00", "Entities": ["妈妈", "医院"]}]}"#;
+        let (url, requests) = mock_server(vec![(200, chat_body(content))]).await;
+
+        let messages = vec![message("user", "昨天陪妈妈去医院了"), message("assistant", "辛苦了")];
+        let extraction = extractor(url, false).extract(&messages).await.unwrap();
+
+        assert_eq!(extraction.events.len(), 1);
+        assert_eq!(extraction.events[0].event_time(), Some("2024-04-30-10-00"));
+        let mut entities: Vec<&str> = extraction.entities.iter().map(|n| n.content.as_str()).collect();
+        entities.sort();
+        assert_eq!(entities, vec!["医院", "妈妈", "玫瑰"]);
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.contains("用户：昨天陪妈妈去医院了"));
+        assert!(request.contains("当前时间：2024-05-01-09-00"));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_rules_on_failure() {
+        let (url, _) = mock_server(vec![
+            (500, "{}".to_string()),
+            (200, chat_body("没有 JSON")),
+        ]).await;
+        let messages = vec![message("user", "陪妈妈，医院")];
+
+        let strict = extractor(url.clone(), false);
+        assert!(strict.extract(&messages).await.is_err());
+
+        let lenient = extractor(url, true);
+        let extraction = lenient.extract(&messages).await.unwrap();
+        assert_eq!(extraction.events.len(), 1);
+        assert!(extraction.events[0].content.starts_with("用户说："));
+    }
+
+    #[test]
+    fn test_transcript_follows_role_handling() {
+        let messages = vec![
+            message("system", "用户住在杭州"),
+            message("user", "推荐个周末去处"),
+            message("assistant", "可以去西湖走走"),
+            message("narrator", "对话结束"),
+        ];
+        assert_eq!(
+            format_transcript(&messages, &MessageGrouping::default()),
+            "系统：用户住在杭州\n用户：推荐个周末去处\n助手：可以去西湖走走\nnarrator：对话结束"
+        );
+
+        let grouping = MessageGrouping::default()
+            .role("assistant", RoleHandling::Skip)
+            .role("narrator", RoleHandling::Skip);
+        assert_eq!(
+            format_transcript(&messages, &grouping),
+            "系统：用户住在杭州\n用户：推荐个周末去处"
+        );
+    }
+}
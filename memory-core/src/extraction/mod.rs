@@ -0,0 +1,306 @@
+//! 信息抽取
+//!
+//! 保存对话时由 `Extractor` 把消息转换为事件、实体、概念节点以及它们之间的边，
+//! 之后的嵌入、实体消解、概念复用、时序连接与写入仍由 `MemorySystem` 完成。
+//! 默认使用基于词典分词与词性标注的 `RuleBasedExtractor`，也可改用调用对话模型的 `LlmExtractor`
+//! 或在构建记忆系统时注册自定义抽取器。
+//! 事件重要性由 `ImportanceScorer` 评分，默认按规则打分，也可改用对话模型打分
+//! 消息按角色与多轮窗口分组为事件的规则见 `MessageGrouping`
+
+mod segment;
+mod english;
+mod time_expr;
+mod importance;
+mod grouping;
+mod rule;
+mod llm;
+
+pub use segment::*;
+pub use english::*;
+pub use time_expr::*;
+pub use importance::*;
+pub use grouping::*;
+pub use rule::*;
+pub use llm::*;
+
+use crate::error::Result;
+use crate::graph::{self, EdgeBuilder, NodeBuilder, INITIAL_EDGE_WEIGHT};
+use crate::types::*;
+use crate::utils;
+pub use futures::future::BoxFuture;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// 抽取器
+///
+/// 实现者只需产出本批消息中的节点与边；同名实体、概念与已有节点的对齐由记忆系统负责
+pub trait Extractor: Send + Sync {
+    /// 抽取器名称（用于日志）
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// 从对话消息中抽取节点与边
+    fn extract<'a>(&'a self, messages: &'a [Message]) -> BoxFuture<'a, Result<Extraction>>;
+}
+
+/// 抽取结果
+///
+/// 边可以连接本结果中的节点，也可以直接引用已有节点的 ID
+#[derive(Debug, Clone, Default)]
+pub struct Extraction {
+    /// 事件节点（按对话顺序，用于建立时序边）
+    pub events: Vec<MemoryNode>,
+    /// 实体节点
+    pub entities: Vec<MemoryNode>,
+    /// 概念节点（同名概念在保存时复用已有节点）
+    pub concepts: Vec<MemoryNode>,
+    /// 边
+    pub edges: Vec<Edge>,
+    /// 事件 ID → 重要性评分所用的文本（通常是组内用户消息的原文），未列出的事件按事件内容评分
+    pub scoring_texts: HashMap<Uuid, String>,
+}
+
+impl Extraction {
+    /// 创建空的抽取结果
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否没有抽取到任何节点
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.entities.is_empty() && self.concepts.is_empty()
+    }
+
+    /// 所有节点数
+    pub fn node_count(&self) -> usize {
+        self.events.len() + self.entities.len() + self.concepts.len()
+    }
+
+    /// 丢弃端点不在本结果节点中的边
+    ///
+    /// 宿主回调等不可信来源的抽取结果不允许引用已有节点，保存前用它过滤
+    ///
+    /// # Returns
+    /// 被丢弃的边数
+    pub fn retain_internal_edges(&mut self) -> usize {
+        let ids: HashSet<Uuid> = self.events.iter()
+            .chain(&self.entities)
+            .chain(&self.concepts)
+            .map(|n| n.id)
+            .collect();
+        let before = self.edges.len();
+        self.edges.retain(|edge| {
+            let known = ids.contains(&edge.source) && ids.contains(&edge.target);
+            if !known {
+                log::warn!("丢弃端点不在抽取结果中的边: {} -[{}]-> {}", edge.source, edge.relation, edge.target);
+            }
+            known
+        });
+        before - self.edges.len()
+    }
+
+    /// 添加事件节点
+    pub fn add_event(&mut self, event: MemoryNode) -> Uuid {
+        let id = event.id;
+        self.events.push(event);
+        id
+    }
+
+    /// 添加实体（同名实体只创建一次）
+    ///
+    /// # Returns
+    /// 实体节点 ID
+    pub fn add_entity(&mut self, name: &str, entity_type: EntityType) -> Uuid {
+        if let Some(existing) = self.entities.iter().find(|n| n.content == name) {
+            return existing.id;
+        }
+        let node = NodeBuilder::entity(name).entity_type(entity_type).build();
+        let id = node.id;
+        self.entities.push(node);
+        id
+    }
+
+    /// 添加时间实体：以规范化取值为名，原文与粒度记入属性（同名只创建一次）
+    ///
+    /// # Returns
+    /// 实体节点 ID
+    pub fn add_time_entity(&mut self, expression: &TimeExpression) -> Uuid {
+        let name = expression.normalized();
+        if let Some(existing) = self.entities.iter().find(|n| n.content == name) {
+            return existing.id;
+        }
+        let node = NodeBuilder::entity(name)
+            .entity_type(EntityType::Time)
+            .attributes(serde_json::json!({
+                "expression": expression.text,
+                "granularity": expression.granularity.as_str(),
+            }))
+            .build();
+        let id = node.id;
+        self.entities.push(node);
+        id
+    }
+
+    /// 实体参与事件：`entity -[participates_in]-> event`
+    pub fn add_participation(&mut self, entity_id: Uuid, event_id: Uuid) {
+        self.edges.push(
+            EdgeBuilder::participates(entity_id, event_id)
+                .weight(INITIAL_EDGE_WEIGHT)
+                .build(),
+        );
+    }
+
+    /// 实体归入概念：`entity -[conceptualized_as]-> concept`（同名概念只创建一次）
+    ///
+    /// # Returns
+    /// 概念节点 ID
+    pub fn add_concept(&mut self, entity_id: Uuid, concept_name: &str) -> Uuid {
+        let concept_id = match self.concepts.iter().find(|n| n.content == concept_name) {
+            Some(existing) => existing.id,
+            None => {
+                let node = NodeBuilder::concept(concept_name).instance_count(0).build();
+                let id = node.id;
+                self.concepts.push(node);
+                id
+            }
+        };
+        self.edges.push(
+            EdgeBuilder::conceptualizes(entity_id, concept_id)
+                .weight(INITIAL_EDGE_WEIGHT)
+                .build(),
+        );
+        concept_id
+    }
+
+    /// 任意关系：`source -[relation]-> target`（关系名会被规范化）
+    pub fn add_relation(&mut self, source: Uuid, target: Uuid, relation: &str) {
+        self.edges.push(
+            EdgeBuilder::new(source, target, graph::normalize_relation(relation))
+                .weight(INITIAL_EDGE_WEIGHT)
+                .build(),
+        );
+    }
+
+    /// 由按名称描述的事件与三元组构建抽取结果
+    ///
+    /// 用于宿主回调与 LLM 等只能给出文本结果的抽取器：实体类型按名称推断，
+    /// 可识别类型的实体归入默认概念；事件时间为空时使用当前时间
+    pub fn from_extracted(events: &[ExtractedEvent], triples: &[RawTriple]) -> Self {
+        let mut extraction = Self::new();
+        let mut entity_ids: HashMap<String, Uuid> = HashMap::new();
+        let mut entity = |extraction: &mut Self, name: &str| -> Uuid {
+            if let Some(id) = entity_ids.get(name) {
+                return *id;
+            }
+            let entity_type = graph::infer_entity_type(name);
+            let id = extraction.add_entity(name, entity_type);
+            if let Some(concept) = default_concept_name(entity_type) {
+                extraction.add_concept(id, concept);
+            }
+            entity_ids.insert(name.to_string(), id);
+            id
+        };
+
+        for event in events {
+            let content = event.event.trim();
+            if content.is_empty() {
+                continue;
+            }
+            let event_time = match event.event_time.trim() {
+                "" => utils::now_event_time(),
+                time => time.to_string(),
+            };
+            let event_id = extraction.add_event(NodeBuilder::event(content).event_time(event_time).build());
+            for name in event.entities.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+                let entity_id = entity(&mut extraction, name);
+                extraction.add_participation(entity_id, event_id);
+            }
+        }
+
+        for triple in triples {
+            let (head, tail) = (triple.head.trim(), triple.tail.trim());
+            if head.is_empty() || tail.is_empty() || graph::normalize_relation(&triple.relation).is_empty() {
+                continue;
+            }
+            let head_id = entity(&mut extraction, head);
+            let tail_id = entity(&mut extraction, tail);
+            extraction.add_relation(head_id, tail_id, &triple.relation);
+        }
+
+        extraction
+    }
+}
+
+/// 实体类型对应的默认概念
+pub fn default_concept_name(entity_type: EntityType) -> Option<&'static str> {
+    match entity_type {
+        EntityType::Person => Some("人物"),
+        EntityType::Place => Some("地点"),
+        EntityType::Time => Some("时间"),
+        EntityType::Object => Some("物品"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_helpers_dedupe_by_name() {
+        let mut extraction = Extraction::new();
+        let event = extraction.add_event(NodeBuilder::event("陪妈妈去医院").build());
+        let mom = extraction.add_entity("妈妈", EntityType::Person);
+        assert_eq!(extraction.add_entity("妈妈", EntityType::Person), mom);
+
+        let person = extraction.add_concept(mom, "人物");
+        let dad = extraction.add_entity("爸爸", EntityType::Person);
+        assert_eq!(extraction.add_concept(dad, "人物"), person);
+        extraction.add_participation(mom, event);
+
+        assert_eq!(extraction.node_count(), 4);
+        assert_eq!(extraction.concepts.len(), 1);
+        assert_eq!(extraction.edges.len(), 3);
+
+        extraction.add_relation(mom, Uuid::new_v4(), "认识");
+        extraction.add_relation(mom, dad, "认识");
+        assert_eq!(extraction.retain_internal_edges(), 1);
+        assert_eq!(extraction.edges.len(), 4);
+    }
+
+    #[test]
+    fn test_from_extracted_events_and_triples() {
+        let events = vec![ExtractedEvent {
+            event: "和妈妈去公园散步".to_string(),
+            event_time: "2024-05-01-09-00".to_string(),
+            entities: vec!["妈妈".to_string(), "公园".to_string(), " ".to_string()],
+        }];
+        let triples = vec![
+            RawTriple {
+                head: "妈妈".to_string(),
+                relation: "喜欢".to_string(),
+                tail: "玫瑰".to_string(),
+            },
+            RawTriple {
+                head: "".to_string(),
+                relation: "喜欢".to_string(),
+                tail: "玫瑰".to_string(),
+            },
+        ];
+
+        let extraction = Extraction::from_extracted(&events, &triples);
+        assert_eq!(extraction.events.len(), 1);
+        assert_eq!(extraction.events[0].event_time(), Some("2024-05-01-09-00"));
+        assert_eq!(extraction.entities.len(), 3);
+
+        let mut concepts: Vec<&str> = extraction.concepts.iter().map(|n| n.content.as_str()).collect();
+        concepts.sort();
+        assert_eq!(concepts, vec!["人物", "地点"]);
+
+        let count = |relation: &str| extraction.edges.iter().filter(|e| e.relation == relation).count();
+        assert_eq!(count(graph::relation_types::PARTICIPATES_IN), 2);
+        assert_eq!(count(graph::relation_types::CONCEPTUALIZED_AS), 2);
+        assert_eq!(extraction.edges.len(), 5);
+    }
+}
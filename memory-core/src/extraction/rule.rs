@@ -0,0 +1,432 @@
+//! 基于规则的抽取器
+//!
+//! 消息按 `MessageGrouping` 分组，每组生成一个对话事件：默认用户消息与紧随的助手回复为一组，
+//! 单独的助手消息自成事件，系统消息与工具结果作为上下文并入相邻事件。
+//! 中文消息经分词与词性标注、英文消息经名词短语与词表规则抽取出人名、地名、时间与物品实体，
+//! 中英混合的消息两者都做，实体归入对应的默认概念；除用户消息外，助手回复与上下文消息是否参与实体抽取
+//! 由 `MessageGrouping::entity_sources` 决定。
+//! 消息中的时间表达（"上周六"、"last Friday"）以消息时间（按配置的时区换算，默认本地时区）为基准解析，
+//! 用于设置事件时间并生成以规范化取值命名的时间实体；没有时间戳的消息无法确定说话时间，
+//! 时间词按原文抽取。事件重要性由 `RuleBasedScorer` 按用户消息打分
+
+use super::{
+    default_concept_name, detect_language, extract_english_entities, parse_time_expressions,
+    resolve_event_time, BoxFuture, Extraction, Extractor, Language, MessageGroup, MessageGrouping,
+    PosTag, RuleBasedScorer, Segmenter,
+};
+use crate::error::Result;
+use crate::graph::NodeBuilder;
+use crate::types::*;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Utc};
+use std::sync::Arc;
+
+/// 实体名的最大字数
+const MAX_ENTITY_CHARS: usize = 20;
+
+/// 基于规则的抽取器（默认）
+#[derive(Debug, Clone)]
+pub struct RuleBasedExtractor {
+    segmenter: Arc<Segmenter>,
+    scorer: RuleBasedScorer,
+    grouping: MessageGrouping,
+    /// 解析时间表达所用的时区（None 表示系统本地时区）
+    utc_offset: Option<FixedOffset>,
+}
+
+impl Default for RuleBasedExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuleBasedExtractor {
+    /// 创建使用内置词典的抽取器
+    pub fn new() -> Self {
+        Self::with_segmenter(Segmenter::shared())
+    }
+
+    /// 使用指定分词器（例如加载了用户词典的分词器）创建抽取器
+    pub fn with_segmenter(segmenter: Arc<Segmenter>) -> Self {
+        Self {
+            scorer: RuleBasedScorer::new().segmenter(segmenter.clone()),
+            segmenter,
+            grouping: MessageGrouping::default(),
+            utc_offset: None,
+        }
+    }
+
+    /// 使用指定的重要性评分器（例如调整过权重的评分器）
+    pub fn with_scorer(mut self, scorer: RuleBasedScorer) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// 当前使用的分词器
+    pub fn segmenter(&self) -> &Arc<Segmenter> {
+        &self.segmenter
+    }
+
+    /// 使用指定的消息分组配置（角色处理方式、多轮合并窗口）
+    pub fn with_grouping(mut self, grouping: MessageGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// 当前的消息分组配置
+    pub fn grouping(&self) -> &MessageGrouping {
+        &self.grouping
+    }
+
+    /// 使用固定的时区解析时间表达（例如东八区为 `FixedOffset::east_opt(8 * 3600)`），默认使用系统本地时区
+    pub fn with_utc_offset(mut self, offset: FixedOffset) -> Self {
+        self.utc_offset = Some(offset);
+        self
+    }
+
+    /// 同步抽取
+    pub fn extract_sync(&self, messages: &[Message]) -> Extraction {
+        let now = self.local_time(Utc::now());
+        let mut extraction = Extraction::new();
+        for group in self.grouping.group(messages) {
+            let event_id = extraction.add_event(self.group_to_event(&group, now));
+            extraction.scoring_texts.insert(event_id, primary_text(&group));
+            for msg in group.entity_messages(&self.grouping.entity_sources) {
+                self.extract_entities(msg, event_id, &mut extraction);
+            }
+        }
+        extraction
+    }
+
+    /// 换算为所配置时区的本地时间
+    fn local_time(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self.utc_offset {
+            Some(offset) => time.with_timezone(&offset).naive_local(),
+            None => time.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// 消息的本地时间（没有时间戳时返回 None）
+    fn message_time(&self, msg: &Message) -> Option<NaiveDateTime> {
+        msg.timestamp
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .map(|time| self.local_time(time))
+    }
+
+    /// 将一组消息转换为事件节点
+    ///
+    /// 事件时间与重要性取自组内的用户消息（没有用户消息时取全部消息），
+    /// 来源角色记录在事件节点上
+    fn group_to_event(&self, group: &MessageGroup, now: NaiveDateTime) -> MemoryNode {
+        let primary = group.primary_messages();
+
+        // 消息提到了其他时间（"上周六"）时以它为事件时间，否则用消息时间（没有时间戳时用当前时间）
+        let first = primary.first().copied().unwrap_or_else(|| group.initiator());
+        let reference = self.message_time(first).unwrap_or(now);
+        let event_time = primary.iter()
+            .find_map(|msg| resolve_event_time(&msg.content, self.message_time(msg)?))
+            .unwrap_or_else(|| reference.format("%Y-%m-%d-%H-%M").to_string());
+
+        // 事件重要性：按情绪、偏好、计划等信号打分
+        NodeBuilder::event(group.content())
+            .event_time(event_time)
+            .importance(self.scorer.score_text(&primary_text(group)))
+            .roles(group.roles())
+            .build()
+    }
+
+    /// 从消息中提取实体并关联到事件
+    ///
+    /// 中文部分经分词与词性标注后，人名/称谓、地名/机构、时间词与普通名词作为实体，
+    /// 代词、动词等虚词不参与抽取；英文部分按名词短语与词表规则抽取
+    fn extract_entities(&self, msg: &Message, event_id: uuid::Uuid, extraction: &mut Extraction) {
+        // 时间表达生成以规范化取值命名的时间实体（没有时间戳的消息不解析，时间词按原文抽取）
+        let expressions = self.message_time(msg)
+            .map(|reference| parse_time_expressions(&msg.content, reference))
+            .unwrap_or_default();
+        for expression in &expressions {
+            let is_new = !extraction.entities.iter().any(|n| n.content == expression.normalized());
+            let entity_id = extraction.add_time_entity(expression);
+            Self::link_entity(extraction, entity_id, EntityType::Time, is_new, event_id);
+        }
+
+        for (name, entity_type) in self.entity_candidates(&msg.content) {
+            let name = name.as_str();
+            if name.chars().count() > MAX_ENTITY_CHARS {
+                continue;
+            }
+            // 已被时间表达覆盖的时间词（"昨天"）由上面的时间实体代替
+            if entity_type == EntityType::Time && expressions.iter().any(|e| e.text.contains(name)) {
+                continue;
+            }
+
+            let is_new = !extraction.entities.iter().any(|n| n.content == name);
+            let entity_id = extraction.add_entity(name, entity_type);
+            Self::link_entity(extraction, entity_id, entity_type, is_new, event_id);
+        }
+    }
+
+    /// 新实体归入默认概念，并关联到对应的事件节点
+    fn link_entity(
+        extraction: &mut Extraction,
+        entity_id: uuid::Uuid,
+        entity_type: EntityType,
+        is_new: bool,
+        event_id: uuid::Uuid,
+    ) {
+        if is_new {
+            if let Some(concept) = default_concept_name(entity_type) {
+                extraction.add_concept(entity_id, concept);
+            }
+        }
+        extraction.add_participation(entity_id, event_id);
+    }
+
+    /// 按消息语言抽取候选实体
+    fn entity_candidates(&self, text: &str) -> Vec<(String, EntityType)> {
+        let language = detect_language(text);
+        let mut candidates = Vec::new();
+        if language != Language::English {
+            candidates.extend(self.segmenter.segment(text).into_iter().filter_map(|token| {
+                // 只提取可识别类型的实体
+                entity_type_of(token.tag).map(|entity_type| (token.word, entity_type))
+            }));
+        }
+        if language != Language::Chinese {
+            candidates.extend(extract_english_entities(text));
+        }
+        candidates
+    }
+}
+
+/// 组内用于打分的消息原文（每条一行）
+fn primary_text(group: &MessageGroup) -> String {
+    group.primary_messages().iter().map(|m| m.content.as_str()).collect::<Vec<_>>().join("\n")
+}
+
+/// 词性对应的实体类型（不作为实体的词性返回 None）
+fn entity_type_of(tag: PosTag) -> Option<EntityType> {
+    match tag {
+        PosTag::PersonName => Some(EntityType::Person),
+        PosTag::PlaceName | PosTag::Organization => Some(EntityType::Place),
+        PosTag::Time => Some(EntityType::Time),
+        PosTag::Noun => Some(EntityType::Object),
+        _ => None,
+    }
+}
+
+impl Extractor for RuleBasedExtractor {
+    fn name(&self) -> &str {
+        "rule"
+    }
+
+    fn extract<'a>(&'a self, messages: &'a [Message]) -> BoxFuture<'a, Result<Extraction>> {
+        Box::pin(futures::future::ready(Ok(self.extract_sync(messages))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extraction::{ImportanceWeights, RoleHandling};
+    use crate::graph;
+
+    fn message(role: &str, content: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: None,
+        }
+    }
+
+    fn beijing() -> FixedOffset {
+        FixedOffset::east_opt(8 * 3600).unwrap()
+    }
+
+    /// 按东八区解析时间的抽取器
+    fn extractor() -> RuleBasedExtractor {
+        RuleBasedExtractor::new().with_utc_offset(beijing())
+    }
+
+    /// 东八区 2025-03-12 10:00（星期三）发出的消息
+    fn timed_message(role: &str, content: &str) -> Message {
+        let time = chrono::NaiveDate::from_ymd_opt(2025, 3, 12).unwrap().and_hms_opt(10, 0, 0).unwrap();
+        Message {
+            timestamp: Some(time.and_local_timezone(beijing()).unwrap().timestamp()),
+            ..message(role, content)
+        }
+    }
+
+    #[test]
+    fn test_pairs_user_and_assistant_into_events() {
+        let messages = vec![
+            message("user", "今天陪妈妈去医院"),
+            message("assistant", "妈妈身体还好吗"),
+            message("system", "忽略"),
+            message("user", "还好"),
+        ];
+        let extraction = RuleBasedExtractor::new().extract_sync(&messages);
+
+        assert_eq!(extraction.events.len(), 2);
+        assert!(extraction.events[0].content.contains("回复：妈妈身体还好吗"));
+        assert_eq!(extraction.events[1].content, "用户说：还好");
+    }
+
+    #[test]
+    fn test_entities_link_to_their_event_and_concept() {
+        let messages = vec![
+            timed_message("user", "我和妈妈去了医院"),
+            timed_message("user", "妈妈说明天想吃饺子"),
+        ];
+        let extraction = extractor().extract_sync(&messages);
+
+        let entities: Vec<(&str, EntityType)> = extraction.entities.iter()
+            .map(|n| (n.content.as_str(), n.entity_type().unwrap()))
+            .collect();
+        assert_eq!(entities, vec![
+            ("妈妈", EntityType::Person),
+            ("医院", EntityType::Place),
+            ("2025-03-13", EntityType::Time),
+            ("饺子", EntityType::Object),
+        ]);
+        assert_eq!(extraction.entities[2].attributes().unwrap()["expression"], "明天");
+        assert_eq!(extraction.concepts.len(), 4);
+
+        let mom = extraction.entities[0].id;
+        let participations: Vec<uuid::Uuid> = extraction.edges.iter()
+            .filter(|e| e.source == mom && e.relation == graph::relation_types::PARTICIPATES_IN)
+            .map(|e| e.target)
+            .collect();
+        let event_ids: Vec<uuid::Uuid> = extraction.events.iter().map(|n| n.id).collect();
+        assert_eq!(participations, event_ids);
+    }
+
+    #[test]
+    fn test_english_and_mixed_messages() {
+        let messages = vec![
+            message("user", "I took my dog to the vet with Alice this morning"),
+            message("user", "周末和 Alice 去了 Starbucks"),
+        ];
+        let extraction = RuleBasedExtractor::new().extract_sync(&messages);
+
+        let entities: Vec<(&str, EntityType)> = extraction.entities.iter()
+            .map(|n| (n.content.as_str(), n.entity_type().unwrap()))
+            .collect();
+        assert_eq!(entities, vec![
+            ("dog", EntityType::Object),
+            ("Alice", EntityType::Person),
+            ("morning", EntityType::Time),
+            ("周末", EntityType::Time),
+            ("Starbucks", EntityType::Place),
+        ]);
+    }
+
+    #[test]
+    fn test_time_expressions_resolve_against_message_time() {
+        let messages = vec![
+            timed_message("user", "I took my dog to the vet with Alice this morning"),
+            timed_message("user", "上周末和 Alice 去了 Starbucks"),
+        ];
+        let extraction = extractor().extract_sync(&messages);
+
+        let entities: Vec<(&str, EntityType)> = extraction.entities.iter()
+            .map(|n| (n.content.as_str(), n.entity_type().unwrap()))
+            .collect();
+        assert_eq!(entities, vec![
+            ("2025-03-12 09:00", EntityType::Time),
+            ("dog", EntityType::Object),
+            ("Alice", EntityType::Person),
+            ("2025-03-08", EntityType::Time),
+            ("Starbucks", EntityType::Place),
+        ]);
+
+        let event_times: Vec<&str> = extraction.events.iter().filter_map(|n| n.event_time()).collect();
+        assert_eq!(event_times, vec!["2025-03-12-09-00", "2025-03-08-00-00"]);
+    }
+
+    #[test]
+    fn test_time_expressions_use_configured_offset() {
+        // 2025-03-12 01:00 UTC：东八区已是 12 日上午，西八区还是 11 日下午
+        let time = chrono::NaiveDate::from_ymd_opt(2025, 3, 12).unwrap().and_hms_opt(1, 0, 0).unwrap();
+        let messages = vec![Message {
+            timestamp: Some(time.and_utc().timestamp()),
+            ..message("user", "昨天去了医院")
+        }];
+        let resolved = |extractor: RuleBasedExtractor| {
+            let extraction = extractor.extract_sync(&messages);
+            let entity = extraction.entities.iter()
+                .find(|n| n.entity_type() == Some(EntityType::Time))
+                .map(|n| n.content.clone());
+            (entity, extraction.events[0].event_time().map(str::to_string))
+        };
+
+        assert_eq!(resolved(extractor()), (Some("2025-03-11".to_string()), Some("2025-03-11-00-00".to_string())));
+        let pacific = RuleBasedExtractor::new().with_utc_offset(FixedOffset::west_opt(8 * 3600).unwrap());
+        assert_eq!(resolved(pacific), (Some("2025-03-10".to_string()), Some("2025-03-10-00-00".to_string())));
+    }
+
+    #[test]
+    fn test_event_importance_uses_scorer() {
+        let messages = vec![
+            message("user", "好的"),
+            message("user", "记住，我对花生过敏"),
+        ];
+        let extraction = RuleBasedExtractor::new().extract_sync(&messages);
+        assert_eq!(extraction.scoring_texts[&extraction.events[1].id], "记住，我对花生过敏");
+        assert!(extraction.events[0].importance < 0.3);
+        assert!(extraction.events[1].importance > 0.7);
+
+        // 只保留基础分时所有事件同分
+        let flat = RuleBasedScorer::with_weights(ImportanceWeights {
+            base: 0.5,
+            length: 0.0,
+            emotion: 0.0,
+            first_person_fact: 0.0,
+            preference: 0.0,
+            plan: 0.0,
+            named_entity: 0.0,
+            remember_request: 0.0,
+            small_talk_penalty: 0.0,
+        });
+        let extraction = RuleBasedExtractor::new().with_scorer(flat).extract_sync(&messages);
+        assert!(extraction.events.iter().all(|e| (e.importance - 0.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_assistant_and_system_messages_are_kept() {
+        let messages = vec![
+            timed_message("assistant", "提醒一下，明天下午要带妈妈去医院复查"),
+            timed_message("system", "用户住在杭州"),
+        ];
+        let extraction = extractor().extract_sync(&messages);
+
+        assert_eq!(extraction.events.len(), 1);
+        let event = &extraction.events[0];
+        assert_eq!(event.content, "助手说：提醒一下，明天下午要带妈妈去医院复查\n系统提示：用户住在杭州");
+        assert_eq!(event.roles().unwrap(), ["assistant", "system"]);
+        assert_eq!(event.event_time(), Some("2025-03-13-15-00"));
+        let names: Vec<&str> = extraction.entities.iter().map(|n| n.content.as_str()).collect();
+        assert!(names.contains(&"妈妈") && names.contains(&"杭州"));
+
+        // 助手消息设为忽略时只剩系统上下文
+        let grouping = MessageGrouping::default().role("assistant", RoleHandling::Skip);
+        let extraction = extractor().with_grouping(grouping).extract_sync(&messages);
+        assert_eq!(extraction.events.len(), 1);
+        assert_eq!(extraction.events[0].roles().unwrap(), ["system"]);
+    }
+
+    #[test]
+    fn test_entities_from_context_and_assistant_messages() {
+        let messages = vec![
+            message("system", "用户住在杭州"),
+            message("user", "推荐个周末去处"),
+            message("assistant", "可以去西湖走走"),
+        ];
+        let names = |extractor: RuleBasedExtractor| -> Vec<String> {
+            extractor.extract_sync(&messages).entities.into_iter().map(|n| n.content).collect()
+        };
+
+        assert_eq!(names(RuleBasedExtractor::new()), vec!["杭州", "周末", "西湖"]);
+        let user_only = MessageGrouping::default().entity_sources(Vec::new());
+        assert_eq!(names(RuleBasedExtractor::new().with_grouping(user_only)), vec!["周末"]);
+    }
+}
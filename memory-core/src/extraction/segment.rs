@@ -0,0 +1,434 @@
+//! 中文分词与词性标注
+//!
+//! 基于 jieba 分词（词典最大概率切分，未登录词由 HMM 识别），
+//! 内置词典随代码发布并覆盖在 jieba 默认词典之上，修正人物称谓、场所等
+//! 记忆场景常用词的词性，可再加载用户词典。jieba 无法标注的未登录汉字串
+//! 只有在姓氏开头且上下文像人名时才标为人名，地名后缀结尾的标为地名
+
+use crate::error::{MemoryError, Result};
+use jieba_rs::Jieba;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// 内置词典（每行：词 词频 词性）
+const DEFAULT_DICT: &str = include_str!("dict.txt");
+
+/// 常见姓氏（用于识别未登录的人名）
+const SURNAMES: &str = "王李张刘陈杨赵黄周吴徐孙胡朱高林何郭马罗梁宋郑谢韩唐冯于董萧程曹袁邓许傅沈曾彭吕苏卢蒋蔡贾丁魏薛叶阎余潘杜戴夏钟汪田任姜范方石姚谭廖邹熊金陆郝孔白崔康毛邱秦江史顾侯邵孟龙万段雷钱汤尹黎易常武乔贺赖龚文";
+
+/// 地名后缀（用于识别未登录的地名）
+const PLACE_SUFFIXES: &str = "省市县区镇村乡路街巷山湖河江岛桥站馆园";
+
+/// 后面通常跟人名的词（和张三、找李四、告诉王五）
+const NAME_PRECEDERS: &[&str] = &[
+    "和", "跟", "与", "同", "叫", "找", "给", "问", "告诉", "陪", "见", "约", "带", "让", "请", "被", "对",
+];
+
+/// 前面通常是人名的词（张三说、李四觉得）
+const NAME_FOLLOWERS: &[&str] = &["说", "告诉", "问", "觉得", "认为"];
+
+/// 跟在人名后的称谓（王芳老师、李明医生）
+const NAME_TITLES: &[&str] = &["老师", "先生", "女士", "医生", "同学", "经理", "师傅", "阿姨", "叔叔"];
+
+/// 未登录人名的最少、最多字数（含姓氏）
+const NAME_CHARS: std::ops::RangeInclusive<usize> = 2..=3;
+
+/// 词性（沿用 jieba 的标注集）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PosTag {
+    /// n：普通名词
+    Noun,
+    /// nr：人名及人物称谓
+    PersonName,
+    /// ns：地名及场所
+    PlaceName,
+    /// nt：机构名
+    Organization,
+    /// t：时间词
+    Time,
+    /// v：动词
+    Verb,
+    /// a：形容词
+    Adjective,
+    /// r：代词
+    Pronoun,
+    /// m：数词
+    Numeral,
+    /// q：量词
+    Quantifier,
+    /// p：介词
+    Preposition,
+    /// c：连词
+    Conjunction,
+    /// u：助词
+    Particle,
+    /// d：副词
+    Adverb,
+    /// eng：英文
+    English,
+    /// x：标点及无法识别的字符
+    Unknown,
+}
+
+impl PosTag {
+    /// jieba 标注代码
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PosTag::Noun => "n",
+            PosTag::PersonName => "nr",
+            PosTag::PlaceName => "ns",
+            PosTag::Organization => "nt",
+            PosTag::Time => "t",
+            PosTag::Verb => "v",
+            PosTag::Adjective => "a",
+            PosTag::Pronoun => "r",
+            PosTag::Numeral => "m",
+            PosTag::Quantifier => "q",
+            PosTag::Preposition => "p",
+            PosTag::Conjunction => "c",
+            PosTag::Particle => "u",
+            PosTag::Adverb => "d",
+            PosTag::English => "eng",
+            PosTag::Unknown => "x",
+        }
+    }
+
+    /// 从标注代码解析（未知代码按首字母归类，仍无法识别时返回 None）
+    pub fn parse(code: &str) -> Option<Self> {
+        let tag = match code {
+            "n" | "nz" | "ng" | "vn" => PosTag::Noun,
+            "nr" | "nrt" | "nrfg" => PosTag::PersonName,
+            "ns" => PosTag::PlaceName,
+            "nt" => PosTag::Organization,
+            "t" | "tg" => PosTag::Time,
+            "v" | "vd" | "vg" => PosTag::Verb,
+            "a" | "ad" | "an" | "ag" => PosTag::Adjective,
+            "r" | "rr" | "rz" => PosTag::Pronoun,
+            "m" | "mq" => PosTag::Numeral,
+            "q" => PosTag::Quantifier,
+            "p" => PosTag::Preposition,
+            "c" => PosTag::Conjunction,
+            "u" | "uj" | "ul" | "y" | "e" => PosTag::Particle,
+            "d" => PosTag::Adverb,
+            "eng" => PosTag::English,
+            "x" => PosTag::Unknown,
+            other => match other.chars().next()? {
+                'n' => PosTag::Noun,
+                'v' => PosTag::Verb,
+                'a' => PosTag::Adjective,
+                'r' => PosTag::Pronoun,
+                'd' => PosTag::Adverb,
+                _ => return None,
+            },
+        };
+        Some(tag)
+    }
+}
+
+impl std::fmt::Display for PosTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 分词结果中的一个词
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// 词
+    pub word: String,
+    /// 词性
+    pub tag: PosTag,
+}
+
+impl Token {
+    fn new(word: impl Into<String>, tag: PosTag) -> Self {
+        Self { word: word.into(), tag }
+    }
+}
+
+/// 中文分词器
+///
+/// 包装 jieba 分词器；内置词典与用户词典中的词同时记录词性，
+/// 标注时优先于 jieba 默认词典的词性
+#[derive(Debug, Clone)]
+pub struct Segmenter {
+    jieba: Jieba,
+    /// 内置词典与用户词典中的词 → 词性
+    tags: HashMap<String, PosTag>,
+}
+
+impl Default for Segmenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Segmenter {
+    /// 创建分词器：jieba 默认词典之上叠加内置词典
+    pub fn new() -> Self {
+        let mut segmenter = Self {
+            jieba: Jieba::new(),
+            tags: HashMap::new(),
+        };
+        segmenter
+            .load_user_dict(DEFAULT_DICT)
+            .expect("内置词典格式错误");
+        segmenter
+    }
+
+    /// 创建空词典的分词器（不含 jieba 默认词典与内置词典）
+    pub fn empty() -> Self {
+        Self {
+            jieba: Jieba::empty(),
+            tags: HashMap::new(),
+        }
+    }
+
+    /// 进程内共享的默认分词器（首次使用时加载词典）
+    pub fn shared() -> Arc<Segmenter> {
+        static SHARED: OnceLock<Arc<Segmenter>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Segmenter::new())).clone()
+    }
+
+    /// 内置词典与用户词典中的词数（不含 jieba 默认词典）
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// 是否没有加载内置词典与用户词典
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// 查询词的词性
+    ///
+    /// 先查内置词典与用户词典，再查 jieba 默认词典；不是词典中的词时返回 None
+    pub fn tag_of(&self, word: &str) -> Option<PosTag> {
+        if let Some(tag) = self.tags.get(word) {
+            return Some(*tag);
+        }
+        match self.jieba.tag(word, false).as_slice() {
+            [only] if only.word == word && only.tag != "x" => PosTag::parse(only.tag),
+            _ => None,
+        }
+    }
+
+    /// 添加（或覆盖）一个词
+    ///
+    /// # Arguments
+    /// * `freq` - 词频，为 None 时取能让该词不被切开的最小词频
+    pub fn add_word(&mut self, word: &str, freq: Option<u64>, tag: PosTag) {
+        let word = word.trim();
+        if word.is_empty() {
+            return;
+        }
+        let freq = freq.map(|f| usize::try_from(f).unwrap_or(usize::MAX));
+        self.jieba.add_word(word, freq, Some(tag.as_str()));
+        self.tags.insert(word.to_string(), tag);
+    }
+
+    /// 加载用户词典
+    ///
+    /// 每行格式为 `词 [词频] [词性]`，空行与 `#` 开头的行忽略；
+    /// 省略词性时记为名词
+    ///
+    /// # Returns
+    /// 加载的词数
+    pub fn load_user_dict(&mut self, content: &str) -> Result<usize> {
+        let mut count = 0;
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let Some(word) = parts.next() else { continue };
+
+            let mut freq = None;
+            let mut tag = PosTag::Noun;
+            for part in parts {
+                if let Ok(f) = part.parse::<u64>() {
+                    freq = Some(f);
+                } else {
+                    tag = PosTag::parse(part).ok_or_else(|| {
+                        MemoryError::InvalidInput(format!("词典第 {} 行词性无法识别：{}", line_no + 1, part))
+                    })?;
+                }
+            }
+            self.add_word(word, freq, tag);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// 从文件加载用户词典
+    pub fn load_user_dict_file(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let content = std::fs::read_to_string(path.as_ref())?;
+        self.load_user_dict(&content)
+    }
+
+    /// 分词并标注词性
+    ///
+    /// 空白字符被丢弃，标点作为 `x` 词性的单字保留
+    pub fn segment(&self, text: &str) -> Vec<Token> {
+        let tokens: Vec<Token> = self.jieba.tag(text, true)
+            .into_iter()
+            .filter(|t| !t.word.trim().is_empty())
+            .map(|t| {
+                let tag = self.tags.get(t.word)
+                    .copied()
+                    .or_else(|| PosTag::parse(t.tag))
+                    .unwrap_or(PosTag::Unknown);
+                Token::new(t.word, tag)
+            })
+            .collect();
+
+        self.merge_unknown(tokens)
+    }
+
+    /// 只返回切分出的词
+    pub fn cut(&self, text: &str) -> Vec<String> {
+        self.segment(text).into_iter().map(|t| t.word).collect()
+    }
+
+    /// 识别未登录词
+    ///
+    /// 连续的未登录汉字合并为一个词：姓氏开头、共 2 到 3 个字且上下文像人名时
+    /// 标为人名，地名后缀结尾的标为地名，其余保持 `x`；"小王""老李"标为人名
+    fn merge_unknown(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let is_oov = |t: &Token| {
+            t.tag == PosTag::Unknown
+                && t.word.chars().all(is_cjk)
+                && !self.tags.contains_key(&t.word)
+        };
+        let is_surname = |t: &Token| t.word.chars().count() == 1 && SURNAMES.contains(t.word.as_str());
+
+        let mut merged: Vec<Token> = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+
+            // 小王、老李
+            if (token.word == "小" || token.word == "老")
+                && tokens.get(i + 1).is_some_and(is_surname)
+                && !tokens.get(i + 2).is_some_and(is_oov)
+            {
+                merged.push(Token::new(format!("{}{}", token.word, tokens[i + 1].word), PosTag::PersonName));
+                i += 2;
+                continue;
+            }
+
+            if !is_oov(token) {
+                merged.push(token.clone());
+                i += 1;
+                continue;
+            }
+
+            let run = tokens[i..].iter().take_while(|t| is_oov(t)).count();
+            let word: String = tokens[i..i + run].iter().map(|t| t.word.as_str()).collect();
+            let len = word.chars().count();
+            let tag = if NAME_CHARS.contains(&len)
+                && word.chars().next().is_some_and(|c| SURNAMES.contains(c))
+                && looks_like_name_context(merged.last(), tokens.get(i + run))
+            {
+                PosTag::PersonName
+            } else if len > 1 && word.chars().last().is_some_and(|c| PLACE_SUFFIXES.contains(c)) {
+                PosTag::PlaceName
+            } else {
+                PosTag::Unknown
+            };
+            merged.push(Token::new(word, tag));
+            i += run;
+        }
+
+        merged
+    }
+}
+
+/// 未登录词前后的词是否像人名所处的上下文
+///
+/// 前一个词是"和""找""告诉"等，或后一个词是"说""觉得"等、称谓；
+/// 位于句首或标点之后时，后面紧跟动词或介词也视为人名（张伟在北京……）
+fn looks_like_name_context(previous: Option<&Token>, next: Option<&Token>) -> bool {
+    if previous.is_some_and(|t| NAME_PRECEDERS.contains(&t.word.as_str())) {
+        return true;
+    }
+    let Some(next) = next else {
+        return false;
+    };
+    if NAME_FOLLOWERS.contains(&next.word.as_str()) || NAME_TITLES.contains(&next.word.as_str()) {
+        return true;
+    }
+    let at_clause_start = previous.is_none_or(|t| t.tag == PosTag::Unknown && !t.word.chars().any(is_cjk));
+    at_clause_start && matches!(next.tag, PosTag::Verb | PosTag::Preposition)
+}
+
+/// 是否为汉字
+pub(super) fn is_cjk(ch: char) -> bool {
+    matches!(ch, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' | '〇')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged(segmenter: &Segmenter, text: &str) -> Vec<(String, &'static str)> {
+        segmenter.segment(text)
+            .into_iter()
+            .map(|t| (t.word, t.tag.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_segment_with_bundled_dictionary() {
+        let segmenter = Segmenter::shared();
+        let pairs = |items: &[(&str, &'static str)]| -> Vec<(String, &'static str)> {
+            items.iter().map(|(w, t)| (w.to_string(), *t)).collect()
+        };
+
+        assert_eq!(
+            tagged(&segmenter, "我和妈妈去了医院"),
+            pairs(&[("我", "r"), ("和", "c"), ("妈妈", "nr"), ("去", "v"), ("了", "u"), ("医院", "ns")])
+        );
+        assert_eq!(
+            tagged(&segmenter, "昨天下午，韩梅梅在北京买了iPhone 15"),
+            pairs(&[
+                ("昨天", "t"), ("下午", "t"), ("，", "x"), ("韩梅梅", "nr"), ("在", "p"),
+                ("北京", "ns"), ("买", "v"), ("了", "u"), ("iPhone", "eng"), ("15", "m"),
+            ])
+        );
+        assert_eq!(segmenter.cut("小王去了长江路"), vec!["小王", "去", "了", "长江路"]);
+    }
+
+    #[test]
+    fn test_user_dictionary_keeps_new_words_whole() {
+        let mut segmenter = Segmenter::new();
+        let loaded = segmenter.load_user_dict("# 自定义词\n螺蛳粉 n\n\n牛肉面 800 n\n阿福 nr\n").unwrap();
+        assert_eq!(loaded, 3);
+        assert_eq!(segmenter.cut("我想吃螺蛳粉和牛肉面"), vec!["我", "想", "吃", "螺蛳粉", "和", "牛肉面"]);
+        assert_eq!(tagged(&segmenter, "螺蛳粉"), vec![("螺蛳粉".to_string(), "n")]);
+        assert_eq!(segmenter.tag_of("阿福"), Some(PosTag::PersonName));
+
+        assert!(segmenter.load_user_dict("蛋挞 99 zz").is_err());
+    }
+
+    #[test]
+    fn test_surname_led_unknown_words_need_name_context() {
+        let segmenter = Segmenter::shared();
+        let person_names = |text: &str| -> Vec<String> {
+            segmenter.segment(text)
+                .into_iter()
+                .filter(|t| t.tag == PosTag::PersonName)
+                .map(|t| t.word)
+                .collect()
+        };
+
+        assert_eq!(person_names("我跟韩梅梅说了"), vec!["韩梅梅"]);
+        // 姓氏开头的常用词不是人名
+        assert!(person_names("谢谢你").is_empty());
+        assert!(person_names("我喜欢黄色的").is_empty());
+        assert!(person_names("提示余额不足").is_empty());
+        assert!(person_names("明天方便吗").is_empty());
+    }
+}
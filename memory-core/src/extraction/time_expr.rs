@@ -0,0 +1,1117 @@
+//! 时间表达式解析
+//!
+//! 识别中英文的相对与绝对时间表达（"昨天下午三点"、"上周六"、"下个月"、"2025年春节"、
+//! "last Friday"、"March 5th"、"3 days ago"），以消息时间为基准解析为具体时间，
+//! 用于设置事件时间并生成带规范化取值的时间实体。
+//!
+//! 相邻的片段（"去年" + "3月" + "5号"、"yesterday" + "at" + "3pm"）合并为一个表达式；
+//! 单独的时段词（"晚上"、"morning"）和不带上下文的"三点"不视为时间表达
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// 时间粒度（从粗到细）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeGranularity {
+    /// 年
+    Year,
+    /// 月
+    Month,
+    /// 周
+    Week,
+    /// 日
+    Day,
+    /// 具体时刻
+    Minute,
+}
+
+impl TimeGranularity {
+    /// 粒度名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeGranularity::Year => "year",
+            TimeGranularity::Month => "month",
+            TimeGranularity::Week => "week",
+            TimeGranularity::Day => "day",
+            TimeGranularity::Minute => "minute",
+        }
+    }
+}
+
+/// 解析出的时间表达式
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeExpression {
+    /// 原文
+    pub text: String,
+    /// 在原文中的起始字符位置
+    pub start: usize,
+    /// 所指时间段的起点
+    pub value: NaiveDateTime,
+    /// 粒度
+    pub granularity: TimeGranularity,
+}
+
+impl TimeExpression {
+    /// 事件时间格式（YYYY-MM-DD-HH-MM）
+    pub fn event_time(&self) -> String {
+        self.value.format("%Y-%m-%d-%H-%M").to_string()
+    }
+
+    /// 规范化取值：按粒度为 `2025`、`2025-03`、`2025-W10`、`2025-03-05` 或 `2025-03-05 14:30`
+    pub fn normalized(&self) -> String {
+        match self.granularity {
+            TimeGranularity::Year => self.value.year().to_string(),
+            TimeGranularity::Month => self.value.format("%Y-%m").to_string(),
+            TimeGranularity::Week => {
+                let week = self.value.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            TimeGranularity::Day => self.value.format("%Y-%m-%d").to_string(),
+            TimeGranularity::Minute => self.value.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+
+    /// 所指时间段是否包含某一时刻
+    pub fn contains(&self, time: NaiveDateTime) -> bool {
+        let end = match self.granularity {
+            TimeGranularity::Year => self.value.checked_add_months(Months::new(12)),
+            TimeGranularity::Month => self.value.checked_add_months(Months::new(1)),
+            TimeGranularity::Week => self.value.checked_add_signed(Duration::days(7)),
+            TimeGranularity::Day => self.value.checked_add_signed(Duration::days(1)),
+            TimeGranularity::Minute => self.value.checked_add_signed(Duration::minutes(1)),
+        };
+        self.value <= time && end.is_some_and(|end| time < end)
+    }
+}
+
+/// 解析文本中的所有时间表达式
+///
+/// # Arguments
+/// * `reference` - 基准时间（通常是消息时间），相对表达以它为准
+pub fn parse_time_expressions(text: &str, reference: NaiveDateTime) -> Vec<TimeExpression> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut components = scan_chinese(&chars);
+    components.extend(scan_english(&chars));
+
+    group_components(components, &chars)
+        .into_iter()
+        .filter(|group| is_anchored(&group.parts))
+        .filter_map(|group| {
+            let (value, granularity) = resolve(&group.parts, reference)?;
+            Some(TimeExpression {
+                text: chars[group.start..group.end].iter().collect(),
+                start: group.start,
+                value,
+                granularity,
+            })
+        })
+        .collect()
+}
+
+/// 从文本推断事件时间
+///
+/// 取粒度最细的时间表达式（同粒度取最先出现的）；没有时间表达式，
+/// 或表达式只是"今天""this week"这类包含基准时间的时间段时返回 None，由调用方沿用消息时间
+pub fn resolve_event_time(text: &str, reference: NaiveDateTime) -> Option<String> {
+    parse_time_expressions(text, reference)
+        .into_iter()
+        .filter(|expr| expr.granularity == TimeGranularity::Minute || !expr.contains(reference))
+        .max_by(|a, b| a.granularity.cmp(&b.granularity).then(b.start.cmp(&a.start)))
+        .map(|expr| expr.event_time())
+}
+
+// ============================================
+// 片段
+// ============================================
+
+/// 相对偏移量的上限（"99999天前"之类更大的数不作为时间表达）
+const MAX_SHIFT_AMOUNT: u64 = 10_000;
+
+/// 时间单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// 节日
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Festival {
+    NewYear,
+    Spring,
+    NewYearsEve,
+    Lantern,
+    Valentine,
+    Labor,
+    DragonBoat,
+    MidAutumn,
+    National,
+    ChristmasEve,
+    Christmas,
+}
+
+/// 农历节日的公历日期：年份及该年的 [春节, 端午, 中秋]（月, 日）
+const LUNAR_FESTIVALS: &[(i32, [(u32, u32); 3])] = &[
+    (2020, [(1, 25), (6, 25), (10, 1)]),
+    (2021, [(2, 12), (6, 14), (9, 21)]),
+    (2022, [(2, 1), (6, 3), (9, 10)]),
+    (2023, [(1, 22), (6, 22), (9, 29)]),
+    (2024, [(2, 10), (6, 10), (9, 17)]),
+    (2025, [(1, 29), (5, 31), (10, 6)]),
+    (2026, [(2, 17), (6, 19), (9, 25)]),
+    (2027, [(2, 6), (6, 9), (9, 15)]),
+    (2028, [(1, 26), (5, 28), (10, 3)]),
+    (2029, [(2, 13), (6, 16), (9, 22)]),
+    (2030, [(2, 3), (6, 5), (9, 12)]),
+];
+
+impl Festival {
+    /// 某年的节日日期（农历节日超出内置年份范围时返回 None）
+    fn date(self, year: i32) -> Option<NaiveDate> {
+        let lunar = |index: usize| {
+            LUNAR_FESTIVALS.iter()
+                .find(|(y, _)| *y == year)
+                .and_then(|(_, dates)| {
+                    let (month, day) = dates[index];
+                    NaiveDate::from_ymd_opt(year, month, day)
+                })
+        };
+        match self {
+            Festival::NewYear => NaiveDate::from_ymd_opt(year, 1, 1),
+            Festival::Spring => lunar(0),
+            Festival::NewYearsEve => lunar(0).map(|d| d - Duration::days(1)),
+            Festival::Lantern => lunar(0).map(|d| d + Duration::days(14)),
+            Festival::Valentine => NaiveDate::from_ymd_opt(year, 2, 14),
+            Festival::Labor => NaiveDate::from_ymd_opt(year, 5, 1),
+            Festival::DragonBoat => lunar(1),
+            Festival::MidAutumn => lunar(2),
+            Festival::National => NaiveDate::from_ymd_opt(year, 10, 1),
+            Festival::ChristmasEve => NaiveDate::from_ymd_opt(year, 12, 24),
+            Festival::Christmas => NaiveDate::from_ymd_opt(year, 12, 25),
+        }
+    }
+}
+
+/// 时间表达式的组成片段
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part {
+    /// 绝对年份
+    Year(i32),
+    /// 相对年份（去年 = -1）
+    YearOffset(i32),
+    /// 月份
+    Month(u32),
+    /// 相对月份（上个月 = -1）
+    MonthOffset(i32),
+    /// 日
+    Day(u32),
+    /// 相对天数（昨天 = -1）
+    DayOffset(i64),
+    /// 相对周（上周 = -1），可带星期几（1 = 周一，7 = 周日）
+    Week { offset: i64, weekday: Option<u32> },
+    /// 最近一个已到的星期几（英文单独的 "Friday"）
+    PastWeekday(u32),
+    /// 节日
+    Festival(Festival),
+    /// 完整日期
+    Date(NaiveDate),
+    /// 相对基准时间的偏移（三天前 = -3 天）
+    Shift { amount: i64, unit: Unit },
+    /// 时段（取该时段的默认钟点）
+    Period(u32),
+    /// 钟点；`explicit` 表示带冒号或 am/pm，可以脱离日期单独成立
+    Clock { hour: u32, minute: u32, pm: Option<bool>, explicit: bool },
+}
+
+impl Part {
+    /// 所属槽位：同一表达式中每个槽位只出现一次
+    fn slot(&self) -> u8 {
+        match self {
+            Part::Year(_) | Part::YearOffset(_) => 0,
+            Part::Month(_) | Part::MonthOffset(_) => 1,
+            Part::Period(_) => 3,
+            Part::Clock { .. } => 4,
+            _ => 2,
+        }
+    }
+}
+
+/// 文本中的一个片段（字符位置）
+#[derive(Debug, Clone)]
+struct Component {
+    start: usize,
+    end: usize,
+    parts: Vec<Part>,
+}
+
+/// 合并相邻片段，重叠的片段保留较长者
+fn group_components(mut components: Vec<Component>, chars: &[char]) -> Vec<Component> {
+    components.sort_by(|a, b| a.start.cmp(&b.start).then((b.end - b.start).cmp(&(a.end - a.start))));
+
+    let mut groups: Vec<Component> = Vec::new();
+    let mut last_end = 0;
+    for component in components {
+        if component.start < last_end {
+            continue;
+        }
+        last_end = component.end;
+
+        if let Some(group) = groups.last_mut() {
+            let gap: String = chars[group.end..component.start].iter().collect();
+            let slots_free = component.parts.iter()
+                .all(|p| group.parts.iter().all(|q| q.slot() != p.slot()));
+            if slots_free && is_joining_gap(&gap) {
+                group.end = component.end;
+                group.parts.extend(component.parts);
+                continue;
+            }
+        }
+        groups.push(component);
+    }
+    groups
+}
+
+/// 两个片段之间的文字是否允许把它们合并
+fn is_joining_gap(gap: &str) -> bool {
+    gap.split(|c: char| c.is_whitespace() || matches!(c, ',' | '，' | '的'))
+        .filter(|w| !w.is_empty())
+        .all(|w| matches!(w.to_ascii_lowercase().as_str(), "at" | "on" | "in" | "the" | "of" | "around"))
+}
+
+/// 表达式是否锚定到具体时间：单独的时段词或不带冒号/am/pm 的钟点不算
+fn is_anchored(parts: &[Part]) -> bool {
+    let has_period = parts.iter().any(|p| matches!(p, Part::Period(_)));
+    parts.iter().any(|p| match p {
+        Part::Period(_) => false,
+        Part::Clock { explicit, .. } => *explicit || has_period,
+        _ => true,
+    })
+}
+
+// ============================================
+// 解析
+// ============================================
+
+/// 以基准时间解析一组片段
+fn resolve(parts: &[Part], reference: NaiveDateTime) -> Option<(NaiveDateTime, TimeGranularity)> {
+    let today = reference.date();
+
+    let mut year = None;
+    let mut month = None;
+    for part in parts {
+        match *part {
+            Part::Year(y) => year = Some(y),
+            Part::YearOffset(offset) => year = Some(today.year() + offset),
+            Part::MonthOffset(offset) => {
+                let first = shift_months(today.with_day(1)?, offset)?;
+                year = Some(first.year());
+                month = Some(first.month());
+            }
+            Part::Month(m) => month = Some(m),
+            _ => {}
+        }
+    }
+
+    let mut day = None;
+    for part in parts {
+        let resolved = match *part {
+            Part::Day(d) => NaiveDate::from_ymd_opt(
+                year.unwrap_or(today.year()),
+                month.unwrap_or(today.month()),
+                d,
+            ).map(|date| (date, TimeGranularity::Day)),
+            Part::DayOffset(offset) => today
+                .checked_add_signed(Duration::days(offset))
+                .map(|date| (date, TimeGranularity::Day)),
+            Part::Week { offset, weekday } => {
+                let monday = today
+                    .checked_sub_signed(Duration::days(today.weekday().num_days_from_monday() as i64))?
+                    .checked_add_signed(Duration::weeks(offset))?;
+                match weekday {
+                    Some(w) => monday
+                        .checked_add_signed(Duration::days(w as i64 - 1))
+                        .map(|date| (date, TimeGranularity::Day)),
+                    None => Some((monday, TimeGranularity::Week)),
+                }
+            }
+            Part::PastWeekday(w) => {
+                let back = (today.weekday().number_from_monday() + 7 - w) % 7;
+                today
+                    .checked_sub_signed(Duration::days(back as i64))
+                    .map(|date| (date, TimeGranularity::Day))
+            }
+            Part::Festival(festival) => festival
+                .date(year.unwrap_or(today.year()))
+                .map(|date| (date, TimeGranularity::Day)),
+            Part::Date(date) => Some((date, TimeGranularity::Day)),
+            Part::Shift { amount, unit } => return shift(reference, amount, unit),
+            _ => continue,
+        };
+        day = Some(resolved?);
+    }
+
+    let (date, mut granularity) = match (day, year, month) {
+        (Some(day), _, _) => day,
+        (None, year, Some(month)) => (
+            NaiveDate::from_ymd_opt(year.unwrap_or(today.year()), month, 1)?,
+            TimeGranularity::Month,
+        ),
+        (None, Some(year), None) => (NaiveDate::from_ymd_opt(year, 1, 1)?, TimeGranularity::Year),
+        (None, None, None) => (today, TimeGranularity::Day),
+    };
+
+    let period = parts.iter().find_map(|p| match p {
+        Part::Period(hour) => Some(*hour),
+        _ => None,
+    });
+    let clock = parts.iter().find_map(|p| match p {
+        Part::Clock { hour, minute, pm, .. } => Some((*hour, *minute, *pm)),
+        _ => None,
+    });
+    let time = match (clock, period) {
+        (Some((hour, minute, pm)), period) => NaiveTime::from_hms_opt(clock_hour(hour, pm, period), minute, 0)?,
+        (None, Some(hour)) => NaiveTime::from_hms_opt(hour, 0, 0)?,
+        (None, None) => NaiveTime::MIN,
+    };
+    if clock.is_some() || period.is_some() {
+        granularity = TimeGranularity::Minute;
+    }
+
+    Some((date.and_time(time), granularity))
+}
+
+/// 结合 am/pm 与时段把钟点换算为 24 小时制（"下午三点" → 15，"夜里两点" → 2）
+fn clock_hour(hour: u32, pm: Option<bool>, period: Option<u32>) -> u32 {
+    match (pm, period) {
+        (Some(true), _) if hour < 12 => hour + 12,
+        (Some(false), _) if hour == 12 => 0,
+        (Some(_), _) => hour,
+        (None, Some(period)) if period >= 12 && hour < 12 => {
+            let small_hours = period >= 22 && hour <= 4;
+            let noon = period == 12 && hour >= 11;
+            if small_hours || noon { hour } else { hour + 12 }
+        }
+        _ => hour,
+    }
+}
+
+/// 相对基准时间偏移，结果截断到对应粒度的起点
+///
+/// 偏移量超过 `MAX_SHIFT_AMOUNT` 或结果超出可表示的日期范围时返回 None
+fn shift(reference: NaiveDateTime, amount: i64, unit: Unit) -> Option<(NaiveDateTime, TimeGranularity)> {
+    if amount.unsigned_abs() > MAX_SHIFT_AMOUNT {
+        return None;
+    }
+    let date = reference.date();
+    match unit {
+        Unit::Minute => reference
+            .checked_add_signed(Duration::minutes(amount))
+            .map(|time| (time, TimeGranularity::Minute)),
+        Unit::Hour => reference
+            .checked_add_signed(Duration::hours(amount))
+            .map(|time| (time, TimeGranularity::Minute)),
+        Unit::Day => date
+            .checked_add_signed(Duration::days(amount))
+            .map(|date| (date.and_time(NaiveTime::MIN), TimeGranularity::Day)),
+        Unit::Week => date
+            .checked_add_signed(Duration::weeks(amount))
+            .map(|date| (date.and_time(NaiveTime::MIN), TimeGranularity::Day)),
+        Unit::Month => {
+            let first = shift_months(date.with_day(1)?, i32::try_from(amount).ok()?)?;
+            Some((first.and_time(NaiveTime::MIN), TimeGranularity::Month))
+        }
+        Unit::Year => {
+            let year = date.year().checked_add(i32::try_from(amount).ok()?)?;
+            let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+            Some((first.and_time(NaiveTime::MIN), TimeGranularity::Year))
+        }
+    }
+}
+
+/// 按月偏移
+fn shift_months(date: NaiveDate, offset: i32) -> Option<NaiveDate> {
+    let months = Months::new(offset.unsigned_abs());
+    if offset >= 0 {
+        date.checked_add_months(months)
+    } else {
+        date.checked_sub_months(months)
+    }
+}
+
+// ============================================
+// 中文与数字
+// ============================================
+
+/// 固定的中文时间词
+const CN_KEYWORDS: &[(&str, &[Part])] = &[
+    ("大前天", &[Part::DayOffset(-3)]),
+    ("前天", &[Part::DayOffset(-2)]),
+    ("昨天", &[Part::DayOffset(-1)]),
+    ("昨日", &[Part::DayOffset(-1)]),
+    ("昨晚", &[Part::DayOffset(-1), Part::Period(20)]),
+    ("今天", &[Part::DayOffset(0)]),
+    ("今日", &[Part::DayOffset(0)]),
+    ("今早", &[Part::DayOffset(0), Part::Period(8)]),
+    ("今晚", &[Part::DayOffset(0), Part::Period(20)]),
+    ("明天", &[Part::DayOffset(1)]),
+    ("明日", &[Part::DayOffset(1)]),
+    ("明早", &[Part::DayOffset(1), Part::Period(8)]),
+    ("明晚", &[Part::DayOffset(1), Part::Period(20)]),
+    ("后天", &[Part::DayOffset(2)]),
+    ("大后天", &[Part::DayOffset(3)]),
+    ("大前年", &[Part::YearOffset(-3)]),
+    ("前年", &[Part::YearOffset(-2)]),
+    ("去年", &[Part::YearOffset(-1)]),
+    ("今年", &[Part::YearOffset(0)]),
+    ("明年", &[Part::YearOffset(1)]),
+    ("后年", &[Part::YearOffset(2)]),
+    ("上上个月", &[Part::MonthOffset(-2)]),
+    ("上个月", &[Part::MonthOffset(-1)]),
+    ("上月", &[Part::MonthOffset(-1)]),
+    ("这个月", &[Part::MonthOffset(0)]),
+    ("本月", &[Part::MonthOffset(0)]),
+    ("下个月", &[Part::MonthOffset(1)]),
+    ("下月", &[Part::MonthOffset(1)]),
+    ("凌晨", &[Part::Period(3)]),
+    ("早上", &[Part::Period(8)]),
+    ("早晨", &[Part::Period(8)]),
+    ("上午", &[Part::Period(9)]),
+    ("中午", &[Part::Period(12)]),
+    ("下午", &[Part::Period(15)]),
+    ("傍晚", &[Part::Period(18)]),
+    ("晚上", &[Part::Period(20)]),
+    ("夜里", &[Part::Period(22)]),
+    ("半夜", &[Part::Period(23)]),
+    ("春节", &[Part::Festival(Festival::Spring)]),
+    ("除夕", &[Part::Festival(Festival::NewYearsEve)]),
+    ("元宵节", &[Part::Festival(Festival::Lantern)]),
+    ("元宵", &[Part::Festival(Festival::Lantern)]),
+    ("元旦", &[Part::Festival(Festival::NewYear)]),
+    ("情人节", &[Part::Festival(Festival::Valentine)]),
+    ("劳动节", &[Part::Festival(Festival::Labor)]),
+    ("五一", &[Part::Festival(Festival::Labor)]),
+    ("端午节", &[Part::Festival(Festival::DragonBoat)]),
+    ("端午", &[Part::Festival(Festival::DragonBoat)]),
+    ("中秋节", &[Part::Festival(Festival::MidAutumn)]),
+    ("中秋", &[Part::Festival(Festival::MidAutumn)]),
+    ("国庆节", &[Part::Festival(Festival::National)]),
+    ("国庆", &[Part::Festival(Festival::National)]),
+    ("平安夜", &[Part::Festival(Festival::ChristmasEve)]),
+    ("圣诞节", &[Part::Festival(Festival::Christmas)]),
+    ("圣诞", &[Part::Festival(Festival::Christmas)]),
+];
+
+/// 周的前缀及相对周数（单独的"周""星期"必须带星期几）
+const CN_WEEK_PREFIXES: &[(&str, i64)] = &[
+    ("上上个星期", -2), ("上上周", -2),
+    ("上个星期", -1), ("上个礼拜", -1), ("上星期", -1), ("上礼拜", -1), ("上周", -1),
+    ("这个星期", 0), ("这个礼拜", 0), ("这星期", 0), ("这礼拜", 0), ("这周", 0), ("本周", 0),
+    ("下个星期", 1), ("下个礼拜", 1), ("下星期", 1), ("下礼拜", 1), ("下周", 1),
+    ("星期", 0), ("礼拜", 0), ("周", 0),
+];
+
+/// 相对偏移的单位
+const CN_UNITS: &[(&str, Unit)] = &[
+    ("个小时", Unit::Hour), ("个钟头", Unit::Hour), ("小时", Unit::Hour), ("分钟", Unit::Minute),
+    ("天", Unit::Day), ("个星期", Unit::Week), ("个礼拜", Unit::Week), ("星期", Unit::Week),
+    ("周", Unit::Week), ("个月", Unit::Month), ("年", Unit::Year),
+];
+
+/// 相对偏移的方向
+const CN_DIRECTIONS: &[(&str, i64)] = &[
+    ("以前", -1), ("之前", -1), ("前", -1), ("以后", 1), ("之后", 1), ("后", 1),
+];
+
+/// 扫描中文时间词及数字日期、钟点
+fn scan_chinese(chars: &[char]) -> Vec<Component> {
+    let mut components = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let number_boundary = i == 0 || !is_number_char(chars[i - 1]);
+        let matched = (number_boundary.then(|| match_numeric(chars, i)).flatten())
+            .into_iter()
+            .chain(match_cn_keyword(chars, i))
+            .max_by_key(|(len, _)| *len);
+        match matched {
+            Some((len, parts)) => {
+                components.push(Component { start: i, end: i + len, parts });
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+    components
+}
+
+/// 匹配固定时间词与"上周六"等周表达
+fn match_cn_keyword(chars: &[char], i: usize) -> Option<(usize, Vec<Part>)> {
+    let keyword = CN_KEYWORDS.iter()
+        .filter(|(word, _)| starts_with(chars, i, word))
+        .max_by_key(|(word, _)| word.chars().count())
+        .map(|(word, parts)| (word.chars().count(), parts.to_vec()));
+
+    let week = CN_WEEK_PREFIXES.iter()
+        .filter(|(prefix, _)| starts_with(chars, i, prefix))
+        .max_by_key(|(prefix, _)| prefix.chars().count())
+        .and_then(|(prefix, offset)| {
+            let len = prefix.chars().count();
+            let weekday = chars.get(i + len).and_then(|c| match c {
+                '一' => Some(1), '二' => Some(2), '三' => Some(3), '四' => Some(4),
+                '五' => Some(5), '六' | '末' => Some(6), '日' | '天' => Some(7),
+                _ => None,
+            });
+            match weekday {
+                Some(w) => Some((len + 1, vec![Part::Week { offset: *offset, weekday: Some(w) }])),
+                None if !matches!(*prefix, "星期" | "礼拜" | "周") => {
+                    Some((len, vec![Part::Week { offset: *offset, weekday: None }]))
+                }
+                None => None,
+            }
+        });
+
+    keyword.into_iter().chain(week).max_by_key(|(len, _)| *len)
+}
+
+/// 匹配以数字开头的表达：ISO 日期、HH:MM、"2025年"、"3月"、"5号"、"三点半"、"三天前"
+fn match_numeric(chars: &[char], i: usize) -> Option<(usize, Vec<Part>)> {
+    if let Some(matched) = match_iso_date(chars, i).or_else(|| match_colon_clock(chars, i)) {
+        return Some(matched);
+    }
+
+    // "半个月前"、"半小时后"
+    if chars[i] == '半' {
+        let (unit_len, unit) = match_table(chars, i + 1, CN_UNITS)?;
+        let (dir_len, direction) = match_table(chars, i + 1 + unit_len, CN_DIRECTIONS)?;
+        let (amount, unit) = match unit {
+            Unit::Hour => (30, Unit::Minute),
+            Unit::Day => (12, Unit::Hour),
+            Unit::Month => (15, Unit::Day),
+            Unit::Year => (6, Unit::Month),
+            _ => return None,
+        };
+        return Some((1 + unit_len + dir_len, vec![Part::Shift { amount: amount * direction, unit }]));
+    }
+
+    let (value, len) = read_number(chars, i)?;
+    let after = i + len;
+
+    if let Some((unit_len, unit)) = match_table(chars, after, CN_UNITS) {
+        if let Some((dir_len, direction)) = match_table(chars, after + unit_len, CN_DIRECTIONS) {
+            let part = Part::Shift { amount: value as i64 * direction, unit };
+            return Some((len + unit_len + dir_len, vec![part]));
+        }
+    }
+
+    match chars.get(after)? {
+        '年' if len >= 4 && (1900..=2100).contains(&value) => Some((len + 1, vec![Part::Year(value as i32)])),
+        '月' if (1..=12).contains(&value) => {
+            let len = if chars.get(after + 1) == Some(&'份') { len + 2 } else { len + 1 };
+            Some((len, vec![Part::Month(value)]))
+        }
+        '日' | '号' if (1..=31).contains(&value) => Some((len + 1, vec![Part::Day(value)])),
+        '点' if value <= 24 => {
+            let mut end = after + 1;
+            if chars.get(end) == Some(&'钟') {
+                end += 1;
+            }
+            let mut minute = 0;
+            if chars.get(end) == Some(&'半') {
+                minute = 30;
+                end += 1;
+            } else if starts_with(chars, end, "一刻") || starts_with(chars, end, "三刻") {
+                minute = if chars[end] == '一' { 15 } else { 45 };
+                end += 2;
+            } else if let Some((m, m_len)) = read_number(chars, end) {
+                if m < 60 && chars.get(end + m_len) == Some(&'分') {
+                    minute = m;
+                    end += m_len + 1;
+                }
+            }
+            let part = Part::Clock { hour: value % 24, minute, pm: None, explicit: false };
+            Some((end - i, vec![part]))
+        }
+        _ => None,
+    }
+}
+
+/// 匹配 "2025-03-05" 或 "2025/3/5"
+fn match_iso_date(chars: &[char], i: usize) -> Option<(usize, Vec<Part>)> {
+    let (year, year_len) = read_digits(chars, i)?;
+    if year_len != 4 {
+        return None;
+    }
+    let separator = *chars.get(i + 4).filter(|c| matches!(c, '-' | '/'))?;
+    let (month, month_len) = read_digits(chars, i + 5).filter(|(_, len)| *len <= 2)?;
+    if chars.get(i + 5 + month_len) != Some(&separator) {
+        return None;
+    }
+    let (day, day_len) = read_digits(chars, i + 6 + month_len).filter(|(_, len)| *len <= 2)?;
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    Some((6 + month_len + day_len, vec![Part::Date(date)]))
+}
+
+/// 匹配 "14:30"
+fn match_colon_clock(chars: &[char], i: usize) -> Option<(usize, Vec<Part>)> {
+    let (hour, hour_len) = read_digits(chars, i).filter(|(h, len)| *len <= 2 && *h < 24)?;
+    if chars.get(i + hour_len) != Some(&':') {
+        return None;
+    }
+    let (minute, minute_len) = read_digits(chars, i + hour_len + 1).filter(|(m, len)| *len == 2 && *m < 60)?;
+    let part = Part::Clock { hour, minute, pm: None, explicit: true };
+    Some((hour_len + 1 + minute_len, vec![part]))
+}
+
+/// 读取阿拉伯数字或中文数字（"2025"、"二〇二五"、"十二"、"两"）
+fn read_number(chars: &[char], i: usize) -> Option<(u32, usize)> {
+    if let Some(digits) = read_digits(chars, i) {
+        return Some(digits);
+    }
+    let len = chars[i..].iter().take_while(|c| cn_digit(**c).is_some() || matches!(c, '十' | '百')).count();
+    if len == 0 {
+        return None;
+    }
+    let run = &chars[i..i + len];
+
+    let value = if run.iter().any(|c| matches!(c, '十' | '百')) {
+        let (mut value, mut current) = (0u32, 0u32);
+        for c in run {
+            match c {
+                '十' => {
+                    value = value.checked_add(current.max(1) * 10)?;
+                    current = 0;
+                }
+                '百' => {
+                    value = value.checked_add(current.max(1) * 100)?;
+                    current = 0;
+                }
+                c => current = cn_digit(*c)?,
+            }
+        }
+        value.checked_add(current)?
+    } else {
+        run.iter().try_fold(0u32, |acc, c| acc.checked_mul(10)?.checked_add(cn_digit(*c)?))?
+    };
+    Some((value, len))
+}
+
+/// 读取连续的阿拉伯数字
+fn read_digits(chars: &[char], i: usize) -> Option<(u32, usize)> {
+    let len = chars.get(i..)?.iter().take_while(|c| c.is_ascii_digit()).count();
+    if len == 0 || len > 9 {
+        return None;
+    }
+    let value = chars[i..i + len].iter().collect::<String>().parse().ok()?;
+    Some((value, len))
+}
+
+/// 中文数字字符的值
+fn cn_digit(c: char) -> Option<u32> {
+    let value = match c {
+        '零' | '〇' => 0,
+        '一' => 1,
+        '二' | '两' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// 是否为数字字符（用于避免从数字中间开始匹配）
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit() || cn_digit(c).is_some() || matches!(c, '十' | '百')
+}
+
+/// 在位置 i 匹配表中最长的词
+fn match_table<T: Copy>(chars: &[char], i: usize, table: &[(&str, T)]) -> Option<(usize, T)> {
+    table.iter()
+        .filter(|(word, _)| starts_with(chars, i, word))
+        .max_by_key(|(word, _)| word.chars().count())
+        .map(|(word, value)| (word.chars().count(), *value))
+}
+
+/// 从位置 i 起是否以给定词开头
+fn starts_with(chars: &[char], i: usize, word: &str) -> bool {
+    let mut rest = chars.get(i..).unwrap_or_default().iter();
+    word.chars().all(|c| rest.next() == Some(&c))
+}
+
+// ============================================
+// 英文
+// ============================================
+
+/// 英文单词（字符位置与小写形式）
+struct Word {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+const WEEKDAYS: &[&str] = &["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+const MONTHS: &[(&str, u32)] = &[
+    ("january", 1), ("jan", 1), ("february", 2), ("feb", 2), ("march", 3), ("mar", 3),
+    ("april", 4), ("apr", 4), ("may", 5), ("june", 6), ("jun", 6), ("july", 7), ("jul", 7),
+    ("august", 8), ("aug", 8), ("september", 9), ("sept", 9), ("sep", 9), ("october", 10),
+    ("oct", 10), ("november", 11), ("nov", 11), ("december", 12), ("dec", 12),
+];
+
+const EN_UNITS: &[(&str, Unit)] = &[
+    ("minute", Unit::Minute), ("minutes", Unit::Minute), ("hour", Unit::Hour), ("hours", Unit::Hour),
+    ("day", Unit::Day), ("days", Unit::Day), ("week", Unit::Week), ("weeks", Unit::Week),
+    ("month", Unit::Month), ("months", Unit::Month), ("year", Unit::Year), ("years", Unit::Year),
+];
+
+const EN_PERIODS: &[(&str, u32)] = &[("morning", 9), ("afternoon", 15), ("evening", 19), ("night", 21)];
+
+/// 扫描英文时间表达
+fn scan_english(chars: &[char]) -> Vec<Component> {
+    let words = english_words(chars);
+    let mut components = Vec::new();
+    let mut j = 0;
+    while j < words.len() {
+        match match_english(&words, j) {
+            Some((count, parts)) => {
+                components.push(Component {
+                    start: words[j].start,
+                    end: words[j + count - 1].end,
+                    parts,
+                });
+                j += count;
+            }
+            None => j += 1,
+        }
+    }
+    components
+}
+
+/// 切分英文单词（保留撇号、连字符与冒号，便于识别 "new year's"、"3:30pm"）
+fn english_words(chars: &[char]) -> Vec<Word> {
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '\'' | '-' | ':');
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_word_char(chars[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_word_char(chars[i]) {
+            i += 1;
+        }
+        let text: String = chars[start..i].iter().collect::<String>().to_ascii_lowercase();
+        let text = text.trim_end_matches([':', '-']).to_string();
+        if !text.is_empty() {
+            words.push(Word { start, end: start + text.chars().count(), text });
+        }
+    }
+    words
+}
+
+/// 在第 j 个单词处匹配英文时间表达，返回占用的单词数与片段
+fn match_english(words: &[Word], j: usize) -> Option<(usize, Vec<Part>)> {
+    let word = |k: usize| words.get(j + k).map(|w| w.text.as_str());
+    let is_seq = |seq: &[&str]| seq.iter().enumerate().all(|(k, s)| word(k) == Some(*s));
+
+    for (seq, offset) in [
+        (&["the", "day", "before", "yesterday"][..], -2),
+        (&["day", "before", "yesterday"][..], -2),
+        (&["the", "day", "after", "tomorrow"][..], 2),
+        (&["day", "after", "tomorrow"][..], 2),
+    ] {
+        if is_seq(seq) {
+            return Some((seq.len(), vec![Part::DayOffset(offset)]));
+        }
+    }
+
+    if let Some(matched) = match_festival(words, j) {
+        return Some(matched);
+    }
+
+    let first = word(0)?;
+    match first {
+        "today" => return Some((1, vec![Part::DayOffset(0)])),
+        "tonight" => return Some((1, vec![Part::DayOffset(0), Part::Period(21)])),
+        "yesterday" => return Some((1, vec![Part::DayOffset(-1)])),
+        "tomorrow" => return Some((1, vec![Part::DayOffset(1)])),
+        "noon" => return Some((1, vec![Part::Clock { hour: 12, minute: 0, pm: None, explicit: true }])),
+        "midnight" => return Some((1, vec![Part::Clock { hour: 0, minute: 0, pm: None, explicit: true }])),
+        "last" | "this" | "next" => {
+            let offset = match first {
+                "last" => -1,
+                "this" => 0,
+                _ => 1,
+            };
+            return match_relative(words, j, offset);
+        }
+        _ => {}
+    }
+
+    if let Some(w) = WEEKDAYS.iter().position(|d| *d == first) {
+        return Some((1, vec![Part::PastWeekday(w as u32 + 1)]));
+    }
+    if let Some((_, hour)) = EN_PERIODS.iter().find(|(p, _)| *p == first) {
+        return Some((1, vec![Part::Period(*hour)]));
+    }
+    if let Some(matched) = match_month_date(words, j) {
+        return Some(matched);
+    }
+    if let Some(matched) = match_english_clock(words, j) {
+        return Some(matched);
+    }
+
+    // "3 days ago"、"in 2 weeks"、"in 2023"
+    let (amount_index, direction) = if first == "in" { (1, 1) } else { (0, -1) };
+    if let Some(amount) = word(amount_index).and_then(english_amount) {
+        if let Some((_, unit)) = word(amount_index + 1).and_then(|u| EN_UNITS.iter().find(|(name, _)| *name == u)) {
+            if direction == 1 || word(amount_index + 2) == Some("ago") {
+                return Some((3, vec![Part::Shift { amount: amount * direction, unit: *unit }]));
+            }
+        }
+    }
+    if matches!(first, "in" | "since" | "during") {
+        if let Some(year) = word(1).and_then(parse_year) {
+            return Some((2, vec![Part::Year(year)]));
+        }
+    }
+    None
+}
+
+/// "last/this/next" 之后的周、月、年、星期几、时段与节日
+fn match_relative(words: &[Word], j: usize, offset: i64) -> Option<(usize, Vec<Part>)> {
+    let next = words.get(j + 1)?.text.as_str();
+    if let Some(w) = WEEKDAYS.iter().position(|d| *d == next) {
+        return Some((2, vec![Part::Week { offset, weekday: Some(w as u32 + 1) }]));
+    }
+    let parts = match next {
+        "week" => vec![Part::Week { offset, weekday: None }],
+        "weekend" => vec![Part::Week { offset, weekday: Some(6) }],
+        "month" => vec![Part::MonthOffset(offset as i32)],
+        "year" => vec![Part::YearOffset(offset as i32)],
+        "night" if offset <= 0 => vec![Part::DayOffset(offset), Part::Period(21)],
+        period if offset == 0 => {
+            let (_, hour) = EN_PERIODS.iter().find(|(p, _)| *p == period)?;
+            vec![Part::DayOffset(0), Part::Period(*hour)]
+        }
+        _ => {
+            let (count, mut parts) = match_festival(words, j + 1)?;
+            parts.insert(0, Part::YearOffset(offset as i32));
+            return Some((count + 1, parts));
+        }
+    };
+    Some((2, parts))
+}
+
+/// 英文节日（可带年份："Christmas 2024"）
+fn match_festival(words: &[Word], j: usize) -> Option<(usize, Vec<Part>)> {
+    let word = |k: usize| words.get(j + k).map(|w| w.text.as_str());
+    let (count, festival) = match (word(0)?, word(1), word(2)) {
+        ("christmas", Some("eve"), _) => (2, Festival::ChristmasEve),
+        ("christmas", Some("day"), _) => (2, Festival::Christmas),
+        ("christmas", _, _) => (1, Festival::Christmas),
+        ("chinese", Some("new"), Some("year")) => (3, Festival::Spring),
+        ("spring", Some("festival"), _) => (2, Festival::Spring),
+        ("new", Some("year's"), Some("day")) => (3, Festival::NewYear),
+        ("new", Some("year's") | Some("year"), _) => (2, Festival::NewYear),
+        ("valentine's", Some("day"), _) => (2, Festival::Valentine),
+        ("mid-autumn", Some("festival"), _) => (2, Festival::MidAutumn),
+        _ => return None,
+    };
+    let mut parts = vec![Part::Festival(festival)];
+    if let Some(year) = word(count).and_then(parse_year) {
+        parts.push(Part::Year(year));
+        return Some((count + 1, parts));
+    }
+    Some((count, parts))
+}
+
+/// "March 5th, 2024"、"5 March"、"March 2024"、"in March"
+fn match_month_date(words: &[Word], j: usize) -> Option<(usize, Vec<Part>)> {
+    let month_of = |k: usize| {
+        let text = words.get(k)?.text.as_str();
+        MONTHS.iter().find(|(name, _)| *name == text).map(|(_, m)| *m)
+    };
+    let day_of = |k: usize| words.get(k).and_then(|w| parse_ordinal(&w.text));
+    let year_of = |k: usize| words.get(k).and_then(|w| parse_year(&w.text));
+
+    // 日在前："5 March 2024"、"5th of March"
+    if let Some(day) = day_of(j) {
+        let of = usize::from(words.get(j + 1).is_some_and(|w| w.text == "of"));
+        let month = month_of(j + 1 + of)?;
+        let mut parts = vec![Part::Day(day), Part::Month(month)];
+        let mut count = 2 + of;
+        if let Some(year) = year_of(j + count) {
+            parts.push(Part::Year(year));
+            count += 1;
+        }
+        return Some((count, parts));
+    }
+
+    // 月在前："March 5th, 2024"、"March 2024"、"March"
+    let month = month_of(j)?;
+    let mut parts = vec![Part::Month(month)];
+    let mut count = 1;
+    if let Some(day) = day_of(j + 1) {
+        parts.push(Part::Day(day));
+        count += 1;
+    }
+    if let Some(year) = year_of(j + count) {
+        parts.push(Part::Year(year));
+        count += 1;
+    }
+    // "may" 也是情态动词，"mar"、"jan" 等缩写容易误判，单独出现时不算
+    let bare = count == 1;
+    let full_name = MONTHS.iter().any(|(name, m)| *m == month && name.len() > 3 && *name == words[j].text);
+    if bare && (!full_name || words[j].text == "may") {
+        return None;
+    }
+    Some((count, parts))
+}
+
+/// "3pm"、"3:30 pm"、"7 o'clock"
+fn match_english_clock(words: &[Word], j: usize) -> Option<(usize, Vec<Part>)> {
+    let text = words[j].text.as_str();
+    let (number, suffix) = text.split_at(text.find(|c: char| !(c.is_ascii_digit() || c == ':')).unwrap_or(text.len()));
+    if number.is_empty() {
+        return None;
+    }
+    let (hour, minute) = match number.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None => (number.parse::<u32>().ok()?, 0),
+    };
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+
+    let meridiem = |s: &str| match s {
+        "am" => Some(false),
+        "pm" => Some(true),
+        _ => None,
+    };
+    let next = words.get(j + 1).map(|w| w.text.as_str());
+    let (count, pm, explicit) = match (suffix, next) {
+        ("", Some(s)) if meridiem(s).is_some() => (2, meridiem(s), true),
+        ("", Some("o'clock")) => (2, None, false),
+        ("", _) if number.contains(':') => (1, None, true),
+        (s, _) if meridiem(s).is_some() => (1, meridiem(s), true),
+        _ => return None,
+    };
+    if pm.is_some() && !(1..=12).contains(&hour) {
+        return None;
+    }
+    Some((count, vec![Part::Clock { hour, minute, pm, explicit }]))
+}
+
+/// 英文数量（数字、a/an、one 到 twelve）
+fn english_amount(word: &str) -> Option<i64> {
+    const NUMBERS: &[&str] = &[
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve",
+    ];
+    match word {
+        "a" | "an" => Some(1),
+        _ => word.parse::<i64>().ok()
+            .filter(|n| (1..1000).contains(n))
+            .or_else(|| NUMBERS.iter().position(|n| *n == word).map(|i| i as i64 + 1)),
+    }
+}
+
+/// 日期序数（"5"、"5th"、"1st"、"22nd"）
+fn parse_ordinal(word: &str) -> Option<u32> {
+    let digits = ["st", "nd", "rd", "th"].iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    digits.parse::<u32>().ok().filter(|d| (1..=31).contains(d))
+}
+
+/// 四位年份（1900 – 2100）
+fn parse_year(word: &str) -> Option<i32> {
+    if word.len() != 4 {
+        return None;
+    }
+    word.parse::<i32>().ok().filter(|y| (1900..=2100).contains(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025-03-12 10:00，星期三
+    fn reference() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 12).unwrap().and_hms_opt(10, 0, 0).unwrap()
+    }
+
+    fn normalized(text: &str) -> Vec<(String, String)> {
+        parse_time_expressions(text, reference())
+            .into_iter()
+            .map(|e| {
+                let value = e.normalized();
+                (e.text, value)
+            })
+            .collect()
+    }
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect()
+    }
+
+    #[test]
+    fn test_chinese_expressions() {
+        assert_eq!(normalized("上周六我去了北京"), pairs(&[("上周六", "2025-03-08")]));
+        assert_eq!(normalized("昨天下午三点开会"), pairs(&[("昨天下午三点", "2025-03-11 15:00")]));
+        assert_eq!(normalized("下个月搬家，2025年春节回了老家"), pairs(&[
+            ("下个月", "2025-04"),
+            ("2025年春节", "2025-01-29"),
+        ]));
+        assert_eq!(normalized("三天前和去年3月5号"), pairs(&[
+            ("三天前", "2025-03-09"),
+            ("去年3月5号", "2024-03-05"),
+        ]));
+        assert_eq!(normalized("下周三晚上八点半"), pairs(&[("下周三晚上八点半", "2025-03-19 20:30")]));
+        assert_eq!(normalized("周杰伦有三点建议"), pairs(&[]));
+    }
+
+    #[test]
+    fn test_english_expressions() {
+        assert_eq!(normalized("I saw her last Friday"), pairs(&[("last Friday", "2025-03-07")]));
+        assert_eq!(normalized("Born on March 5th, 2024."), pairs(&[("March 5th, 2024", "2024-03-05")]));
+        assert_eq!(normalized("3 days ago"), pairs(&[("3 days ago", "2025-03-09")]));
+        assert_eq!(normalized("yesterday at 3:30 pm"), pairs(&[("yesterday at 3:30 pm", "2025-03-11 15:30")]));
+        assert_eq!(normalized("on Monday morning"), pairs(&[("Monday morning", "2025-03-10 09:00")]));
+        assert_eq!(normalized("next week, Christmas 2024"), pairs(&[
+            ("next week", "2025-W12"),
+            ("Christmas 2024", "2024-12-25"),
+        ]));
+        assert_eq!(normalized("Good morning, I may buy 3 apples"), pairs(&[]));
+    }
+
+    #[test]
+    fn test_resolve_event_time() {
+        assert_eq!(resolve_event_time("今天好累", reference()), None);
+        assert_eq!(resolve_event_time("上周六我去了北京", reference()), Some("2025-03-08-00-00".to_string()));
+        assert_eq!(
+            resolve_event_time("I met Alice last Friday at 7pm", reference()),
+            Some("2025-03-07-19-00".to_string())
+        );
+        assert_eq!(resolve_event_time("随便聊聊", reference()), None);
+    }
+
+    #[test]
+    fn test_huge_offsets_are_ignored() {
+        assert_eq!(normalized("999999999天前"), pairs(&[]));
+        assert_eq!(normalized("999999999年后"), pairs(&[]));
+        assert_eq!(normalized("九九九九九九九九九九九天前"), pairs(&[]));
+        assert_eq!(normalized("999999999 days ago"), pairs(&[]));
+        assert_eq!(normalized("in 999999999 weeks"), pairs(&[]));
+        assert_eq!(resolve_event_time("999999999分钟前", reference()), None);
+        assert_eq!(normalized("10000天前"), pairs(&[("10000天前", "1997-10-25")]));
+    }
+}
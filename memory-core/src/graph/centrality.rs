@@ -0,0 +1,227 @@
+//! 全图中心性
+//!
+//! 计算每个节点的 PageRank 与介数中心性并归一化到 [0, 1]，
+//! 用于识别串联起大量记忆的人物、地点等关键节点
+
+use super::{KnowledgeGraph, PageRankConfig};
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::NodeIndexable;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+/// 综合得分中 PageRank 占比的默认值
+pub const DEFAULT_PAGERANK_WEIGHT: f64 = 0.5;
+
+/// 用中心性提升节点重要性时的最小增幅（低于此值不写回存储，避免为微小差异反复更新节点）
+pub const MIN_IMPORTANCE_GAIN: f32 = 0.01;
+
+/// 中心性计算配置
+#[derive(Debug, Clone)]
+pub struct CentralityConfig {
+    /// PageRank 参数
+    pub pagerank: PageRankConfig,
+    /// 综合得分中 PageRank 的占比（其余为介数中心性）
+    pub pagerank_weight: f64,
+    /// 介数中心性采样的源节点数（None 表示使用全部节点，图较大时应采样）
+    pub betweenness_samples: Option<usize>,
+}
+
+impl Default for CentralityConfig {
+    fn default() -> Self {
+        Self {
+            pagerank: PageRankConfig::default(),
+            pagerank_weight: DEFAULT_PAGERANK_WEIGHT,
+            betweenness_samples: Some(256),
+        }
+    }
+}
+
+/// 节点的中心性得分（均已按全图最大值归一化）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CentralityScores {
+    /// PageRank
+    pub pagerank: f64,
+    /// 介数中心性
+    pub betweenness: f64,
+}
+
+impl CentralityScores {
+    /// 按权重合成的综合得分
+    pub fn combined(&self, pagerank_weight: f64) -> f64 {
+        let w = pagerank_weight.clamp(0.0, 1.0);
+        w * self.pagerank + (1.0 - w) * self.betweenness
+    }
+}
+
+impl KnowledgeGraph {
+    /// 计算全部节点的中心性
+    pub fn compute_centrality(&self, config: &CentralityConfig) -> HashMap<Uuid, CentralityScores> {
+        let pagerank = normalize(self.pagerank(&config.pagerank).into_iter().collect());
+        let betweenness = self.betweenness_centrality(config.betweenness_samples);
+
+        self.graph.node_indices()
+            .map(|idx| {
+                let id = self.graph[idx];
+                let scores = CentralityScores {
+                    pagerank: pagerank.get(&id).copied().unwrap_or(0.0),
+                    betweenness: betweenness.get(&id).copied().unwrap_or(0.0),
+                };
+                (id, scores)
+            })
+            .collect()
+    }
+
+    /// 介数中心性（Brandes 算法，图按无向、无权处理）
+    ///
+    /// # Arguments
+    /// * `samples` - 采样的源节点数（恰好选取这么多个，按节点顺序等间隔分布）；None 表示精确计算
+    ///
+    /// # Returns
+    /// 按最大值归一化到 [0, 1] 的得分
+    pub fn betweenness_centrality(&self, samples: Option<usize>) -> HashMap<Uuid, f64> {
+        let bound = self.graph.node_bound();
+        let nodes: Vec<NodeIndex> = self.graph.node_indices().collect();
+        if nodes.is_empty() {
+            return HashMap::new();
+        }
+
+        let mut centrality = vec![0.0f64; bound];
+        let mut sigma = vec![0.0f64; bound];
+        let mut distance = vec![usize::MAX; bound];
+        let mut delta = vec![0.0f64; bound];
+        let mut predecessors: Vec<Vec<NodeIndex>> = vec![Vec::new(); bound];
+
+        for source in sample_sources(&nodes, samples) {
+            for &idx in &nodes {
+                let i = idx.index();
+                sigma[i] = 0.0;
+                distance[i] = usize::MAX;
+                delta[i] = 0.0;
+                predecessors[i].clear();
+            }
+            sigma[source.index()] = 1.0;
+            distance[source.index()] = 0;
+
+            // BFS 统计最短路径数
+            let mut order = Vec::with_capacity(nodes.len());
+            let mut queue = VecDeque::from([source]);
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                for w in self.graph.neighbors_undirected(v) {
+                    if distance[w.index()] == usize::MAX {
+                        distance[w.index()] = distance[v.index()] + 1;
+                        queue.push_back(w);
+                    }
+                    if distance[w.index()] == distance[v.index()] + 1 {
+                        sigma[w.index()] += sigma[v.index()];
+                        predecessors[w.index()].push(v);
+                    }
+                }
+            }
+
+            // 逆序累积依赖
+            for &w in order.iter().rev() {
+                for &v in &predecessors[w.index()] {
+                    delta[v.index()] += sigma[v.index()] / sigma[w.index()] * (1.0 + delta[w.index()]);
+                }
+                if w != source {
+                    centrality[w.index()] += delta[w.index()];
+                }
+            }
+        }
+
+        normalize(nodes.iter().map(|idx| (self.graph[*idx], centrality[idx.index()])).collect())
+    }
+}
+
+/// 从节点中等间隔选取 `samples` 个源节点（None、0 或不少于节点数时选取全部）
+fn sample_sources(nodes: &[NodeIndex], samples: Option<usize>) -> Vec<NodeIndex> {
+    match samples {
+        Some(k) if k > 0 && k < nodes.len() => (0..k).map(|i| nodes[i * nodes.len() / k]).collect(),
+        _ => nodes.to_vec(),
+    }
+}
+
+/// 按最大值归一化到 [0, 1]
+fn normalize(scores: HashMap<Uuid, f64>) -> HashMap<Uuid, f64> {
+    let max = scores.values().copied().fold(0.0f64, f64::max);
+    if max <= 0.0 {
+        return scores.into_keys().map(|id| (id, 0.0)).collect();
+    }
+    scores.into_iter().map(|(id, s)| (id, s / max)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+    use crate::types::Edge;
+
+    fn edge(source: Uuid, target: Uuid) -> Edge {
+        Edge::new(source, target, relation_types::PARTICIPATES_IN.to_string())
+    }
+
+    /// 星形：中心节点连接 4 个叶子
+    fn star() -> (KnowledgeGraph, Uuid, Vec<Uuid>) {
+        let mut graph = KnowledgeGraph::new();
+        let center = Uuid::new_v4();
+        let leaves: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for leaf in &leaves {
+            graph.add_edge(edge(center, *leaf));
+        }
+        (graph, center, leaves)
+    }
+
+    #[test]
+    fn test_betweenness_star_and_path() {
+        let (graph, center, leaves) = star();
+        let scores = graph.betweenness_centrality(None);
+        assert!((scores[&center] - 1.0).abs() < 1e-9);
+        assert!(leaves.iter().all(|leaf| scores[leaf] == 0.0));
+
+        // 链 a - b - c - d：中间两个节点的介数相同且最高
+        let mut graph = KnowledgeGraph::new();
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(edge(pair[0], pair[1]));
+        }
+        let scores = graph.betweenness_centrality(None);
+        assert!((scores[&ids[1]] - 1.0).abs() < 1e-9);
+        assert!((scores[&ids[2]] - 1.0).abs() < 1e-9);
+        assert_eq!(scores[&ids[0]], 0.0);
+    }
+
+    #[test]
+    fn test_sample_sources_count() {
+        let nodes: Vec<NodeIndex> = (0..511).map(NodeIndex::new).collect();
+        let sampled = sample_sources(&nodes, Some(256));
+        assert_eq!(sampled.len(), 256);
+        assert_eq!(sampled[0], nodes[0]);
+        assert!(sampled.windows(2).all(|w| w[0] < w[1]));
+
+        assert_eq!(sample_sources(&nodes, None).len(), 511);
+        assert_eq!(sample_sources(&nodes, Some(0)).len(), 511);
+        assert_eq!(sample_sources(&nodes[..10], Some(256)).len(), 10);
+    }
+
+    #[test]
+    fn test_compute_centrality_combined() {
+        let (graph, center, leaves) = star();
+        let scores = graph.compute_centrality(&CentralityConfig::default());
+
+        assert_eq!(scores.len(), 5);
+        let center_score = scores[&center];
+        assert!((center_score.pagerank - 1.0).abs() < 1e-9);
+        assert!((center_score.combined(0.5) - 1.0).abs() < 1e-9);
+        for leaf in &leaves {
+            assert!(scores[leaf].combined(0.5) < center_score.combined(0.5));
+        }
+    }
+
+    #[test]
+    fn test_sampled_betweenness_finds_hub() {
+        let (graph, center, _) = star();
+        let scores = graph.betweenness_centrality(Some(2));
+        assert!((scores[&center] - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,300 @@
+//! 社区发现
+//!
+//! 基于加权标签传播（Label Propagation）把知识图谱划分为若干社区，
+//! 每个社区对应用户记忆中的一个话题。"人物"之类连接大量节点的泛化概念
+//! 会把所有记忆连成一片，可以配置为在划分时忽略这类概念枢纽
+
+use super::KnowledgeGraph;
+use crate::types::NodeType;
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::{EdgeRef, NodeIndexable};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 社区发现配置
+#[derive(Debug, Clone)]
+pub struct CommunityConfig {
+    /// 标签传播最大轮数
+    pub max_iterations: usize,
+    /// 度数超过该值的概念节点视为枢纽并忽略（None 表示不忽略）
+    pub max_concept_degree: Option<usize>,
+    /// 社区最少成员数，更小的社区不返回
+    pub min_size: usize,
+    /// 每个社区返回的代表节点数
+    pub representatives: usize,
+}
+
+impl Default for CommunityConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 20,
+            max_concept_degree: Some(20),
+            min_size: 2,
+            representatives: 3,
+        }
+    }
+}
+
+/// 社区
+#[derive(Debug, Clone)]
+pub struct Community {
+    /// 社区编号（取成员中最小的节点 ID，图修改后只要社区仍包含该节点就保持不变）
+    pub id: Uuid,
+    /// 成员节点
+    pub members: Vec<Uuid>,
+    /// 代表节点（社区内加权度最高的节点，降序）
+    pub representatives: Vec<Uuid>,
+}
+
+/// 构建 节点 ID → 社区编号 的映射
+pub fn community_membership(communities: &[Community]) -> HashMap<Uuid, Uuid> {
+    communities.iter()
+        .flat_map(|c| c.members.iter().map(move |id| (*id, c.id)))
+        .collect()
+}
+
+impl KnowledgeGraph {
+    /// 社区发现
+    ///
+    /// 图按无向处理，每轮按节点顺序把节点标签更新为邻居中权重之和最大的标签，
+    /// 当前标签与最优标签并列时保持不变，直到没有节点改变标签
+    ///
+    /// # Returns
+    /// 按成员数降序（同规模按编号）排列的社区，被忽略的概念枢纽不属于任何社区
+    pub fn detect_communities(&self, config: &CommunityConfig) -> Vec<Community> {
+        let bound = self.graph.node_bound();
+        let ignored: Vec<bool> = (0..bound)
+            .map(|i| self.is_concept_hub(NodeIndex::new(i), config))
+            .collect();
+
+        let mut labels: Vec<usize> = (0..bound).collect();
+        let mut votes: HashMap<usize, f64> = HashMap::new();
+
+        for iteration in 0..config.max_iterations {
+            let mut changed = false;
+
+            for idx in self.graph.node_indices() {
+                if ignored[idx.index()] {
+                    continue;
+                }
+
+                votes.clear();
+                for edge in self.graph.edges_directed(idx, petgraph::Direction::Outgoing)
+                    .chain(self.graph.edges_directed(idx, petgraph::Direction::Incoming))
+                {
+                    let neighbor = if edge.source() == idx { edge.target() } else { edge.source() };
+                    if neighbor == idx || ignored[neighbor.index()] {
+                        continue;
+                    }
+                    *votes.entry(labels[neighbor.index()]).or_insert(0.0) +=
+                        edge.weight().weight.max(0.0) as f64;
+                }
+                if votes.is_empty() {
+                    continue;
+                }
+
+                let current = labels[idx.index()];
+                let best_weight = votes.values().copied().fold(f64::MIN, f64::max);
+                let keeps_current = votes.get(&current).is_some_and(|w| *w >= best_weight);
+                if keeps_current {
+                    continue;
+                }
+                let best_label = votes.iter()
+                    .filter(|(_, w)| **w >= best_weight)
+                    .map(|(label, _)| *label)
+                    .min()
+                    .unwrap_or(current);
+                labels[idx.index()] = best_label;
+                changed = true;
+            }
+
+            if !changed {
+                log::debug!("标签传播在第 {} 轮收敛", iteration + 1);
+                break;
+            }
+        }
+
+        // 按标签分组
+        let mut groups: HashMap<usize, Vec<NodeIndex>> = HashMap::new();
+        for idx in self.graph.node_indices() {
+            if !ignored[idx.index()] {
+                groups.entry(labels[idx.index()]).or_default().push(idx);
+            }
+        }
+
+        let mut communities: Vec<Community> = groups.into_values()
+            .filter(|members| members.len() >= config.min_size.max(1))
+            .map(|members| {
+                let representatives = self.representatives(&members, &labels, &ignored, config.representatives);
+                let members: Vec<Uuid> = members.iter().map(|idx| self.graph[*idx]).collect();
+                Community {
+                    id: members.iter().copied().min().unwrap_or_default(),
+                    members,
+                    representatives,
+                }
+            })
+            .collect();
+        communities.sort_by(|a, b| b.members.len().cmp(&a.members.len()).then_with(|| a.id.cmp(&b.id)));
+        communities
+    }
+
+    /// 是否为需要忽略的概念枢纽
+    fn is_concept_hub(&self, idx: NodeIndex, config: &CommunityConfig) -> bool {
+        let Some(max_degree) = config.max_concept_degree else {
+            return false;
+        };
+        let Some(id) = self.graph.node_weight(idx) else {
+            return false;
+        };
+        self.node_type(id) == Some(NodeType::Concept)
+            && self.graph.neighbors_undirected(idx).count() > max_degree
+    }
+
+    /// 社区内加权度最高的若干节点
+    fn representatives(
+        &self,
+        members: &[NodeIndex],
+        labels: &[usize],
+        ignored: &[bool],
+        limit: usize,
+    ) -> Vec<Uuid> {
+        let mut strength: Vec<(NodeIndex, f64)> = members.iter()
+            .map(|&idx| {
+                let label = labels[idx.index()];
+                let internal: f64 = self.graph.edges_directed(idx, petgraph::Direction::Outgoing)
+                    .chain(self.graph.edges_directed(idx, petgraph::Direction::Incoming))
+                    .filter(|e| {
+                        let other = if e.source() == idx { e.target() } else { e.source() };
+                        !ignored[other.index()] && labels[other.index()] == label
+                    })
+                    .map(|e| e.weight().weight.max(0.0) as f64)
+                    .sum();
+                (idx, internal)
+            })
+            .collect();
+        strength.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        strength.into_iter()
+            .take(limit)
+            .map(|(idx, _)| self.graph[idx])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+    use crate::types::Edge;
+
+    fn weighted_edge(source: Uuid, target: Uuid, weight: f32) -> Edge {
+        let mut edge = Edge::new(source, target, relation_types::RELATES_TO.to_string());
+        edge.weight = weight;
+        edge
+    }
+
+    /// 两个 4 节点的完全子图，由一条弱边相连，另有一个连接全部节点的概念枢纽
+    fn two_topics() -> (KnowledgeGraph, Vec<Uuid>, Vec<Uuid>, Uuid) {
+        let mut graph = KnowledgeGraph::new();
+        let left: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let right: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let hub = Uuid::new_v4();
+        graph.add_typed_node(hub, NodeType::Concept);
+
+        for group in [&left, &right] {
+            for i in 0..group.len() {
+                for j in i + 1..group.len() {
+                    graph.add_edge(weighted_edge(group[i], group[j], 1.0));
+                }
+            }
+        }
+        graph.add_edge(weighted_edge(left[0], right[0], 0.1));
+        for id in left.iter().chain(right.iter()) {
+            graph.add_edge(Edge::new(*id, hub, relation_types::CONCEPTUALIZED_AS.to_string()));
+        }
+        (graph, left, right, hub)
+    }
+
+    fn sorted(mut ids: Vec<Uuid>) -> Vec<Uuid> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_communities_ignore_concept_hub() {
+        let (graph, left, right, hub) = two_topics();
+        let config = CommunityConfig {
+            max_concept_degree: Some(5),
+            ..CommunityConfig::default()
+        };
+        let communities = graph.detect_communities(&config);
+
+        assert_eq!(communities.len(), 2);
+        let found: Vec<Vec<Uuid>> = communities.iter().map(|c| sorted(c.members.clone())).collect();
+        assert!(found.contains(&sorted(left.clone())));
+        assert!(found.contains(&sorted(right.clone())));
+
+        let membership = community_membership(&communities);
+        assert!(!membership.contains_key(&hub));
+        assert_eq!(membership[&left[1]], membership[&left[2]]);
+        assert_ne!(membership[&left[0]], membership[&right[0]]);
+    }
+
+    #[test]
+    fn test_representatives_and_min_size() {
+        let (mut graph, left, _, _) = two_topics();
+        // 孤立的边构成 2 节点的小社区
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        graph.add_edge(weighted_edge(a, b, 1.0));
+
+        let config = CommunityConfig {
+            max_concept_degree: Some(5),
+            min_size: 3,
+            representatives: 2,
+            ..CommunityConfig::default()
+        };
+        let communities = graph.detect_communities(&config);
+        assert_eq!(communities.len(), 2);
+        assert!(communities.iter().all(|c| c.representatives.len() == 2));
+
+        let left_community = communities.iter().find(|c| c.members.contains(&left[0])).unwrap();
+        assert_eq!(left_community.members.len(), 4);
+        assert!(left_community.representatives.iter().all(|id| left.contains(id)));
+    }
+
+    #[test]
+    fn test_community_ids_stable_across_changes() {
+        let (mut graph, left, right, _) = two_topics();
+        let config = CommunityConfig {
+            max_concept_degree: Some(5),
+            ..CommunityConfig::default()
+        };
+        let before = community_membership(&graph.detect_communities(&config));
+        assert_eq!(before[&left[0]], *left.iter().min().unwrap());
+        assert_eq!(before[&right[0]], *right.iter().min().unwrap());
+
+        // 新增节点改变了社区规模与排序，已有社区的编号不变
+        let extra: Vec<Uuid> = (0..3).map(|i| Uuid::from_u128(u128::MAX - i)).collect();
+        for id in &extra {
+            graph.add_edge(weighted_edge(right[1], *id, 1.0));
+        }
+        let after = community_membership(&graph.detect_communities(&config));
+        assert_eq!(after[&left[0]], before[&left[0]]);
+        assert_eq!(after[&right[2]], before[&right[2]]);
+    }
+
+    #[test]
+    fn test_hub_kept_when_not_ignored() {
+        let (graph, _, _, hub) = two_topics();
+        let config = CommunityConfig {
+            max_concept_degree: None,
+            ..CommunityConfig::default()
+        };
+        let membership = community_membership(&graph.detect_communities(&config));
+        assert!(membership.contains_key(&hub));
+    }
+}
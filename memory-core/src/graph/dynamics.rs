@@ -0,0 +1,186 @@
+//! 边权重动态
+//!
+//! 边权重随闲置时间指数衰减，检索时扩展路径经过的边得到增强
+//! （Hebbian：一起被使用的关联越来越强），低于下限的边由维护任务清理
+
+use super::{
+    decayed_edge_weight_at, filtered_neighbors, reinforce_weight, KnowledgeGraph, TraversalOptions,
+    DEFAULT_EDGE_DECAY_RATE,
+};
+use crate::types::Edge;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 边衰减与清理配置
+#[derive(Debug, Clone)]
+pub struct EdgeDecayConfig {
+    /// 每天的衰减速率
+    pub decay_rate: f64,
+    /// 衰减后权重低于该值的边会被清理
+    pub prune_below: f64,
+}
+
+impl Default for EdgeDecayConfig {
+    fn default() -> Self {
+        Self {
+            decay_rate: DEFAULT_EDGE_DECAY_RATE,
+            prune_below: 0.05,
+        }
+    }
+}
+
+impl KnowledgeGraph {
+    /// 按连接强度排列的 1 跳邻居
+    ///
+    /// # Returns
+    /// (邻居 ID, 与其相连的边中衰减后的最大权重)，按强度降序
+    pub fn ranked_neighbors(
+        &self,
+        node_id: &Uuid,
+        options: &TraversalOptions,
+        decay_rate: f64,
+    ) -> Vec<(Uuid, f64)> {
+        self.ranked_neighbor_edges(node_id, options, decay_rate)
+            .into_iter()
+            .map(|(neighbor, _, strength)| (neighbor, strength))
+            .collect()
+    }
+
+    /// 按连接强度排列的 1 跳邻居及连接它们的最强边
+    ///
+    /// `options.min_weight` 与衰减后的权重比较，长期闲置而衰减过弱的边不再经过
+    ///
+    /// # Returns
+    /// (邻居 ID, 衰减后权重最大的边, 该权重)，按强度降序
+    pub fn ranked_neighbor_edges<'a>(
+        &'a self,
+        node_id: &Uuid,
+        options: &'a TraversalOptions,
+        decay_rate: f64,
+    ) -> Vec<(Uuid, &'a Edge, f64)> {
+        let Some(&idx) = self.id_to_index.get(node_id) else {
+            return Vec::new();
+        };
+        let now = chrono::Utc::now().timestamp();
+
+        let mut strongest: HashMap<Uuid, (&'a Edge, f64)> = HashMap::new();
+        for (neighbor, edge) in filtered_neighbors(&self.graph, &self.node_types, idx, options) {
+            let neighbor_id = self.graph[neighbor];
+            if neighbor_id == *node_id {
+                continue;
+            }
+            let weight = decayed_edge_weight_at(edge, decay_rate, now);
+            if weight < options.min_weight as f64 {
+                continue;
+            }
+            let entry = strongest.entry(neighbor_id).or_insert((edge, weight));
+            if weight > entry.1 {
+                *entry = (edge, weight);
+            }
+        }
+
+        let mut ranked: Vec<(Uuid, &'a Edge, f64)> = strongest.into_iter()
+            .map(|(id, (edge, weight))| (id, edge, weight))
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.2.partial_cmp(&a.2)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        ranked
+    }
+
+    /// 计算检索路径经过的边增强后的状态，不修改图
+    ///
+    /// 每条边先按闲置时间衰减，再按经过次数依次用 `reinforce_weight` 增强，
+    /// 并刷新最近观测时间；结果写入存储后再用 `refresh_edge` 应用到图上
+    ///
+    /// # Arguments
+    /// * `traversals` - 边 ID → 被经过的次数（图中已不存在的边会被忽略）
+    pub fn plan_edge_reinforcement(&self, traversals: &HashMap<Uuid, u32>, decay_rate: f64) -> Vec<Edge> {
+        let now = chrono::Utc::now().timestamp();
+        traversals.iter()
+            .filter_map(|(id, &count)| {
+                let edge = self.graph.edge_weight(*self.edge_index.get(id)?)?;
+                let mut reinforced = edge.clone();
+                let mut weight = decayed_edge_weight_at(edge, decay_rate, now) as f32;
+                for _ in 0..count {
+                    weight = reinforce_weight(weight, weight);
+                }
+                reinforced.weight = weight;
+                reinforced.last_seen_at = now;
+                Some(reinforced)
+            })
+            .collect()
+    }
+
+    /// 衰减后权重低于清理下限的边
+    pub fn weak_edges(&self, config: &EdgeDecayConfig) -> Vec<Uuid> {
+        let now = chrono::Utc::now().timestamp();
+        self.graph.edge_weights()
+            .filter(|e| decayed_edge_weight_at(e, config.decay_rate, now) < config.prune_below)
+            .map(|e| e.id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+
+    fn edge(source: Uuid, target: Uuid, weight: f32, idle_days: i64) -> Edge {
+        let mut edge = Edge::new(source, target, relation_types::RELATES_TO.to_string());
+        edge.weight = weight;
+        edge.created_at -= idle_days * 86400;
+        edge.last_seen_at = edge.created_at;
+        edge
+    }
+
+    #[test]
+    fn test_ranked_neighbors_prefer_strong_edges() {
+        let (center, strong, weak, stale) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(edge(center, weak, 0.3, 0));
+        graph.add_edge(edge(center, strong, 0.9, 0));
+        graph.add_edge(edge(stale, center, 0.9, 365));
+
+        let ranked = graph.ranked_neighbors(&center, &TraversalOptions::default(), DEFAULT_EDGE_DECAY_RATE);
+        let order: Vec<Uuid> = ranked.iter().map(|(id, _)| *id).collect();
+        assert_eq!(order, vec![strong, weak, stale]);
+
+        // 不衰减时长期未用的边与新边同样强
+        let ranked = graph.ranked_neighbors(&center, &TraversalOptions::default(), 0.0);
+        assert_eq!(ranked[2].0, weak);
+
+        // 最小权重按衰减后的权重比较
+        let strong_only = TraversalOptions::default().with_min_weight(0.5);
+        let ranked = graph.ranked_neighbors(&center, &strong_only, DEFAULT_EDGE_DECAY_RATE);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0, strong);
+    }
+
+    #[test]
+    fn test_reinforce_and_prune() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(edge(ids[0], ids[1], 0.5, 0));
+        graph.add_edge(edge(ids[1], ids[2], 0.5, 400));
+
+        let traversed = graph.ranked_neighbor_edges(&ids[0], &TraversalOptions::default(), DEFAULT_EDGE_DECAY_RATE)[0].1.id;
+        let once = graph.plan_edge_reinforcement(&HashMap::from([(traversed, 1)]), DEFAULT_EDGE_DECAY_RATE);
+        let twice = graph.plan_edge_reinforcement(&HashMap::from([(traversed, 2), (Uuid::new_v4(), 1)]), DEFAULT_EDGE_DECAY_RATE);
+        assert_eq!((once.len(), twice.len()), (1, 1));
+        assert!(once[0].weight > 0.5 && twice[0].weight > once[0].weight);
+        // 只计算结果，写入由调用方完成
+        assert_eq!(graph.get_outgoing_edges(&ids[0])[0].weight, 0.5);
+        assert!(graph.refresh_edge(&twice[0]));
+        assert_eq!(graph.get_outgoing_edges(&ids[0])[0].weight, twice[0].weight);
+
+        let weak = graph.weak_edges(&EdgeDecayConfig::default());
+        assert_eq!(weak.len(), 1);
+        let removed = graph.remove_edge(&weak[0]).unwrap();
+        assert_eq!(removed.source, ids[1]);
+        assert_eq!(graph.edge_count(), 1);
+    }
+}
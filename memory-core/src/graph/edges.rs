@@ -4,7 +4,7 @@
 
 use crate::types::Edge;
 use uuid::Uuid;
-// use chrono::Utc;
+use chrono::Utc;
 use std::collections::HashMap;
 
 /// 预定义的关系类型
@@ -35,6 +35,75 @@ pub mod relation_types {
     pub const CONCEPTUALIZED_AS: &str = "conceptualized_as";
 }
 
+/// 首次观测到的关系的边权重（重复观测后逐步增强）
+pub const INITIAL_EDGE_WEIGHT: f32 = 0.5;
+
+/// 每次重复观测时权重向上限 1.0 靠近的比例
+pub const REINFORCEMENT_RATE: f32 = 0.25;
+
+/// 重复观测后的边权重
+///
+/// 先取已有权重与本次观测权重的较大者，再向 1.0 靠近 `REINFORCEMENT_RATE`，
+/// 多次观测后趋于饱和而不会超过 1.0
+pub fn reinforce_weight(current: f32, observed: f32) -> f32 {
+    let base = current.max(observed).clamp(0.0, 1.0);
+    base + (1.0 - base) * REINFORCEMENT_RATE
+}
+
+/// 边权重的默认衰减速率（每天，约 70 天衰减一半）
+pub const DEFAULT_EDGE_DECAY_RATE: f64 = 0.01;
+
+/// 计算边衰减后的权重
+///
+/// 自最近一次观测或被检索使用起按天指数衰减: weight * e^(-decay_rate * days)
+pub fn calculate_decayed_edge_weight(edge: &Edge, decay_rate: f64) -> f64 {
+    decayed_edge_weight_at(edge, decay_rate, Utc::now().timestamp())
+}
+
+/// 计算边在指定时刻衰减后的权重
+pub fn decayed_edge_weight_at(edge: &Edge, decay_rate: f64, now: i64) -> f64 {
+    let last_seen = edge.last_seen_at.max(edge.created_at);
+    let idle_days = (now - last_seen).max(0) as f64 / 86400.0;
+    (edge.weight as f64) * (-decay_rate.max(0.0) * idle_days).exp()
+}
+
+/// 合并平行边
+///
+/// 按 (源节点, 目标节点, 关系) 分组，每组保留最早创建的边，
+/// 其余每条边视为一次重复观测对其增强，最近观测时间取组内最大值
+///
+/// # Returns
+/// (合并后的保留边, 被合并掉的边 ID)，只包含存在重复的分组
+pub fn collapse_parallel_edges(edges: Vec<Edge>) -> (Vec<Edge>, Vec<Uuid>) {
+    let mut groups: HashMap<(Uuid, Uuid, String), Vec<Edge>> = HashMap::new();
+    for edge in edges {
+        groups.entry((edge.source, edge.target, edge.relation.clone()))
+            .or_default()
+            .push(edge);
+    }
+
+    let mut merged = Vec::new();
+    let mut removed = Vec::new();
+    for mut group in groups.into_values() {
+        if group.len() < 2 {
+            continue;
+        }
+        group.sort_by_key(|e| (e.created_at, e.id));
+        let mut duplicates = group.into_iter();
+        let Some(mut kept) = duplicates.next() else {
+            continue;
+        };
+        for duplicate in duplicates {
+            kept.weight = reinforce_weight(kept.weight, duplicate.weight);
+            kept.last_seen_at = kept.last_seen_at.max(duplicate.last_seen_at);
+            removed.push(duplicate.id);
+        }
+        merged.push(kept);
+    }
+
+    (merged, removed)
+}
+
 /// 边构建器
 pub struct EdgeBuilder {
     edge: Edge,
@@ -302,6 +371,50 @@ mod tests {
         assert_eq!(index.find_by_target(&id3).len(), 1);
     }
 
+    #[test]
+    fn test_reinforce_weight_saturates() {
+        let mut weight = INITIAL_EDGE_WEIGHT;
+        for _ in 0..20 {
+            let next = reinforce_weight(weight, INITIAL_EDGE_WEIGHT);
+            assert!(next > weight && next <= 1.0);
+            weight = next;
+        }
+        assert!(weight > 0.99);
+        assert_eq!(reinforce_weight(1.0, 0.5), 1.0);
+    }
+
+    #[test]
+    fn test_decayed_edge_weight() {
+        let mut edge = Edge::new(Uuid::new_v4(), Uuid::new_v4(), "relates".to_string());
+        edge.weight = 0.8;
+        let now = edge.last_seen_at;
+        assert!((decayed_edge_weight_at(&edge, DEFAULT_EDGE_DECAY_RATE, now) - 0.8).abs() < 1e-6);
+
+        let later = now + 70 * 86400;
+        let decayed = decayed_edge_weight_at(&edge, DEFAULT_EDGE_DECAY_RATE, later);
+        assert!(decayed < 0.8 * 0.51 && decayed > 0.8 * 0.49);
+        assert_eq!(decayed_edge_weight_at(&edge, 0.0, later), edge.weight as f64);
+    }
+
+    #[test]
+    fn test_collapse_parallel_edges() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut first = EdgeBuilder::participates(a, b).weight(INITIAL_EDGE_WEIGHT).build();
+        first.created_at = 100;
+        first.last_seen_at = 100;
+        let mut second = EdgeBuilder::participates(a, b).weight(INITIAL_EDGE_WEIGHT).build();
+        second.created_at = 200;
+        second.last_seen_at = 300;
+        let other = EdgeBuilder::new(a, b, relation_types::KNOWS).build();
+
+        let (merged, removed) = collapse_parallel_edges(vec![second.clone(), other, first.clone()]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, first.id);
+        assert_eq!(merged[0].last_seen_at, 300);
+        assert!(merged[0].weight > INITIAL_EDGE_WEIGHT);
+        assert_eq!(removed, vec![second.id]);
+    }
+
     #[test]
     fn test_normalize_relation() {
         assert_eq!(normalize_relation("之前"), relation_types::BEFORE);
@@ -0,0 +1,297 @@
+//! 图导出
+//!
+//! 将图结构与节点内容拼接后导出为 GraphML（Gephi）、DOT（Graphviz）
+//! 和 JSON node-link（d3 / networkx 等网页查看器）格式
+
+use super::{KnowledgeGraph, TraversalOptions};
+use crate::types::{Edge, NodeType};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use serde::Serialize;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// GraphML（Gephi、yEd）
+    GraphMl,
+    /// DOT（Graphviz）
+    Dot,
+    /// JSON node-link（d3、networkx）
+    JsonNodeLink,
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// 关系类型、节点类型与边权重过滤
+    pub traversal: TraversalOptions,
+    /// 只导出这些种子节点周围的子图（为空时导出整张图）
+    pub seed_ids: Vec<Uuid>,
+    /// 子图扩展跳数（仅在指定种子时生效）
+    pub hop_depth: usize,
+}
+
+/// 导出的节点（图结构 + 存储中的节点数据）
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportNode {
+    /// 节点 ID
+    pub id: Uuid,
+    /// 节点内容
+    pub content: String,
+    /// 节点类型（未知时为 None）
+    pub node_type: Option<NodeType>,
+    /// 重要性
+    pub importance: f32,
+}
+
+/// JSON node-link 格式
+#[derive(Serialize)]
+struct NodeLinkGraph<'a> {
+    directed: bool,
+    multigraph: bool,
+    nodes: &'a [ExportNode],
+    links: Vec<NodeLinkEdge<'a>>,
+}
+
+#[derive(Serialize)]
+struct NodeLinkEdge<'a> {
+    id: Uuid,
+    source: Uuid,
+    target: Uuid,
+    relation: &'a str,
+    weight: f32,
+}
+
+impl KnowledgeGraph {
+    /// 按导出选项选出节点和边
+    ///
+    /// # Returns
+    /// (节点 ID, 两端都在节点集合内且满足过滤条件的边)
+    pub fn select_for_export(&self, options: &ExportOptions) -> (Vec<Uuid>, Vec<Edge>) {
+        let traversal = &options.traversal;
+        let selected: HashSet<Uuid> = if options.seed_ids.is_empty() {
+            self.graph.node_weights()
+                .filter(|id| traversal.allows_node_type(self.node_type(id)))
+                .copied()
+                .collect()
+        } else {
+            options.seed_ids.iter()
+                .flat_map(|seed| self.get_neighbors_with_options(seed, options.hop_depth, traversal))
+                .collect()
+        };
+
+        let node_ids: Vec<Uuid> = self.graph.node_weights()
+            .filter(|id| selected.contains(id))
+            .copied()
+            .collect();
+        let edges: Vec<Edge> = self.graph.edge_references()
+            .filter(|e| {
+                selected.contains(&self.graph[e.source()])
+                    && selected.contains(&self.graph[e.target()])
+                    && traversal.allows_edge(e.weight())
+            })
+            .map(|e| e.weight().clone())
+            .collect();
+
+        (node_ids, edges)
+    }
+}
+
+/// 按格式序列化
+pub fn export_graph(format: ExportFormat, nodes: &[ExportNode], edges: &[Edge]) -> String {
+    match format {
+        ExportFormat::GraphMl => to_graphml(nodes, edges),
+        ExportFormat::Dot => to_dot(nodes, edges),
+        ExportFormat::JsonNodeLink => to_json_node_link(nodes, edges),
+    }
+}
+
+/// 导出为 GraphML
+pub fn to_graphml(nodes: &[ExportNode], edges: &[Edge]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"content\" for=\"node\" attr.name=\"content\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"node_type\" for=\"node\" attr.name=\"node_type\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"importance\" for=\"node\" attr.name=\"importance\" attr.type=\"double\"/>\n");
+    out.push_str("  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n");
+    out.push_str("  <graph id=\"memory\" edgedefault=\"directed\">\n");
+
+    for node in nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", node.id));
+        out.push_str(&format!("      <data key=\"content\">{}</data>\n", escape_xml(&node.content)));
+        if let Some(node_type) = node.node_type {
+            out.push_str(&format!("      <data key=\"node_type\">{}</data>\n", node_type.as_str()));
+        }
+        out.push_str(&format!("      <data key=\"importance\">{}</data>\n", node.importance));
+        out.push_str("    </node>\n");
+    }
+    for edge in edges {
+        out.push_str(&format!(
+            "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+            edge.id, edge.source, edge.target
+        ));
+        out.push_str(&format!("      <data key=\"relation\">{}</data>\n", escape_xml(&edge.relation)));
+        out.push_str(&format!("      <data key=\"weight\">{}</data>\n", edge.weight));
+        out.push_str("    </edge>\n");
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+/// 导出为 DOT
+///
+/// 重要性与边权重写在标签中（Graphviz 的 weight 属性只接受整数，不用于携带浮点权重）
+pub fn to_dot(nodes: &[ExportNode], edges: &[Edge]) -> String {
+    let mut out = String::from("digraph memory {\n");
+    for node in nodes {
+        let shape = match node.node_type {
+            Some(NodeType::Entity) => "ellipse",
+            Some(NodeType::Event) => "box",
+            Some(NodeType::Concept) => "diamond",
+            None => "plaintext",
+        };
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\\n重要性 {:.2}\", shape={}];\n",
+            node.id, escape_dot(&node.content), node.importance, shape
+        ));
+    }
+    for edge in edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\" [label=\"{} ({:.2})\"];\n",
+            edge.source, edge.target, escape_dot(&edge.relation), edge.weight
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// 导出为 JSON node-link
+pub fn to_json_node_link(nodes: &[ExportNode], edges: &[Edge]) -> String {
+    let graph = NodeLinkGraph {
+        directed: true,
+        multigraph: true,
+        nodes,
+        links: edges.iter()
+            .map(|e| NodeLinkEdge {
+                id: e.id,
+                source: e.source,
+                target: e.target,
+                relation: &e.relation,
+                weight: e.weight,
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&graph).unwrap_or_default()
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+
+    fn sample() -> (KnowledgeGraph, [Uuid; 3]) {
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let [mom, visit, person] = ids;
+        let mut graph = KnowledgeGraph::new();
+        graph.add_typed_node(mom, NodeType::Entity);
+        graph.add_typed_node(visit, NodeType::Event);
+        graph.add_typed_node(person, NodeType::Concept);
+        graph.add_edge(Edge::new(mom, visit, relation_types::PARTICIPATES_IN.to_string()));
+        graph.add_edge(Edge::new(mom, person, relation_types::CONCEPTUALIZED_AS.to_string()));
+        (graph, ids)
+    }
+
+    fn export_nodes(ids: &[Uuid]) -> Vec<ExportNode> {
+        ids.iter()
+            .map(|id| ExportNode {
+                id: *id,
+                content: "妈妈说\"<你好>\" & 再见".to_string(),
+                node_type: Some(NodeType::Entity),
+                importance: 0.5,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_with_filters() {
+        let (graph, [mom, visit, person]) = sample();
+
+        let (nodes, edges) = graph.select_for_export(&ExportOptions::default());
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 2);
+
+        let options = ExportOptions {
+            traversal: TraversalOptions::default()
+                .with_node_types([NodeType::Entity, NodeType::Event]),
+            ..ExportOptions::default()
+        };
+        let (nodes, edges) = graph.select_for_export(&options);
+        assert!(!nodes.contains(&person));
+        assert_eq!(edges.len(), 1);
+
+        let options = ExportOptions {
+            traversal: TraversalOptions::default()
+                .with_relations([relation_types::PARTICIPATES_IN]),
+            seed_ids: vec![visit],
+            hop_depth: 2,
+        };
+        let (nodes, edges) = graph.select_for_export(&options);
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.contains(&mom) && nodes.contains(&visit));
+        assert_eq!(edges[0].relation, relation_types::PARTICIPATES_IN);
+    }
+
+    #[test]
+    fn test_graphml_and_dot_escape() {
+        let (graph, _) = sample();
+        let (ids, edges) = graph.select_for_export(&ExportOptions::default());
+        let nodes = export_nodes(&ids);
+
+        let graphml = export_graph(ExportFormat::GraphMl, &nodes, &edges);
+        assert!(graphml.contains("&quot;&lt;你好&gt;&quot; &amp; 再见"));
+        assert_eq!(graphml.matches("<node ").count(), 3);
+        assert_eq!(graphml.matches("<edge ").count(), 2);
+
+        let mut nodes = nodes;
+        nodes.extend(export_nodes(&[Uuid::new_v4()]));
+        let dot = export_graph(ExportFormat::Dot, &nodes, &edges);
+        assert!(dot.starts_with("digraph memory {"));
+        assert!(dot.contains("妈妈说\\\"<你好>\\\" & 再见\\n重要性 0.50"));
+        assert!(dot.contains(&format!("[label=\"{} (1.00)\"]", relation_types::PARTICIPATES_IN)));
+        assert!(!dot.contains("weight=") && !dot.contains("importance="));
+        // 没有边的孤立节点也会输出
+        assert_eq!(dot.matches(" [label=").count(), 6);
+        assert_eq!(dot.matches(" -> ").count(), 2);
+    }
+
+    #[test]
+    fn test_json_node_link() {
+        let (graph, _) = sample();
+        let (ids, edges) = graph.select_for_export(&ExportOptions::default());
+        let json = export_graph(ExportFormat::JsonNodeLink, &export_nodes(&ids), &edges);
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["directed"], true);
+        assert_eq!(value["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(value["links"].as_array().unwrap().len(), 2);
+        assert_eq!(value["nodes"][0]["node_type"], "entity");
+    }
+}
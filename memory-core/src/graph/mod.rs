@@ -3,32 +3,69 @@
 mod nodes;
 mod edges;
 mod traversal;
+mod snapshot;
+mod pagerank;
+mod paths;
+mod community;
+mod centrality;
+mod export;
+mod temporal;
+mod dynamics;
+mod pattern;
+mod stats;
+mod resolution;
+mod taxonomy;
 
 pub use nodes::*;
 pub use edges::*;
 pub use traversal::*;
+pub use snapshot::*;
+pub use pagerank::*;
+pub use paths::*;
+pub use community::*;
+pub use centrality::*;
+pub use export::*;
+pub use temporal::*;
+pub use dynamics::*;
+pub use pattern::*;
+pub use stats::*;
+pub use resolution::*;
+pub use taxonomy::*;
 
 use crate::types::*;
-use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::stable_graph::{EdgeIndex as GraphEdgeIndex, NodeIndex, StableDiGraph};
+use petgraph::visit::EdgeRef;
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// 知识图谱
 /// 
-/// 使用 petgraph 实现的有向图结构
+/// 使用 petgraph 的 StableGraph 实现的有向图结构：删除节点或边时
+/// 其余索引保持不变，删除开销只与被删节点的度数相关。
+/// 克隆得到的副本可在锁外做耗时的全图计算
+#[derive(Clone)]
 pub struct KnowledgeGraph {
     /// 内部图结构（节点存储 UUID，边存储 Edge）
-    graph: DiGraph<Uuid, Edge>,
+    graph: StableDiGraph<Uuid, Edge>,
     /// UUID 到节点索引的映射
     id_to_index: HashMap<Uuid, NodeIndex>,
+    /// 边 ID 到边索引的映射（同时防止同一条边被重复加入）
+    edge_index: HashMap<Uuid, GraphEdgeIndex>,
+    /// 节点类型（用于按类型过滤遍历，未知类型的节点不在其中）
+    node_types: HashMap<Uuid, NodeType>,
+    /// 修改计数（每次结构变化递增，供外部缓存判断是否失效）
+    generation: u64,
 }
 
 impl KnowledgeGraph {
     /// 创建新的空图谱
     pub fn new() -> Self {
         Self {
-            graph: DiGraph::new(),
+            graph: StableDiGraph::new(),
             id_to_index: HashMap::new(),
+            edge_index: HashMap::new(),
+            node_types: HashMap::new(),
+            generation: 0,
         }
     }
 
@@ -39,14 +76,49 @@ impl KnowledgeGraph {
         }
         let idx = self.graph.add_node(id);
         self.id_to_index.insert(id, idx);
+        self.generation += 1;
         idx
     }
 
+    /// 添加带类型的节点到图中
+    pub fn add_typed_node(&mut self, id: Uuid, node_type: NodeType) -> NodeIndex {
+        self.set_node_type(id, node_type);
+        self.add_node(id)
+    }
+
+    /// 记录节点类型（节点不在图中时也会记录，待其边加入后生效）
+    pub fn set_node_type(&mut self, id: Uuid, node_type: NodeType) {
+        if self.node_types.insert(id, node_type) != Some(node_type) {
+            self.generation += 1;
+        }
+    }
+
+    /// 获取节点类型
+    pub fn node_type(&self, id: &Uuid) -> Option<NodeType> {
+        self.node_types.get(id).copied()
+    }
+
+    /// 获取图中类型未知的节点 ID
+    pub fn untyped_node_ids(&self) -> Vec<Uuid> {
+        self.id_to_index.keys()
+            .filter(|id| !self.node_types.contains_key(id))
+            .copied()
+            .collect()
+    }
+
     /// 添加边到图中
+    ///
+    /// 已存在相同 ID 的边时忽略
     pub fn add_edge(&mut self, edge: Edge) {
+        if self.edge_index.contains_key(&edge.id) {
+            return;
+        }
         let source_idx = self.add_node(edge.source);
         let target_idx = self.add_node(edge.target);
-        self.graph.add_edge(source_idx, target_idx, edge);
+        let edge_id = edge.id;
+        let edge_idx = self.graph.add_edge(source_idx, target_idx, edge);
+        self.edge_index.insert(edge_id, edge_idx);
+        self.generation += 1;
     }
 
     /// 批量添加边
@@ -56,28 +128,173 @@ impl KnowledgeGraph {
         }
     }
 
-    /// 删除节点及其所有边
+    /// 按 (源节点, 目标节点, 关系) 查找边
+    pub fn find_edge_by_key(&self, source: &Uuid, target: &Uuid, relation: &str) -> Option<&Edge> {
+        let source_idx = *self.id_to_index.get(source)?;
+        let target_idx = *self.id_to_index.get(target)?;
+        self.graph.edges_connecting(source_idx, target_idx)
+            .map(|e| e.weight())
+            .find(|e| e.relation == relation)
+    }
+
+    /// 添加或增强边
     ///
-    /// 注意：petgraph 的 remove_node 会导致 NodeIndex 重新编号，
-    /// 因此必须重建 id_to_index 映射
-    pub fn remove_node(&mut self, id: &Uuid) -> bool {
-        if let Some(idx) = self.id_to_index.remove(id) {
-            self.graph.remove_node(idx);
-            // 重建 id_to_index 映射，因为 petgraph 会重新编号
-            self.rebuild_index();
-            return true;
+    /// 以 (源节点, 目标节点, 关系) 为键：不存在时插入；已存在时按
+    /// `reinforce_weight` 增强权重并刷新最近观测时间，不产生平行边
+    ///
+    /// # Returns
+    /// 写入后的边，以及是否为新插入
+    pub fn upsert_edge(&mut self, edge: Edge) -> (Edge, bool) {
+        let existing = self.id_to_index.get(&edge.source)
+            .zip(self.id_to_index.get(&edge.target))
+            .and_then(|(&s, &t)| {
+                self.graph.edges_connecting(s, t)
+                    .find(|e| e.weight().relation == edge.relation)
+                    .map(|e| e.id())
+            });
+
+        match existing {
+            Some(edge_idx) => {
+                let stored = &mut self.graph[edge_idx];
+                observe_again(stored, &edge);
+                self.generation += 1;
+                (stored.clone(), false)
+            }
+            None => {
+                self.add_edge(edge.clone());
+                (edge, true)
+            }
         }
-        false
     }
 
-    /// 重建 UUID → NodeIndex 映射
-    fn rebuild_index(&mut self) {
-        self.id_to_index.clear();
-        for idx in self.graph.node_indices() {
-            if let Some(uuid) = self.graph.node_weight(idx) {
-                self.id_to_index.insert(*uuid, idx);
+    /// 批量添加或增强边
+    ///
+    /// # Returns
+    /// (新插入的边, 被增强的已有边)，均为写入后的最终状态；
+    /// 同一批次内重复的边只会出现在其中一个列表里
+    pub fn upsert_edges(&mut self, edges: impl IntoIterator<Item = Edge>) -> (Vec<Edge>, Vec<Edge>) {
+        let (inserted, reinforced) = self.plan_upsert_edges(edges);
+        self.apply_upserted_edges(&inserted, &reinforced);
+        (inserted, reinforced)
+    }
+
+    /// 计算批量添加或增强边的结果，不修改图
+    ///
+    /// 返回值与 [`upsert_edges`](Self::upsert_edges) 相同，调用方可以先写入存储，
+    /// 成功后再用 [`apply_upserted_edges`](Self::apply_upserted_edges) 更新图
+    pub fn plan_upsert_edges(&self, edges: impl IntoIterator<Item = Edge>) -> (Vec<Edge>, Vec<Edge>) {
+        let mut inserted: Vec<Edge> = Vec::new();
+        let mut reinforced: Vec<Edge> = Vec::new();
+        // (源节点, 目标节点, 关系) → (是否新插入, 下标)
+        let mut positions: HashMap<(Uuid, Uuid, String), (bool, usize)> = HashMap::new();
+
+        for edge in edges {
+            let key = (edge.source, edge.target, edge.relation.clone());
+            if let Some(&(is_new, i)) = positions.get(&key) {
+                let stored = if is_new { &mut inserted[i] } else { &mut reinforced[i] };
+                observe_again(stored, &edge);
+                continue;
             }
+
+            let existing = self.id_to_index.get(&edge.source)
+                .zip(self.id_to_index.get(&edge.target))
+                .and_then(|(&s, &t)| {
+                    self.graph.edges_connecting(s, t)
+                        .find(|e| e.weight().relation == edge.relation)
+                });
+            match existing {
+                Some(existing) => {
+                    let mut stored = existing.weight().clone();
+                    observe_again(&mut stored, &edge);
+                    positions.insert(key, (false, reinforced.len()));
+                    reinforced.push(stored);
+                }
+                None => {
+                    positions.insert(key, (true, inserted.len()));
+                    inserted.push(edge);
+                }
+            }
+        }
+
+        (inserted, reinforced)
+    }
+
+    /// 把 [`plan_upsert_edges`](Self::plan_upsert_edges) 的结果写入图
+    pub fn apply_upserted_edges(&mut self, inserted: &[Edge], reinforced: &[Edge]) {
+        for edge in inserted {
+            self.add_edge(edge.clone());
+        }
+        for edge in reinforced {
+            self.refresh_edge(edge);
+        }
+    }
+
+    /// 用存储中的最新状态覆盖同 ID 边的权重、观测时间与元数据
+    ///
+    /// # Returns
+    /// 图中存在该边时返回 true
+    pub fn refresh_edge(&mut self, edge: &Edge) -> bool {
+        let Some(stored) = self.edge_index.get(&edge.id)
+            .and_then(|&idx| self.graph.edge_weight_mut(idx))
+        else {
+            return false;
+        };
+        stored.weight = edge.weight;
+        stored.last_seen_at = edge.last_seen_at;
+        stored.metadata = edge.metadata.clone();
+        self.generation += 1;
+        true
+    }
+
+    /// 删除节点及其所有边
+    pub fn remove_node(&mut self, id: &Uuid) -> bool {
+        if !self.detach_node(id) {
+            return false;
+        }
+        self.generation += 1;
+        true
+    }
+
+    /// 批量删除节点及其所有边
+    ///
+    /// # Returns
+    /// 实际删除的节点数
+    pub fn remove_nodes<'a>(&mut self, ids: impl IntoIterator<Item = &'a Uuid>) -> usize {
+        let removed = ids.into_iter()
+            .filter(|id| self.detach_node(id))
+            .count();
+        if removed > 0 {
+            self.generation += 1;
+        }
+        removed
+    }
+
+    /// 按 ID 删除边（两端节点保留）
+    ///
+    /// # Returns
+    /// 被删除的边
+    pub fn remove_edge(&mut self, edge_id: &Uuid) -> Option<Edge> {
+        let idx = self.edge_index.remove(edge_id)?;
+        let edge = self.graph.remove_edge(idx)?;
+        self.generation += 1;
+        Some(edge)
+    }
+
+    /// 从图中摘除节点，不修改 generation
+    ///
+    /// StableGraph 删除节点后其余索引不变，只需清理该节点自身及其边的映射
+    fn detach_node(&mut self, id: &Uuid) -> bool {
+        let Some(idx) = self.id_to_index.remove(id) else {
+            return false;
+        };
+        self.node_types.remove(id);
+        for edge in self.graph.edges_directed(idx, petgraph::Direction::Outgoing)
+            .chain(self.graph.edges_directed(idx, petgraph::Direction::Incoming))
+        {
+            self.edge_index.remove(&edge.weight().id);
         }
+        self.graph.remove_node(idx);
+        true
     }
 
     /// 检查节点是否存在
@@ -85,6 +302,19 @@ impl KnowledgeGraph {
         self.id_to_index.contains_key(id)
     }
 
+    /// 检查边是否存在
+    pub fn contains_edge(&self, edge_id: &Uuid) -> bool {
+        self.edge_index.contains_key(edge_id)
+    }
+
+    /// 修改计数
+    ///
+    /// 图结构或节点类型每次变化都会递增，缓存社区划分等全图计算结果时
+    /// 可以据此判断是否需要重新计算
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     /// 获取节点数量
     pub fn node_count(&self) -> usize {
         self.graph.node_count()
@@ -104,6 +334,19 @@ impl KnowledgeGraph {
     /// # Returns
     /// 所有在 N 跳范围内的节点 ID 集合
     pub fn get_neighbors(&self, node_id: &Uuid, hops: usize) -> HashSet<Uuid> {
+        self.get_neighbors_with_options(node_id, hops, &TraversalOptions::default())
+    }
+
+    /// 按遍历选项获取 N 跳邻居
+    ///
+    /// # Returns
+    /// 起始节点及所有满足选项、在 N 跳范围内的节点 ID 集合
+    pub fn get_neighbors_with_options(
+        &self,
+        node_id: &Uuid,
+        hops: usize,
+        options: &TraversalOptions,
+    ) -> HashSet<Uuid> {
         let mut visited = HashSet::new();
         let mut current_level = HashSet::new();
         
@@ -115,13 +358,9 @@ impl KnowledgeGraph {
                 let mut next_level = HashSet::new();
                 
                 for idx in &current_level {
-                    // 遍历所有邻居（包括出边和入边方向）
-                    for neighbor in self.graph.neighbors_undirected(*idx) {
-                        if let Some(neighbor_id) = self.graph.node_weight(neighbor) {
-                            if !visited.contains(neighbor_id) {
-                                visited.insert(*neighbor_id);
-                                next_level.insert(neighbor);
-                            }
+                    for (neighbor, _) in filtered_neighbors(&self.graph, &self.node_types, *idx, options) {
+                        if visited.insert(self.graph[neighbor]) {
+                            next_level.insert(neighbor);
                         }
                     }
                 }
@@ -136,6 +375,31 @@ impl KnowledgeGraph {
         visited
     }
 
+    /// BFS 遍历
+    pub fn bfs(&self, start: &Uuid, max_depth: usize, options: &TraversalOptions) -> TraversalResult {
+        bfs_traverse(&self.graph, &self.id_to_index, &self.node_types, start, max_depth, options)
+    }
+
+    /// DFS 遍历
+    pub fn dfs(&self, start: &Uuid, max_depth: usize, options: &TraversalOptions) -> TraversalResult {
+        dfs_traverse(&self.graph, &self.id_to_index, &self.node_types, start, max_depth, options)
+    }
+
+    /// 按跳数的最短路径
+    pub fn shortest_path(&self, from: &Uuid, to: &Uuid, options: &TraversalOptions) -> Option<Vec<Uuid>> {
+        find_shortest_path(&self.graph, &self.id_to_index, &self.node_types, from, to, options)
+    }
+
+    /// 从种子节点提取子图
+    pub fn subgraph(
+        &self,
+        seed_nodes: &[Uuid],
+        hop_depth: usize,
+        config: &SubgraphConfig,
+    ) -> (HashSet<Uuid>, Vec<Edge>) {
+        extract_subgraph(&self.graph, &self.id_to_index, &self.node_types, seed_nodes, hop_depth, config)
+    }
+
     /// 查找概念桥接的节点
     /// 
     /// 通过概念节点找到相关的其他节点
@@ -145,35 +409,13 @@ impl KnowledgeGraph {
     /// * `concept_nodes` - 概念节点 ID 集合
     /// 
     /// # Returns
-    /// 通过概念节点桥接找到的节点 ID 集合
+    /// 通过概念节点桥接找到的节点 ID，按与概念相连的边权重降序排列
     pub fn find_concept_bridged(
         &self,
         source_nodes: &[Uuid],
         concept_nodes: &HashSet<Uuid>,
-    ) -> HashSet<Uuid> {
-        let mut bridged = HashSet::new();
-        
-        for source_id in source_nodes {
-            if let Some(&source_idx) = self.id_to_index.get(source_id) {
-                // 找到连接到概念节点的边
-                for neighbor_idx in self.graph.neighbors_undirected(source_idx) {
-                    if let Some(neighbor_id) = self.graph.node_weight(neighbor_idx) {
-                        if concept_nodes.contains(neighbor_id) {
-                            // 找到通过该概念连接的其他节点
-                            for bridged_idx in self.graph.neighbors_undirected(neighbor_idx) {
-                                if let Some(bridged_id) = self.graph.node_weight(bridged_idx) {
-                                    if !source_nodes.contains(bridged_id) {
-                                        bridged.insert(*bridged_id);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        bridged
+    ) -> Vec<Uuid> {
+        self.find_concept_bridged_with_levels(source_nodes, concept_nodes, 0, 0, None)
     }
 
     /// 获取两个节点之间的边
@@ -207,6 +449,9 @@ impl KnowledgeGraph {
     pub fn clear(&mut self) {
         self.graph.clear();
         self.id_to_index.clear();
+        self.edge_index.clear();
+        self.node_types.clear();
+        self.generation += 1;
     }
 }
 
@@ -216,6 +461,12 @@ impl Default for KnowledgeGraph {
     }
 }
 
+/// 同一关系再次被观测：增强权重并刷新最近观测时间
+fn observe_again(stored: &mut Edge, observed: &Edge) {
+    stored.weight = reinforce_weight(stored.weight, observed.weight);
+    stored.last_seen_at = stored.last_seen_at.max(observed.last_seen_at);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,4 +505,79 @@ mod tests {
         let neighbors = graph.get_neighbors(&id1, 2);
         assert!(neighbors.contains(&id3));
     }
+
+    #[test]
+    fn test_upsert_edges_collapses_parallel_edges() {
+        let mut graph = KnowledgeGraph::new();
+        let entity = Uuid::new_v4();
+        let event = Uuid::new_v4();
+
+        let observe = || EdgeBuilder::participates(entity, event).weight(INITIAL_EDGE_WEIGHT).build();
+        let (inserted, reinforced) = graph.upsert_edges([observe(), observe()]);
+        assert_eq!(inserted.len(), 1);
+        assert!(reinforced.is_empty());
+        assert!(inserted[0].weight > INITIAL_EDGE_WEIGHT);
+
+        let (inserted, reinforced) = graph.upsert_edges([observe()]);
+        assert!(inserted.is_empty());
+        assert_eq!(reinforced.len(), 1);
+        assert_eq!(graph.edge_count(), 1);
+
+        // 关系不同的边仍然单独保存
+        graph.upsert_edge(Edge::new(entity, event, relation_types::INITIATED.to_string()));
+        assert_eq!(graph.edge_count(), 2);
+        let stored = graph.find_edge_by_key(&entity, &event, relation_types::PARTICIPATES_IN).unwrap();
+        assert_eq!(stored.weight, reinforced[0].weight);
+    }
+
+    #[test]
+    fn test_plan_upsert_leaves_graph_untouched() {
+        let mut graph = KnowledgeGraph::new();
+        let entity = Uuid::new_v4();
+        let event = Uuid::new_v4();
+        let observe = || EdgeBuilder::participates(entity, event).weight(INITIAL_EDGE_WEIGHT).build();
+        graph.upsert_edges([observe()]);
+        let generation = graph.generation();
+
+        let other = Edge::new(event, entity, relation_types::INITIATED.to_string());
+        let (inserted, reinforced) = graph.plan_upsert_edges([observe(), other, observe()]);
+        assert_eq!((inserted.len(), reinforced.len()), (1, 1));
+        assert_eq!(graph.generation(), generation);
+        assert_eq!(graph.edge_count(), 1);
+        let stored = graph.find_edge_by_key(&entity, &event, relation_types::PARTICIPATES_IN).unwrap();
+        assert!(reinforced[0].weight > stored.weight);
+
+        graph.apply_upserted_edges(&inserted, &reinforced);
+        assert_eq!(graph.edge_count(), 2);
+        let stored = graph.find_edge_by_key(&entity, &event, relation_types::PARTICIPATES_IN).unwrap();
+        assert_eq!(stored.weight, reinforced[0].weight);
+    }
+
+    #[test]
+    fn test_remove_keeps_other_indices_valid() {
+        let mut graph = KnowledgeGraph::new();
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(Edge::new(pair[0], pair[1], "relates".to_string()));
+        }
+        let tail_edge = graph.get_outgoing_edges(&ids[3])[0].clone();
+
+        assert_eq!(graph.remove_nodes(&[ids[0], ids[2], Uuid::new_v4()]), 2);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 1);
+        assert!(!graph.contains_node(&ids[2]));
+        assert!(graph.get_neighbors(&ids[3], 1).contains(&ids[4]));
+        assert!(!graph.get_neighbors(&ids[1], 1).contains(&ids[2]));
+
+        let removed = graph.remove_edge(&tail_edge.id).unwrap();
+        assert_eq!(removed.target, ids[4]);
+        assert!(!graph.contains_edge(&tail_edge.id));
+        assert!(graph.remove_edge(&tail_edge.id).is_none());
+        assert_eq!(graph.node_count(), 3);
+
+        // 删除后新加入的节点与边仍可正常访问
+        let fresh = Uuid::new_v4();
+        graph.add_edge(Edge::new(ids[1], fresh, "relates".to_string()));
+        assert!(graph.get_neighbors(&ids[1], 1).contains(&fresh));
+    }
 }
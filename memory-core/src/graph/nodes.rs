@@ -96,6 +96,22 @@ impl NodeBuilder {
         self
     }
 
+    /// 设置来源消息的角色（发起事件的角色在前）
+    pub fn roles(mut self, roles: Vec<String>) -> Self {
+        if let NodeData::Event { roles: ref mut r, .. } = self.node.data {
+            *r = roles;
+        }
+        self
+    }
+
+    /// 设置概念实例数
+    pub fn instance_count(mut self, count: u32) -> Self {
+        if let NodeData::Concept { instance_count: ref mut c, .. } = self.node.data {
+            *c = count;
+        }
+        self
+    }
+
     /// 构建节点
     pub fn build(self) -> MemoryNode {
         self.node
@@ -164,14 +180,14 @@ pub fn merge_nodes(existing: &mut MemoryNode, new_node: &MemoryNode) {
         existing.importance = new_node.importance;
     }
     
-    // 更新嵌入（如果新节点有更新的嵌入）
-    if !new_node.embedding.is_empty() {
+    // 补全嵌入（保留节点的内容不变，已有嵌入与内容对应，不被覆盖）
+    if existing.embedding.is_empty() && !new_node.embedding.is_empty() {
         existing.embedding = new_node.embedding.clone();
     }
     
-    // 合并参与者列表（用于事件节点）
-    if let (NodeData::Event { participants: ref mut existing_parts, .. },
-            NodeData::Event { participants: ref new_parts, .. }) =
+    // 合并参与者与来源角色列表（用于事件节点）
+    if let (NodeData::Event { participants: ref mut existing_parts, roles: ref mut existing_roles, .. },
+            NodeData::Event { participants: ref new_parts, roles: ref new_roles, .. }) =
         (&mut existing.data, &new_node.data)
     {
         for p in new_parts {
@@ -179,6 +195,34 @@ pub fn merge_nodes(existing: &mut MemoryNode, new_node: &MemoryNode) {
                 existing_parts.push(*p);
             }
         }
+        for role in new_roles {
+            if !existing_roles.contains(role) {
+                existing_roles.push(role.clone());
+            }
+        }
+    }
+
+    // 实体名称不同时把新名称与其别名记为保留节点的别名
+    if let (NodeData::Entity { aliases: ref mut existing_aliases, .. },
+            NodeData::Entity { aliases: ref new_aliases, .. }) =
+        (&mut existing.data, &new_node.data)
+    {
+        for name in std::iter::once(&new_node.content).chain(new_aliases) {
+            if *name != existing.content && !existing_aliases.contains(name) {
+                existing_aliases.push(name.clone());
+            }
+        }
+    }
+}
+
+/// 记录概念被使用
+///
+/// 实例数累加新归入该概念的节点数，并刷新最近使用时间；非概念节点不变
+pub fn touch_concept(node: &mut MemoryNode, new_instances: u32) {
+    if let NodeData::Concept { instance_count, last_used_at } = &mut node.data {
+        *instance_count = instance_count.saturating_add(new_instances);
+        *last_used_at = Utc::now().timestamp();
+        node.updated_at = *last_used_at;
     }
 }
 
@@ -199,28 +243,82 @@ pub fn calculate_decayed_weight(node: &MemoryNode, decay_rate: f64) -> f64 {
     (node.importance as f64) * decay_factor * access_boost
 }
 
+/// 英文人物关键词
+const ENGLISH_PERSON_WORDS: &[&str] = &[
+    "mom", "mum", "mother", "dad", "father", "parents", "brother", "sister", "son", "daughter",
+    "wife", "husband", "grandma", "grandpa", "grandmother", "grandfather", "uncle", "aunt",
+    "cousin", "friend", "boyfriend", "girlfriend", "colleague", "coworker", "boss", "teacher",
+    "doctor", "nurse", "neighbor", "neighbour", "roommate", "classmate", "kid", "kids", "baby",
+    "family", "mr", "mrs", "ms", "dr",
+];
+
+/// 英文地点关键词
+const ENGLISH_PLACE_WORDS: &[&str] = &[
+    "home", "house", "school", "office", "company", "hospital", "clinic", "store", "shop",
+    "supermarket", "mall", "restaurant", "cafe", "park", "station", "airport", "library", "gym",
+    "university", "college", "hotel", "beach", "city", "street", "road", "avenue", "church",
+    "museum", "bank", "pharmacy", "downtown", "london", "paris", "tokyo", "beijing", "shanghai",
+    "berlin", "seattle", "boston", "chicago", "china", "japan", "america", "usa", "uk", "france",
+    "germany", "canada",
+];
+
+/// 英文时间关键词
+const ENGLISH_TIME_WORDS: &[&str] = &[
+    "today", "yesterday", "tomorrow", "tonight", "morning", "afternoon", "evening", "night",
+    "weekend", "week", "month", "year", "monday", "tuesday", "wednesday", "thursday", "friday",
+    "saturday", "sunday", "christmas",
+];
+
+/// 英文月份（"may""march" 也是常用词，只有首字母大写或后跟日期时才视为时间）
+const ENGLISH_MONTHS: &[&str] = &[
+    "january", "february", "march", "april", "may", "june", "july", "august", "september",
+    "october", "november", "december",
+];
+
+/// 是否为日期数字（5、12th、1st）
+fn is_day_number(word: &str) -> bool {
+    let digits = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    digits.parse::<u32>().is_ok_and(|day| (1..=31).contains(&day))
+}
+
 /// 从内容推断实体类型
 ///
-/// 中文不需要 to_lowercase，直接使用原始字符匹配
+/// 中文关键词直接按子串匹配；英文按单词（不区分大小写）匹配，避免 "the" 命中 "he"；
+/// 月份名只有首字母大写或后跟日期（"may 5th"）时才算时间
 pub fn infer_entity_type(content: &str) -> EntityType {
+    let raw: Vec<&str> = content
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let words: Vec<String> = raw.iter().map(|w| w.to_ascii_lowercase()).collect();
+    let has_word = |lexicon: &[&str]| words.iter().any(|w| lexicon.contains(&w.as_str()));
+    let has_month = words.iter().enumerate().any(|(i, w)| {
+        ENGLISH_MONTHS.contains(&w.as_str())
+            && (raw[i].starts_with(|c: char| c.is_ascii_uppercase())
+                || raw.get(i + 1).is_some_and(|next| is_day_number(next)))
+    });
+
     // 人物关键词
     let person_keywords = ["我", "你", "他", "她", "哥", "姐", "弟", "妹", 
                           "爸", "妈", "老师", "朋友", "同学"];
-    if person_keywords.iter().any(|k| content.contains(k)) {
+    if person_keywords.iter().any(|k| content.contains(k)) || has_word(ENGLISH_PERSON_WORDS) {
         return EntityType::Person;
     }
     
     // 地点关键词
     let place_keywords = ["家", "学校", "公司", "商店", "餐厅", "公园", 
                          "医院", "车站", "机场"];
-    if place_keywords.iter().any(|k| content.contains(k)) {
+    if place_keywords.iter().any(|k| content.contains(k)) || has_word(ENGLISH_PLACE_WORDS) {
         return EntityType::Place;
     }
     
     // 时间关键词
     let time_keywords = ["今天", "昨天", "明天", "上午", "下午", "晚上", 
                         "周一", "周末", "月", "年"];
-    if time_keywords.iter().any(|k| content.contains(k)) {
+    if time_keywords.iter().any(|k| content.contains(k)) || has_word(ENGLISH_TIME_WORDS) || has_month {
         return EntityType::Time;
     }
     
@@ -255,11 +353,47 @@ mod tests {
         assert!(result.errors.iter().any(|e| e.contains("内容不能为空")));
     }
 
+    #[test]
+    fn test_merge_nodes_records_aliases() {
+        let mut mom = NodeBuilder::entity("妈妈").entity_type(EntityType::Person).build();
+        let mut old_mom = NodeBuilder::entity("老妈").entity_type(EntityType::Person).build();
+        if let NodeData::Entity { aliases, .. } = &mut old_mom.data {
+            aliases.push("妈".to_string());
+        }
+
+        merge_nodes(&mut mom, &old_mom);
+        merge_nodes(&mut mom, &NodeBuilder::entity("妈妈").build());
+        merge_nodes(&mut mom, &old_mom);
+        assert_eq!(mom.content, "妈妈");
+        assert_eq!(mom.aliases(), ["老妈".to_string(), "妈".to_string()]);
+    }
+
+    #[test]
+    fn test_touch_concept() {
+        let mut concept = NodeBuilder::concept("人物").instance_count(0).build();
+        touch_concept(&mut concept, 2);
+        touch_concept(&mut concept, 0);
+        assert_eq!(concept.instance_count(), Some(2));
+        assert!(concept.last_used_at().unwrap() > 0);
+
+        let mut entity = NodeBuilder::entity("妈妈").build();
+        touch_concept(&mut entity, 1);
+        assert_eq!(entity.instance_count(), None);
+    }
+
     #[test]
     fn test_infer_entity_type() {
         assert_eq!(infer_entity_type("我的朋友张三"), EntityType::Person);
         assert_eq!(infer_entity_type("北京的公园"), EntityType::Place);
         assert_eq!(infer_entity_type("今天下午"), EntityType::Time);
         assert_eq!(infer_entity_type("一本书"), EntityType::Other);
+        assert_eq!(infer_entity_type("my Mom"), EntityType::Person);
+        assert_eq!(infer_entity_type("Central Park"), EntityType::Place);
+        assert_eq!(infer_entity_type("next Friday"), EntityType::Time);
+        assert_eq!(infer_entity_type("the laptop"), EntityType::Other);
+        assert_eq!(infer_entity_type("March"), EntityType::Time);
+        assert_eq!(infer_entity_type("may 5th"), EntityType::Time);
+        assert_eq!(infer_entity_type("may"), EntityType::Other);
+        assert_eq!(infer_entity_type("march on"), EntityType::Other);
     }
 }
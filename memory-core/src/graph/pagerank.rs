@@ -0,0 +1,228 @@
+//! Personalized PageRank
+//!
+//! HippoRAG 的核心检索算法：以向量检索命中的节点作为个性化种子，
+//! 在知识图谱上做带重启的随机游走，按稳态概率对节点排序，
+//! 从而召回与种子多跳相连、且被多条路径共同指向的节点
+
+use super::KnowledgeGraph;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, NodeIndexable};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// PageRank 配置
+#[derive(Debug, Clone)]
+pub struct PageRankConfig {
+    /// 阻尼系数（沿边继续游走的概率，1 - damping 为回到种子的概率）
+    pub damping: f64,
+    /// 最大迭代次数
+    pub max_iterations: usize,
+    /// 收敛阈值（两次迭代的 L1 差值）
+    pub tolerance: f64,
+}
+
+impl Default for PageRankConfig {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            max_iterations: 50,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+impl KnowledgeGraph {
+    /// Personalized PageRank
+    ///
+    /// 图按无向处理，转移概率与边权重成正比；没有边的节点把概率交还给种子
+    ///
+    /// # Arguments
+    /// * `seeds` - 种子节点及其权重（如向量相似度），不要求归一化
+    /// * `config` - 阻尼系数与迭代限制
+    ///
+    /// # Returns
+    /// 按得分降序排列的 (节点 ID, 得分)，不包含得分为 0 的节点
+    pub fn personalized_pagerank(
+        &self,
+        seeds: &HashMap<Uuid, f64>,
+        config: &PageRankConfig,
+    ) -> Vec<(Uuid, f64)> {
+        let bound = self.graph.node_bound();
+        if bound == 0 {
+            return Vec::new();
+        }
+
+        // 个性化向量
+        let mut personalization = vec![0.0f64; bound];
+        let mut seed_total = 0.0;
+        for (id, weight) in seeds {
+            if let Some(idx) = self.id_to_index.get(id) {
+                let weight = weight.max(0.0);
+                personalization[idx.index()] += weight;
+                seed_total += weight;
+            }
+        }
+        if seed_total <= 0.0 {
+            return Vec::new();
+        }
+        for p in personalization.iter_mut() {
+            *p /= seed_total;
+        }
+
+        self.power_iteration(&personalization, config)
+    }
+
+    /// 全局 PageRank（均匀个性化向量）
+    pub fn pagerank(&self, config: &PageRankConfig) -> Vec<(Uuid, f64)> {
+        let bound = self.graph.node_bound();
+        let node_count = self.graph.node_count();
+        if node_count == 0 {
+            return Vec::new();
+        }
+
+        let mut personalization = vec![0.0f64; bound];
+        for idx in self.graph.node_indices() {
+            personalization[idx.index()] = 1.0 / node_count as f64;
+        }
+
+        self.power_iteration(&personalization, config)
+    }
+
+    /// 幂迭代求解
+    fn power_iteration(&self, personalization: &[f64], config: &PageRankConfig) -> Vec<(Uuid, f64)> {
+        let bound = personalization.len();
+        let damping = config.damping.clamp(0.0, 1.0);
+
+        // 每个节点的总出权重（无向：出边 + 入边）
+        let mut strength = vec![0.0f64; bound];
+        for edge in self.graph.edge_references() {
+            let w = edge.weight().weight.max(0.0) as f64;
+            strength[edge.source().index()] += w;
+            strength[edge.target().index()] += w;
+        }
+
+        let mut rank = personalization.to_vec();
+        let mut next = vec![0.0f64; bound];
+
+        for iteration in 0..config.max_iterations {
+            next.iter_mut().for_each(|v| *v = 0.0);
+
+            for edge in self.graph.edge_references() {
+                let w = edge.weight().weight.max(0.0) as f64;
+                if w == 0.0 {
+                    continue;
+                }
+                let s = edge.source().index();
+                let t = edge.target().index();
+                next[t] += damping * rank[s] * w / strength[s];
+                next[s] += damping * rank[t] * w / strength[t];
+            }
+
+            // 无出边节点的概率与重启概率一起回到种子
+            let dangling: f64 = (0..bound)
+                .filter(|&i| strength[i] == 0.0)
+                .map(|i| rank[i])
+                .sum();
+            let restart = (1.0 - damping) + damping * dangling;
+            for (v, p) in next.iter_mut().zip(personalization) {
+                *v += restart * p;
+            }
+
+            let delta: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            std::mem::swap(&mut rank, &mut next);
+            if delta < config.tolerance {
+                log::debug!("PageRank 在第 {} 轮收敛", iteration + 1);
+                break;
+            }
+        }
+
+        let mut scores: Vec<(Uuid, f64)> = self.graph.node_indices()
+            .filter(|idx| rank[idx.index()] > 0.0)
+            .map(|idx| (self.graph[idx], rank[idx.index()]))
+            .collect();
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+    use crate::types::Edge;
+
+    fn position(scores: &[(Uuid, f64)], id: &Uuid) -> Option<usize> {
+        scores.iter().position(|(n, _)| n == id)
+    }
+
+    #[test]
+    fn test_ppr_multi_hop_recall() {
+        // 事件"去医院看病" ← 妈妈 → 事件"妈妈生日" ← 蛋糕
+        //                 ← 医院 → 事件"医院体检"
+        // 另有一个与种子无关的连通块：小猫 → 事件"喂猫"
+        let mut graph = KnowledgeGraph::new();
+        let hospital_visit = Uuid::new_v4();
+        let mom = Uuid::new_v4();
+        let hospital = Uuid::new_v4();
+        let birthday = Uuid::new_v4();
+        let cake = Uuid::new_v4();
+        let checkup = Uuid::new_v4();
+        let cat = Uuid::new_v4();
+        let feed_cat = Uuid::new_v4();
+
+        for (entity, event) in [
+            (mom, hospital_visit),
+            (hospital, hospital_visit),
+            (mom, birthday),
+            (cake, birthday),
+            (hospital, checkup),
+            (cat, feed_cat),
+        ] {
+            graph.add_edge(Edge::new(entity, event, relation_types::PARTICIPATES_IN.to_string()));
+        }
+
+        let seeds = HashMap::from([(hospital_visit, 1.0)]);
+        let scores = graph.personalized_pagerank(&seeds, &PageRankConfig::default());
+
+        // 种子排第一，两跳外的事件与三跳外的实体都被召回
+        assert_eq!(scores[0].0, hospital_visit);
+        assert!(position(&scores, &birthday).is_some());
+        assert!(position(&scores, &cake).is_some());
+        // 与种子不连通的节点得分为 0
+        assert!(position(&scores, &cat).is_none());
+        assert!(position(&scores, &feed_cat).is_none());
+        // 一跳实体排在两跳事件之前，两跳事件排在三跳实体之前
+        assert!(position(&scores, &mom) < position(&scores, &birthday));
+        assert!(position(&scores, &birthday) < position(&scores, &cake));
+    }
+
+    #[test]
+    fn test_ppr_respects_edge_weight() {
+        let mut graph = KnowledgeGraph::new();
+        let seed = Uuid::new_v4();
+        let strong = Uuid::new_v4();
+        let weak = Uuid::new_v4();
+
+        let mut strong_edge = Edge::new(seed, strong, "relates_to".to_string());
+        strong_edge.weight = 1.0;
+        let mut weak_edge = Edge::new(seed, weak, "relates_to".to_string());
+        weak_edge.weight = 0.1;
+        graph.add_edge(strong_edge);
+        graph.add_edge(weak_edge);
+
+        let seeds = HashMap::from([(seed, 1.0)]);
+        let scores = graph.personalized_pagerank(&seeds, &PageRankConfig::default());
+        assert!(position(&scores, &strong) < position(&scores, &weak));
+
+        let total: f64 = scores.iter().map(|(_, s)| s).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ppr_unknown_seeds() {
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(Edge::new(Uuid::new_v4(), Uuid::new_v4(), "relates_to".to_string()));
+
+        let seeds = HashMap::from([(Uuid::new_v4(), 1.0)]);
+        assert!(graph.personalized_pagerank(&seeds, &PageRankConfig::default()).is_empty());
+    }
+}
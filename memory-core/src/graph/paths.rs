@@ -0,0 +1,450 @@
+//! 加权路径搜索
+//!
+//! 基于 Dijkstra 的加权最短路径与 Yen 算法的 K 条最短路径，
+//! 边代价由边权重换算（权重越高代价越低），可按关系类型和方向限制
+
+use super::{KnowledgeGraph, TraversalDirection, TraversalOptions};
+use crate::types::{ConnectionPath, Edge, MemoryNode, NodeType, PathNode, PathRelation};
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// 计算代价时的最小边权重，防止权重为 0 的边代价无穷大
+const MIN_EDGE_WEIGHT: f32 = 0.01;
+
+/// 路径搜索选项
+#[derive(Debug, Clone, Default)]
+pub struct PathOptions {
+    /// 关系类型、方向、边权重与中间节点类型限制
+    pub traversal: TraversalOptions,
+    /// 路径最多包含的边数（None 表示不限制）
+    pub max_hops: Option<usize>,
+}
+
+impl PathOptions {
+    /// 只允许指定的关系类型
+    pub fn with_relations<I, S>(mut self, relations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.traversal = self.traversal.with_relations(relations);
+        self
+    }
+
+    /// 设置遍历方向
+    pub fn with_direction(mut self, direction: TraversalDirection) -> Self {
+        self.traversal.direction = direction;
+        self
+    }
+}
+
+/// 加权路径
+#[derive(Debug, Clone)]
+pub struct WeightedPath {
+    /// 路径上的节点（含起点和终点）
+    pub nodes: Vec<Uuid>,
+    /// 依次经过的边，`edges[i]` 连接 `nodes[i]` 与 `nodes[i + 1]`
+    pub edges: Vec<Edge>,
+    /// 总代价
+    pub cost: f64,
+}
+
+impl WeightedPath {
+    /// 第 i 条边是否按其方向（source → target）经过
+    pub fn is_forward(&self, i: usize) -> bool {
+        self.edges.get(i).is_some_and(|e| e.source == self.nodes[i])
+    }
+
+    fn edge_ids(&self) -> Vec<Uuid> {
+        self.edges.iter().map(|e| e.id).collect()
+    }
+}
+
+/// 边代价：权重的倒数
+pub fn edge_cost(edge: &Edge) -> f64 {
+    1.0 / edge.weight.max(MIN_EDGE_WEIGHT) as f64
+}
+
+/// 将图路径与节点内容拼接为 ConnectionPath
+///
+/// `nodes` 中缺失的节点以其 ID 代替内容
+pub fn describe_path(path: &WeightedPath, nodes: &HashMap<Uuid, MemoryNode>) -> ConnectionPath {
+    let path_nodes: Vec<PathNode> = path.nodes.iter()
+        .map(|id| match nodes.get(id) {
+            Some(node) => PathNode {
+                id: *id,
+                content: node.content.clone(),
+                node_type: node.node_type(),
+            },
+            None => PathNode {
+                id: *id,
+                content: id.to_string(),
+                node_type: NodeType::Entity,
+            },
+        })
+        .collect();
+
+    let relations: Vec<PathRelation> = path.edges.iter()
+        .enumerate()
+        .map(|(i, edge)| PathRelation {
+            relation: edge.relation.clone(),
+            weight: edge.weight,
+            forward: path.is_forward(i),
+        })
+        .collect();
+
+    let mut description = path_nodes.first()
+        .map(|n| n.content.clone())
+        .unwrap_or_default();
+    for (relation, node) in relations.iter().zip(path_nodes.iter().skip(1)) {
+        if relation.forward {
+            description.push_str(&format!(" -[{}]-> {}", relation.relation, node.content));
+        } else {
+            description.push_str(&format!(" <-[{}]- {}", relation.relation, node.content));
+        }
+    }
+
+    ConnectionPath {
+        nodes: path_nodes,
+        relations,
+        cost: path.cost,
+        description,
+    }
+}
+
+/// Dijkstra 搜索状态：(节点, 已用跳数)
+type SearchState = (NodeIndex, usize);
+
+/// Dijkstra 优先队列条目（按代价升序）
+#[derive(Clone, Copy)]
+struct QueueEntry {
+    cost: f64,
+    node: NodeIndex,
+    hops: usize,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl KnowledgeGraph {
+    /// 加权最短路径
+    ///
+    /// # Returns
+    /// 代价最小的路径；不连通或被选项过滤时返回 None
+    pub fn find_weighted_path(
+        &self,
+        from: &Uuid,
+        to: &Uuid,
+        options: &PathOptions,
+    ) -> Option<WeightedPath> {
+        let start = *self.id_to_index.get(from)?;
+        let end = *self.id_to_index.get(to)?;
+        self.dijkstra(&[start], end, options, &HashSet::new(), &HashSet::new())
+    }
+
+    /// 从多个起点中任意一个出发的加权最短路径
+    ///
+    /// # Returns
+    /// 代价最小的路径，起点为距离终点最近的那个源节点；都不连通时返回 None
+    pub fn find_weighted_path_from_any(
+        &self,
+        sources: &[Uuid],
+        to: &Uuid,
+        options: &PathOptions,
+    ) -> Option<WeightedPath> {
+        let end = *self.id_to_index.get(to)?;
+        let starts: Vec<NodeIndex> = sources.iter()
+            .filter_map(|id| self.id_to_index.get(id).copied())
+            .collect();
+        if starts.is_empty() {
+            return None;
+        }
+        self.dijkstra(&starts, end, options, &HashSet::new(), &HashSet::new())
+    }
+
+    /// K 条最短无环路径（Yen 算法）
+    ///
+    /// # Returns
+    /// 按代价升序排列的至多 k 条路径
+    pub fn find_k_shortest_paths(
+        &self,
+        from: &Uuid,
+        to: &Uuid,
+        k: usize,
+        options: &PathOptions,
+    ) -> Vec<WeightedPath> {
+        let mut found: Vec<WeightedPath> = Vec::new();
+        if k == 0 {
+            return found;
+        }
+        let (Some(&start), Some(&end)) = (self.id_to_index.get(from), self.id_to_index.get(to)) else {
+            return found;
+        };
+        let Some(first) = self.dijkstra(&[start], end, options, &HashSet::new(), &HashSet::new()) else {
+            return found;
+        };
+        found.push(first);
+
+        let mut candidates: Vec<WeightedPath> = Vec::new();
+        while found.len() < k {
+            let last = found[found.len() - 1].clone();
+
+            for i in 0..last.edges.len() {
+                let spur_node = last.nodes[i];
+                let root_nodes = &last.nodes[..=i];
+                let root_edges = &last.edges[..i];
+
+                // 禁用与当前根路径相同的已知路径的下一条边
+                let mut banned_edges: HashSet<Uuid> = HashSet::new();
+                for path in &found {
+                    if path.nodes.len() > i && path.nodes[..=i] == *root_nodes {
+                        if let Some(edge) = path.edges.get(i) {
+                            banned_edges.insert(edge.id);
+                        }
+                    }
+                }
+
+                // 禁用根路径上除分叉点外的节点，保证无环
+                let banned_nodes: HashSet<NodeIndex> = root_nodes[..i]
+                    .iter()
+                    .filter_map(|id| self.id_to_index.get(id).copied())
+                    .collect();
+
+                let spur_options = PathOptions {
+                    max_hops: options.max_hops.map(|h| h.saturating_sub(i)),
+                    ..options.clone()
+                };
+                let spur_idx = self.id_to_index[&spur_node];
+                let Some(spur) = self.dijkstra(&[spur_idx], end, &spur_options, &banned_nodes, &banned_edges) else {
+                    continue;
+                };
+
+                let mut nodes = root_nodes.to_vec();
+                nodes.extend_from_slice(&spur.nodes[1..]);
+                let mut edges = root_edges.to_vec();
+                edges.extend(spur.edges);
+                let cost = edges.iter().map(edge_cost).sum();
+                let candidate = WeightedPath { nodes, edges, cost };
+
+                let ids = candidate.edge_ids();
+                let duplicate = found.iter().chain(candidates.iter())
+                    .any(|p| p.edge_ids() == ids);
+                if !duplicate {
+                    candidates.push(candidate);
+                }
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
+
+            let best = candidates.iter()
+                .enumerate()
+                .min_by(|a, b| a.1.cost.partial_cmp(&b.1.cost).unwrap_or(Ordering::Equal))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            found.push(candidates.swap_remove(best));
+        }
+
+        found
+    }
+
+    /// Dijkstra 搜索（支持多起点、禁用节点和边，供 Yen 算法复用）
+    ///
+    /// 限制跳数时以 (节点, 已用跳数) 为搜索状态：代价更低但跳数更多的路径
+    /// 不会挡住跳数限制内代价稍高的路径
+    fn dijkstra(
+        &self,
+        starts: &[NodeIndex],
+        end: NodeIndex,
+        options: &PathOptions,
+        banned_nodes: &HashSet<NodeIndex>,
+        banned_edges: &HashSet<Uuid>,
+    ) -> Option<WeightedPath> {
+        // 不限跳数时所有状态的跳数维度取 0，退化为普通 Dijkstra
+        let state_of = |node: NodeIndex, hops: usize| (node, if options.max_hops.is_some() { hops } else { 0 });
+
+        let mut best_cost: HashMap<SearchState, f64> = HashMap::new();
+        // 状态 -> (前驱状态, 到达所用的边)
+        let mut previous: HashMap<SearchState, (SearchState, &Edge)> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+
+        for &start in starts {
+            best_cost.insert(state_of(start, 0), 0.0);
+            heap.push(QueueEntry { cost: 0.0, node: start, hops: 0 });
+        }
+
+        let mut reached = None;
+        while let Some(QueueEntry { cost, node, hops }) = heap.pop() {
+            let state = state_of(node, hops);
+            if cost > best_cost.get(&state).copied().unwrap_or(f64::INFINITY) {
+                continue;
+            }
+            // 按代价升序出队，第一次出队的终点状态即最优
+            if node == end {
+                reached = Some((state, cost));
+                break;
+            }
+            if options.max_hops.is_some_and(|max| hops >= max) {
+                continue;
+            }
+
+            for &direction in options.traversal.direction.petgraph_directions() {
+                for edge_ref in self.graph.edges_directed(node, direction) {
+                    let edge = edge_ref.weight();
+                    if banned_edges.contains(&edge.id) || !options.traversal.allows_edge(edge) {
+                        continue;
+                    }
+                    let next = if edge_ref.source() == node { edge_ref.target() } else { edge_ref.source() };
+                    if banned_nodes.contains(&next) {
+                        continue;
+                    }
+                    // 终点不受节点类型限制，只过滤中间节点
+                    if next != end && !options.traversal.allows_node_type(self.node_type(&self.graph[next])) {
+                        continue;
+                    }
+
+                    let next_cost = cost + edge_cost(edge);
+                    let next_state = state_of(next, hops + 1);
+                    if next_cost < best_cost.get(&next_state).copied().unwrap_or(f64::INFINITY) {
+                        best_cost.insert(next_state, next_cost);
+                        previous.insert(next_state, (state, edge));
+                        heap.push(QueueEntry { cost: next_cost, node: next, hops: hops + 1 });
+                    }
+                }
+            }
+        }
+
+        let (end_state, total) = reached?;
+
+        // 回溯路径（起点没有前驱）
+        let mut nodes = vec![self.graph[end]];
+        let mut edges = Vec::new();
+        let mut current = end_state;
+        while let Some((prev, edge)) = previous.get(&current) {
+            edges.push((*edge).clone());
+            nodes.push(self.graph[prev.0]);
+            current = *prev;
+        }
+        nodes.reverse();
+        edges.reverse();
+
+        Some(WeightedPath { nodes, edges, cost: total })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+
+    fn weighted_edge(source: Uuid, target: Uuid, relation: &str, weight: f32) -> Edge {
+        let mut edge = Edge::new(source, target, relation.to_string());
+        edge.weight = weight;
+        edge
+    }
+
+    /// a -(弱)-> d 直连；a -> b -> d 与 a -> c -> d 两条强路径
+    fn diamond() -> (KnowledgeGraph, [Uuid; 4]) {
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let [a, b, c, d] = ids;
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(weighted_edge(a, d, relation_types::RELATES_TO, 0.1));
+        graph.add_edge(weighted_edge(a, b, relation_types::KNOWS, 1.0));
+        graph.add_edge(weighted_edge(b, d, relation_types::KNOWS, 1.0));
+        graph.add_edge(weighted_edge(a, c, relation_types::RELATES_TO, 0.5));
+        graph.add_edge(weighted_edge(c, d, relation_types::RELATES_TO, 0.5));
+        (graph, ids)
+    }
+
+    #[test]
+    fn test_weighted_path_prefers_strong_edges() {
+        let (graph, [a, b, _, d]) = diamond();
+        let path = graph.find_weighted_path(&a, &d, &PathOptions::default()).unwrap();
+        assert_eq!(path.nodes, vec![a, b, d]);
+        assert!((path.cost - 2.0).abs() < 1e-9);
+        assert!(path.is_forward(0));
+    }
+
+    #[test]
+    fn test_relation_and_direction_filters() {
+        let (graph, [a, _, c, d]) = diamond();
+
+        let options = PathOptions::default().with_relations([relation_types::RELATES_TO]);
+        let path = graph.find_weighted_path(&a, &d, &options).unwrap();
+        assert_eq!(path.nodes, vec![a, c, d]);
+
+        // 逆着边的方向无法从 a 到达 d
+        let options = PathOptions::default().with_direction(TraversalDirection::Incoming);
+        assert!(graph.find_weighted_path(&a, &d, &options).is_none());
+        let path = graph.find_weighted_path(&d, &a, &options).unwrap();
+        assert!(!path.is_forward(0));
+    }
+
+    #[test]
+    fn test_path_from_nearest_source() {
+        let (graph, [a, b, c, d]) = diamond();
+        let path = graph.find_weighted_path_from_any(&[a, c], &d, &PathOptions::default()).unwrap();
+        assert_eq!(path.nodes, vec![c, d]);
+
+        let nodes: HashMap<Uuid, MemoryNode> = HashMap::new();
+        let described = describe_path(&path, &nodes);
+        assert_eq!(described.description, format!("{} -[relates_to]-> {}", c, d));
+
+        assert!(graph.find_weighted_path_from_any(&[Uuid::new_v4()], &b, &PathOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_k_shortest_paths() {
+        let (graph, [a, b, c, d]) = diamond();
+        let paths = graph.find_k_shortest_paths(&a, &d, 5, &PathOptions::default());
+
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[0].nodes, vec![a, b, d]);
+        assert_eq!(paths[1].nodes, vec![a, c, d]);
+        assert_eq!(paths[2].nodes, vec![a, d]);
+        assert!(paths.windows(2).all(|w| w[0].cost <= w[1].cost));
+    }
+
+    #[test]
+    fn test_max_hops_keeps_costlier_path_within_limit() {
+        // a -> x -> y -> d 最便宜但需要 3 跳；a -> y -> d 代价更高但只需 2 跳
+        let [a, x, y, d] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(weighted_edge(a, x, relation_types::KNOWS, 1.0));
+        graph.add_edge(weighted_edge(x, y, relation_types::KNOWS, 1.0));
+        graph.add_edge(weighted_edge(a, y, relation_types::KNOWS, 0.2));
+        graph.add_edge(weighted_edge(y, d, relation_types::KNOWS, 1.0));
+
+        let unlimited = graph.find_weighted_path(&a, &d, &PathOptions::default()).unwrap();
+        assert_eq!(unlimited.nodes, vec![a, x, y, d]);
+
+        let options = PathOptions { max_hops: Some(2), ..PathOptions::default() };
+        let path = graph.find_weighted_path(&a, &d, &options).unwrap();
+        assert_eq!(path.nodes, vec![a, y, d]);
+        assert!((path.cost - 6.0).abs() < 1e-6);
+
+        let options = PathOptions { max_hops: Some(1), ..PathOptions::default() };
+        assert!(graph.find_weighted_path(&a, &d, &options).is_none());
+    }
+}
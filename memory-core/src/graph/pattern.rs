@@ -0,0 +1,943 @@
+//! 图模式查询
+//!
+//! 在知识图谱上按节点模式（类型、内容条件）和关系模式（关系类型、方向、跳数范围）
+//! 匹配子图，返回变量绑定。既可以用 `PatternQuery` 构建器构造，
+//! 也可以解析类 Cypher 的文本：
+//!
+//! ```text
+//! MATCH (m:entity {content: "妈妈"})-[:participates_in]->(e:event)
+//!       <-[:participates_in]-(h:entity {content: "医院"})
+//! RETURN e LIMIT 10
+//! ```
+//!
+//! 内容条件需要节点内容，由调用方在存储中解析为候选节点集合后传入
+
+use super::{filtered_neighbors, KnowledgeGraph, TraversalDirection, TraversalOptions};
+use crate::error::{MemoryError, Result};
+use crate::types::NodeType;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// 关系模式省略上限时（如 `*` 或 `*2..`）允许的最大跳数
+pub const MAX_PATTERN_HOPS: usize = 5;
+
+/// 节点内容条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentPredicate {
+    /// 内容完全相同
+    Equals(String),
+    /// 内容包含子串
+    Contains(String),
+}
+
+impl ContentPredicate {
+    /// 内容是否满足条件
+    pub fn matches(&self, content: &str) -> bool {
+        match self {
+            ContentPredicate::Equals(value) => content == value,
+            ContentPredicate::Contains(value) => content.contains(value.as_str()),
+        }
+    }
+}
+
+/// 节点模式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodePattern {
+    /// 变量名
+    pub variable: String,
+    /// 节点类型（None 表示不限制）
+    pub node_type: Option<NodeType>,
+    /// 内容条件（需同时满足）
+    pub content: Vec<ContentPredicate>,
+}
+
+impl NodePattern {
+    /// 创建不带条件的节点模式
+    pub fn new(variable: impl Into<String>) -> Self {
+        Self {
+            variable: variable.into(),
+            node_type: None,
+            content: Vec::new(),
+        }
+    }
+
+    /// 限制节点类型
+    pub fn node_type(mut self, node_type: NodeType) -> Self {
+        self.node_type = Some(node_type);
+        self
+    }
+
+    /// 要求内容完全相同
+    pub fn content_equals(mut self, value: impl Into<String>) -> Self {
+        self.content.push(ContentPredicate::Equals(value.into()));
+        self
+    }
+
+    /// 要求内容包含子串
+    pub fn content_contains(mut self, value: impl Into<String>) -> Self {
+        self.content.push(ContentPredicate::Contains(value.into()));
+        self
+    }
+}
+
+/// 关系模式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationPattern {
+    /// 允许的关系类型（为空表示不限制）
+    pub relations: Vec<String>,
+    /// 方向（相对于模式中左侧的节点）
+    pub direction: TraversalDirection,
+    /// 最少跳数
+    pub min_hops: usize,
+    /// 最多跳数
+    pub max_hops: usize,
+}
+
+impl Default for RelationPattern {
+    fn default() -> Self {
+        Self {
+            relations: Vec::new(),
+            direction: TraversalDirection::Outgoing,
+            min_hops: 1,
+            max_hops: 1,
+        }
+    }
+}
+
+impl RelationPattern {
+    /// 指定关系类型的出边
+    pub fn outgoing(relation: impl Into<String>) -> Self {
+        Self {
+            relations: vec![relation.into()],
+            ..Self::default()
+        }
+    }
+
+    /// 指定关系类型的入边
+    pub fn incoming(relation: impl Into<String>) -> Self {
+        Self {
+            relations: vec![relation.into()],
+            direction: TraversalDirection::Incoming,
+            ..Self::default()
+        }
+    }
+
+    /// 设置方向
+    pub fn direction(mut self, direction: TraversalDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// 设置跳数范围
+    pub fn hops(mut self, min_hops: usize, max_hops: usize) -> Self {
+        self.min_hops = min_hops;
+        self.max_hops = max_hops;
+        self
+    }
+
+    /// 对应的遍历选项（reversed 为 true 时从右侧节点反向行走）
+    fn traversal_options(&self, reversed: bool) -> TraversalOptions {
+        let direction = match (self.direction, reversed) {
+            (TraversalDirection::Outgoing, true) => TraversalDirection::Incoming,
+            (TraversalDirection::Incoming, true) => TraversalDirection::Outgoing,
+            (direction, _) => direction,
+        };
+        let options = TraversalOptions::default().with_direction(direction);
+        if self.relations.is_empty() {
+            options
+        } else {
+            options.with_relations(self.relations.iter().cloned())
+        }
+    }
+}
+
+/// 两个节点变量之间的关系
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternRelation {
+    /// 左侧变量
+    pub from: String,
+    /// 右侧变量
+    pub to: String,
+    /// 关系模式
+    pub pattern: RelationPattern,
+}
+
+/// 模式查询
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternQuery {
+    /// 节点模式（变量名唯一）
+    pub nodes: Vec<NodePattern>,
+    /// 关系模式
+    pub relations: Vec<PatternRelation>,
+    /// 返回的变量
+    pub returns: Vec<String>,
+    /// 最多返回的行数
+    pub limit: Option<usize>,
+}
+
+impl PatternQuery {
+    /// 创建空查询
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加节点模式（变量已存在时合并条件）
+    pub fn node(mut self, pattern: NodePattern) -> Self {
+        self.add_node(pattern);
+        self
+    }
+
+    /// 添加关系模式
+    pub fn relation(mut self, from: impl Into<String>, pattern: RelationPattern, to: impl Into<String>) -> Self {
+        self.relations.push(PatternRelation {
+            from: from.into(),
+            to: to.into(),
+            pattern,
+        });
+        self
+    }
+
+    /// 设置返回的变量
+    pub fn returns<I, S>(mut self, variables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.returns = variables.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 设置最多返回的行数
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// 解析类 Cypher 文本
+    ///
+    /// 支持 `MATCH 路径[, 路径] [WHERE 条件] RETURN 变量[, 变量] [LIMIT n]`：
+    /// - 节点：`(变量:类型 {content: "值"})`，类型为 entity / event / concept
+    /// - 关系：`-[:关系|关系*最少..最多]->`、`<-[...]-`、`-[...]-`，以及 `-->` 等简写
+    /// - 条件：`变量.content = "值"` 或 `变量.content CONTAINS "值"`，以 AND 连接
+    pub fn parse(text: &str) -> Result<Self> {
+        Parser::new(text)?.parse_query()
+    }
+
+    /// 按变量名查找节点模式
+    pub fn node_pattern(&self, variable: &str) -> Option<&NodePattern> {
+        self.nodes.iter().find(|n| n.variable == variable)
+    }
+
+    /// 检查变量引用与跳数范围
+    pub fn validate(&self) -> Result<()> {
+        if self.nodes.is_empty() {
+            return Err(pattern_error("查询没有节点模式"));
+        }
+        for relation in &self.relations {
+            for variable in [&relation.from, &relation.to] {
+                if self.node_pattern(variable).is_none() {
+                    return Err(pattern_error(&format!("关系引用了未定义的变量 {}", variable)));
+                }
+            }
+            let pattern = &relation.pattern;
+            if pattern.min_hops > pattern.max_hops || pattern.max_hops > MAX_PATTERN_HOPS {
+                return Err(pattern_error(&format!(
+                    "跳数范围 {}..{} 无效（上限 {}）",
+                    pattern.min_hops, pattern.max_hops, MAX_PATTERN_HOPS
+                )));
+            }
+        }
+        if self.returns.is_empty() {
+            return Err(pattern_error("查询没有 RETURN 变量"));
+        }
+        for variable in &self.returns {
+            if self.node_pattern(variable).is_none() {
+                return Err(pattern_error(&format!("RETURN 引用了未定义的变量 {}", variable)));
+            }
+        }
+        Ok(())
+    }
+
+    fn add_node(&mut self, pattern: NodePattern) {
+        match self.nodes.iter_mut().find(|n| n.variable == pattern.variable) {
+            Some(existing) => {
+                existing.node_type = existing.node_type.or(pattern.node_type);
+                existing.content.extend(pattern.content);
+            }
+            None => self.nodes.push(pattern),
+        }
+    }
+}
+
+/// 匹配计划中的一步
+enum PlanStep {
+    /// 枚举变量的候选节点
+    Scan(usize),
+    /// 从已绑定的一端沿关系扩展到未绑定的另一端
+    Expand(usize),
+    /// 检查两个已绑定变量之间的关系
+    Check(usize),
+}
+
+impl KnowledgeGraph {
+    /// 匹配模式查询
+    ///
+    /// 可变跳数关系按最短跳数判断是否落在范围内
+    ///
+    /// # Arguments
+    /// * `query` - 模式查询
+    /// * `candidates` - 变量 → 允许的节点集合（带内容条件的变量由调用方在存储中解析）
+    ///
+    /// # Returns
+    /// 去重后的结果行，每行按 `returns` 的顺序给出绑定的节点 ID
+    pub fn match_pattern(
+        &self,
+        query: &PatternQuery,
+        candidates: &HashMap<String, HashSet<Uuid>>,
+    ) -> Result<Vec<Vec<Uuid>>> {
+        query.validate()?;
+
+        let index: HashMap<&str, usize> = query.nodes.iter()
+            .enumerate()
+            .map(|(i, n)| (n.variable.as_str(), i))
+            .collect();
+        let endpoints: Vec<(usize, usize)> = query.relations.iter()
+            .map(|r| (index[r.from.as_str()], index[r.to.as_str()]))
+            .collect();
+        let plan = Self::plan_pattern(query, &endpoints, candidates);
+        let returns: Vec<usize> = query.returns.iter().map(|v| index[v.as_str()]).collect();
+
+        let mut matcher = PatternMatcher {
+            graph: self,
+            query,
+            candidates,
+            endpoints: &endpoints,
+            plan: &plan,
+            returns: &returns,
+            binding: vec![None; query.nodes.len()],
+            seen: HashSet::new(),
+            rows: Vec::new(),
+        };
+        matcher.search(0);
+        Ok(matcher.rows)
+    }
+
+    /// 生成匹配计划：优先从候选最少的变量开始，再沿关系逐步扩展
+    fn plan_pattern(
+        query: &PatternQuery,
+        endpoints: &[(usize, usize)],
+        candidates: &HashMap<String, HashSet<Uuid>>,
+    ) -> Vec<PlanStep> {
+        let mut plan = Vec::new();
+        let mut bound = vec![false; query.nodes.len()];
+        let mut used = vec![false; endpoints.len()];
+
+        while bound.iter().any(|b| !b) {
+            let expandable = endpoints.iter()
+                .enumerate()
+                .find(|(i, (from, to))| !used[*i] && bound[*from] != bound[*to]);
+            match expandable {
+                Some((relation, &(from, to))) => {
+                    used[relation] = true;
+                    bound[from] = true;
+                    bound[to] = true;
+                    plan.push(PlanStep::Expand(relation));
+                }
+                None => {
+                    let next = (0..query.nodes.len())
+                        .filter(|&i| !bound[i])
+                        .min_by_key(|&i| {
+                            candidates.get(&query.nodes[i].variable).map_or(usize::MAX, HashSet::len)
+                        });
+                    let Some(next) = next else { break };
+                    bound[next] = true;
+                    plan.push(PlanStep::Scan(next));
+                }
+            }
+
+            // 两端都已绑定的关系尽早检查
+            for (i, (from, to)) in endpoints.iter().enumerate() {
+                if !used[i] && bound[*from] && bound[*to] {
+                    used[i] = true;
+                    plan.push(PlanStep::Check(i));
+                }
+            }
+        }
+
+        plan
+    }
+
+    /// 从起点沿关系模式可到达、且跳数在范围内的节点
+    fn reach(&self, start: &Uuid, pattern: &RelationPattern, reversed: bool) -> HashSet<Uuid> {
+        let mut result = HashSet::new();
+        let Some(&start_idx) = self.id_to_index.get(start) else {
+            return result;
+        };
+        if pattern.min_hops == 0 {
+            result.insert(*start);
+        }
+
+        let options = pattern.traversal_options(reversed);
+        let mut visited = HashSet::from([start_idx]);
+        let mut frontier = vec![start_idx];
+        for depth in 1..=pattern.max_hops {
+            let mut next = Vec::new();
+            for idx in &frontier {
+                for (neighbor, _) in filtered_neighbors(&self.graph, &self.node_types, *idx, &options) {
+                    if visited.insert(neighbor) {
+                        next.push(neighbor);
+                        if depth >= pattern.min_hops {
+                            result.insert(self.graph[neighbor]);
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        result
+    }
+}
+
+/// 回溯匹配状态
+struct PatternMatcher<'a> {
+    graph: &'a KnowledgeGraph,
+    query: &'a PatternQuery,
+    candidates: &'a HashMap<String, HashSet<Uuid>>,
+    endpoints: &'a [(usize, usize)],
+    plan: &'a [PlanStep],
+    returns: &'a [usize],
+    binding: Vec<Option<Uuid>>,
+    seen: HashSet<Vec<Uuid>>,
+    rows: Vec<Vec<Uuid>>,
+}
+
+impl PatternMatcher<'_> {
+    fn is_full(&self) -> bool {
+        self.query.limit.is_some_and(|limit| self.rows.len() >= limit)
+    }
+
+    /// 节点是否满足变量的类型与候选条件
+    fn accepts(&self, variable: usize, id: &Uuid) -> bool {
+        let pattern = &self.query.nodes[variable];
+        self.graph.contains_node(id)
+            && pattern.node_type.is_none_or(|t| self.graph.node_type(id) == Some(t))
+            && self.candidates.get(&pattern.variable).is_none_or(|set| set.contains(id))
+    }
+
+    fn search(&mut self, step: usize) {
+        if self.is_full() {
+            return;
+        }
+        let Some(current) = self.plan.get(step) else {
+            let row: Vec<Uuid> = self.returns.iter()
+                .filter_map(|&v| self.binding[v])
+                .collect();
+            if self.seen.insert(row.clone()) {
+                self.rows.push(row);
+            }
+            return;
+        };
+
+        match *current {
+            PlanStep::Scan(variable) => {
+                let ids: Vec<Uuid> = match self.candidates.get(&self.query.nodes[variable].variable) {
+                    Some(set) => set.iter().copied().collect(),
+                    None => self.graph.id_to_index.keys().copied().collect(),
+                };
+                self.try_bind(step, variable, ids);
+            }
+            PlanStep::Expand(relation) => {
+                // 右侧先绑定时从右侧反向行走
+                let (from, to) = self.endpoints[relation];
+                let (bound, target, reversed) = if self.binding[from].is_some() {
+                    (from, to, false)
+                } else {
+                    (to, from, true)
+                };
+                let Some(start) = self.binding[bound] else { return };
+                let ids: Vec<Uuid> = self.graph
+                    .reach(&start, &self.query.relations[relation].pattern, reversed)
+                    .into_iter()
+                    .collect();
+                self.try_bind(step, target, ids);
+            }
+            PlanStep::Check(relation) => {
+                let (from, to) = self.endpoints[relation];
+                let (Some(source), Some(target)) = (self.binding[from], self.binding[to]) else {
+                    return;
+                };
+                if self.graph.reach(&source, &self.query.relations[relation].pattern, false).contains(&target) {
+                    self.search(step + 1);
+                }
+            }
+        }
+    }
+
+    fn try_bind(&mut self, step: usize, variable: usize, mut ids: Vec<Uuid>) {
+        ids.sort();
+        for id in ids {
+            if self.is_full() {
+                return;
+            }
+            if !self.accepts(variable, &id) {
+                continue;
+            }
+            self.binding[variable] = Some(id);
+            self.search(step + 1);
+        }
+        self.binding[variable] = None;
+    }
+}
+
+fn pattern_error(msg: &str) -> MemoryError {
+    MemoryError::InvalidInput(format!("模式查询错误: {}", msg))
+}
+
+// ============================================
+// 文本解析
+// ============================================
+
+/// 词法单元
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(usize),
+    Symbol(char),
+    /// `..`
+    Range,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    anonymous: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Self> {
+        Ok(Self {
+            tokens: tokenize(text)?,
+            pos: 0,
+            anonymous: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_symbol(&self, symbol: char) -> bool {
+        self.peek() == Some(&Token::Symbol(symbol))
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<()> {
+        match self.next() {
+            Some(Token::Symbol(c)) if c == symbol => Ok(()),
+            other => Err(pattern_error(&format!("期望 '{}'，实际为 {:?}", symbol, other))),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(pattern_error(&format!("期望关键字 {}，实际为 {:?}", keyword, self.peek())))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Ident(word)) => Ok(word),
+            other => Err(pattern_error(&format!("期望标识符，实际为 {:?}", other))),
+        }
+    }
+
+    fn expect_string(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Str(value)) => Ok(value),
+            other => Err(pattern_error(&format!("期望字符串，实际为 {:?}", other))),
+        }
+    }
+
+    fn parse_query(mut self) -> Result<PatternQuery> {
+        let mut query = PatternQuery::new();
+
+        self.expect_keyword("MATCH")?;
+        loop {
+            self.parse_path(&mut query)?;
+            if self.peek_symbol(',') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        if self.peek_keyword("WHERE") {
+            self.pos += 1;
+            loop {
+                self.parse_condition(&mut query)?;
+                if self.peek_keyword("AND") {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        self.expect_keyword("RETURN")?;
+        loop {
+            query.returns.push(self.expect_ident()?);
+            if self.peek_symbol(',') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        if self.peek_keyword("LIMIT") {
+            self.pos += 1;
+            match self.next() {
+                Some(Token::Number(n)) => query.limit = Some(n),
+                other => return Err(pattern_error(&format!("LIMIT 需要数字，实际为 {:?}", other))),
+            }
+        }
+
+        if let Some(token) = self.peek() {
+            return Err(pattern_error(&format!("多余的内容 {:?}", token)));
+        }
+        query.validate()?;
+        Ok(query)
+    }
+
+    /// 路径：节点 (关系 节点)*
+    fn parse_path(&mut self, query: &mut PatternQuery) -> Result<()> {
+        let mut left = self.parse_node(query)?;
+        while self.peek_symbol('-') || self.peek_symbol('<') {
+            let pattern = self.parse_relation()?;
+            let right = self.parse_node(query)?;
+            query.relations.push(PatternRelation {
+                from: left,
+                to: right.clone(),
+                pattern,
+            });
+            left = right;
+        }
+        Ok(())
+    }
+
+    /// 节点：`(变量:类型 {content: "值", contains: "值"})`
+    fn parse_node(&mut self, query: &mut PatternQuery) -> Result<String> {
+        self.expect_symbol('(')?;
+
+        let variable = match self.peek() {
+            Some(Token::Ident(_)) => self.expect_ident()?,
+            _ => {
+                self.anonymous += 1;
+                format!("_{}", self.anonymous)
+            }
+        };
+        let mut pattern = NodePattern::new(variable.clone());
+
+        if self.peek_symbol(':') {
+            self.pos += 1;
+            let type_name = self.expect_ident()?;
+            pattern.node_type = Some(parse_node_type(&type_name)?);
+        }
+
+        if self.peek_symbol('{') {
+            self.pos += 1;
+            while !self.peek_symbol('}') {
+                let key = self.expect_ident()?;
+                self.expect_symbol(':')?;
+                let value = self.expect_string()?;
+                pattern.content.push(content_predicate(&key, value)?);
+                if self.peek_symbol(',') {
+                    self.pos += 1;
+                }
+            }
+            self.expect_symbol('}')?;
+        }
+
+        self.expect_symbol(')')?;
+        query.add_node(pattern);
+        Ok(variable)
+    }
+
+    /// 关系：`-[:类型|类型*最少..最多]->`、`<-[...]-`、`-[...]-` 或 `-->`、`<--`、`--`
+    fn parse_relation(&mut self) -> Result<RelationPattern> {
+        let incoming = self.peek_symbol('<');
+        if incoming {
+            self.pos += 1;
+        }
+        self.expect_symbol('-')?;
+
+        let mut pattern = RelationPattern::default();
+        if self.peek_symbol('[') {
+            self.pos += 1;
+            // 关系变量不参与绑定，直接忽略
+            if matches!(self.peek(), Some(Token::Ident(_))) {
+                self.pos += 1;
+            }
+            if self.peek_symbol(':') {
+                self.pos += 1;
+                pattern.relations.push(self.expect_ident()?);
+                while self.peek_symbol('|') {
+                    self.pos += 1;
+                    pattern.relations.push(self.expect_ident()?);
+                }
+            }
+            if self.peek_symbol('*') {
+                self.pos += 1;
+                let (min_hops, max_hops) = self.parse_hops()?;
+                pattern.min_hops = min_hops;
+                pattern.max_hops = max_hops;
+            }
+            self.expect_symbol(']')?;
+        }
+
+        self.expect_symbol('-')?;
+        let outgoing = self.peek_symbol('>');
+        if outgoing {
+            self.pos += 1;
+        }
+
+        pattern.direction = match (incoming, outgoing) {
+            (true, true) => return Err(pattern_error("关系不能同时指向两侧")),
+            (true, false) => TraversalDirection::Incoming,
+            (false, true) => TraversalDirection::Outgoing,
+            (false, false) => TraversalDirection::Both,
+        };
+        Ok(pattern)
+    }
+
+    /// 跳数：`*`、`*n`、`*n..m`、`*..m`、`*n..`
+    fn parse_hops(&mut self) -> Result<(usize, usize)> {
+        let min = match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.pos += 1;
+                Some(n)
+            }
+            _ => None,
+        };
+        if self.peek() != Some(&Token::Range) {
+            return Ok(match min {
+                Some(n) => (n, n),
+                None => (1, MAX_PATTERN_HOPS),
+            });
+        }
+        self.pos += 1;
+        let max = match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.pos += 1;
+                n
+            }
+            _ => MAX_PATTERN_HOPS,
+        };
+        Ok((min.unwrap_or(1), max))
+    }
+
+    /// 条件：`变量.content = "值"` 或 `变量.content CONTAINS "值"`
+    fn parse_condition(&mut self, query: &mut PatternQuery) -> Result<()> {
+        let variable = self.expect_ident()?;
+        self.expect_symbol('.')?;
+        let property = self.expect_ident()?;
+        if property != "content" {
+            return Err(pattern_error(&format!("不支持的属性 {}", property)));
+        }
+
+        let predicate = if self.peek_symbol('=') {
+            self.pos += 1;
+            ContentPredicate::Equals(self.expect_string()?)
+        } else {
+            self.expect_keyword("CONTAINS")?;
+            ContentPredicate::Contains(self.expect_string()?)
+        };
+
+        match query.nodes.iter_mut().find(|n| n.variable == variable) {
+            Some(node) => node.content.push(predicate),
+            None => return Err(pattern_error(&format!("WHERE 引用了未定义的变量 {}", variable))),
+        }
+        Ok(())
+    }
+}
+
+fn parse_node_type(name: &str) -> Result<NodeType> {
+    match name.to_lowercase().as_str() {
+        "entity" => Ok(NodeType::Entity),
+        "event" => Ok(NodeType::Event),
+        "concept" => Ok(NodeType::Concept),
+        other => Err(pattern_error(&format!("未知的节点类型 {}", other))),
+    }
+}
+
+fn content_predicate(key: &str, value: String) -> Result<ContentPredicate> {
+    match key {
+        "content" => Ok(ContentPredicate::Equals(value)),
+        "contains" => Ok(ContentPredicate::Contains(value)),
+        other => Err(pattern_error(&format!("不支持的属性 {}", other))),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => match chars.next() {
+                        Some(escaped) => value.push(escaped),
+                        None => return Err(pattern_error("字符串未闭合")),
+                    },
+                    Some(ch) if ch == c => break,
+                    Some(ch) => value.push(ch),
+                    None => return Err(pattern_error("字符串未闭合")),
+                }
+            }
+            tokens.push(Token::Str(value));
+        } else if c.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                number.push(d);
+                chars.next();
+            }
+            let n = number.parse().map_err(|_| pattern_error("数字过大"))?;
+            tokens.push(Token::Number(n));
+        } else if c == '.' {
+            chars.next();
+            if chars.peek() == Some(&'.') {
+                chars.next();
+                tokens.push(Token::Range);
+            } else {
+                tokens.push(Token::Symbol('.'));
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut word = String::new();
+            while let Some(&w) = chars.peek().filter(|w| w.is_alphanumeric() || **w == '_') {
+                word.push(w);
+                chars.next();
+            }
+            tokens.push(Token::Ident(word));
+        } else if "()[]{}:,-<>*|=".contains(c) {
+            chars.next();
+            tokens.push(Token::Symbol(c));
+        } else {
+            return Err(pattern_error(&format!("无法识别的字符 '{}'", c)));
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::relation_types;
+    use crate::types::Edge;
+
+    /// 妈妈、医院都参与了看病；只有妈妈参与了买菜
+    fn sample() -> (KnowledgeGraph, HashMap<&'static str, Uuid>) {
+        let names = ["妈妈", "医院", "菜市场", "看病", "买菜"];
+        let ids: HashMap<&str, Uuid> = names.iter().map(|n| (*n, Uuid::new_v4())).collect();
+        let mut graph = KnowledgeGraph::new();
+        for name in &names[..3] {
+            graph.add_typed_node(ids[name], NodeType::Entity);
+        }
+        for name in &names[3..] {
+            graph.add_typed_node(ids[name], NodeType::Event);
+        }
+        for (entity, event) in [("妈妈", "看病"), ("医院", "看病"), ("妈妈", "买菜"), ("菜市场", "买菜")] {
+            graph.add_edge(Edge::new(ids[entity], ids[event], relation_types::PARTICIPATES_IN.to_string()));
+        }
+        graph.add_edge(Edge::new(ids["买菜"], ids["看病"], relation_types::BEFORE.to_string()));
+        (graph, ids)
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = PatternQuery::parse(
+            "MATCH (m:entity {content: \"妈妈\"})-[:participates_in]->(e:event)<-[:participates_in]-(h:entity), \
+             (e)-[:before|after*1..2]-() WHERE h.content CONTAINS '医' RETURN e, h LIMIT 5",
+        ).unwrap();
+
+        assert_eq!(query.nodes.len(), 4);
+        assert_eq!(query.relations.len(), 3);
+        assert_eq!(query.returns, vec!["e", "h"]);
+        assert_eq!(query.limit, Some(5));
+        assert_eq!(query.node_pattern("m").unwrap().content, vec![ContentPredicate::Equals("妈妈".to_string())]);
+        assert_eq!(query.node_pattern("h").unwrap().content, vec![ContentPredicate::Contains("医".to_string())]);
+        assert_eq!(query.relations[1].pattern.direction, TraversalDirection::Incoming);
+        let temporal = &query.relations[2].pattern;
+        assert_eq!(temporal.direction, TraversalDirection::Both);
+        assert_eq!((temporal.min_hops, temporal.max_hops), (1, 2));
+        assert_eq!(temporal.relations.len(), 2);
+
+        assert!(PatternQuery::parse("MATCH (a)-->(b) RETURN c").is_err());
+        assert!(PatternQuery::parse("MATCH (a:person) RETURN a").is_err());
+        assert!(PatternQuery::parse("MATCH (a)-[*3..9]->(b) RETURN a").is_err());
+        assert!(PatternQuery::parse("MATCH (a {content: \"x) RETURN a").is_err());
+    }
+
+    #[test]
+    fn test_match_shared_event() {
+        let (graph, ids) = sample();
+        let query = PatternQuery::parse(
+            "MATCH (m:entity)-[:participates_in]->(e:event)<-[:participates_in]-(h:entity) RETURN e",
+        ).unwrap();
+        let candidates: HashMap<String, HashSet<Uuid>> = [
+            ("m".to_string(), HashSet::from([ids["妈妈"]])),
+            ("h".to_string(), HashSet::from([ids["医院"]])),
+        ].into_iter().collect();
+
+        let rows = graph.match_pattern(&query, &candidates).unwrap();
+        assert_eq!(rows, vec![vec![ids["看病"]]]);
+
+        // 不限制 h 时，与妈妈共同参与事件的实体（含妈妈自己）
+        let query = query.returns(["h"]);
+        let candidates: HashMap<String, HashSet<Uuid>> =
+            [("m".to_string(), HashSet::from([ids["妈妈"]]))].into_iter().collect();
+        let rows = graph.match_pattern(&query, &candidates).unwrap();
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn test_builder_hops_and_limit() {
+        let (graph, ids) = sample();
+        // 菜市场 → 买菜 → 看病（participates_in 后接 before，共 2 跳，方向不限）
+        let query = PatternQuery::new()
+            .node(NodePattern::new("start").node_type(NodeType::Entity))
+            .node(NodePattern::new("end").node_type(NodeType::Event))
+            .relation("start", RelationPattern::default().direction(TraversalDirection::Both).hops(2, 2), "end")
+            .returns(["end"]);
+        let candidates: HashMap<String, HashSet<Uuid>> =
+            [("start".to_string(), HashSet::from([ids["菜市场"]]))].into_iter().collect();
+        let rows = graph.match_pattern(&query, &candidates).unwrap();
+        assert_eq!(rows, vec![vec![ids["看病"]]]);
+
+        // 未指定候选时扫描全图，LIMIT 截断结果
+        let query = PatternQuery::parse("MATCH (a:entity)-->(e:event) RETURN a, e LIMIT 2").unwrap();
+        assert_eq!(graph.match_pattern(&query, &HashMap::new()).unwrap().len(), 2);
+    }
+}
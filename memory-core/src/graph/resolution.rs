@@ -0,0 +1,402 @@
+//! 实体消解
+//!
+//! 新抽取的实体依次按原文一致、规范化后一致、向量相似度超过阈值
+//! 与已有实体节点对齐，命中时复用已有节点，避免"妈妈"在每次保存后
+//! 都变成一个互不相连的新节点。实体合并时被合并的名称记为别名，
+//! 别名与名称同等参与匹配
+
+use super::KnowledgeGraph;
+use crate::retrieval::cosine_similarity;
+use crate::types::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 实体消解配置
+#[derive(Debug, Clone)]
+pub struct EntityResolutionConfig {
+    /// 是否启用实体消解（关闭时每次保存都新建实体节点）
+    pub enabled: bool,
+    /// 是否允许规范化后一致的名称匹配（忽略空白、标点、大小写、全角和"我的"等前缀）
+    pub normalized_match: bool,
+    /// 向量相似度匹配阈值，大于 1.0 时关闭向量匹配
+    pub similarity_threshold: f32,
+    /// 规范化与向量匹配是否要求实体类型一致（Other 视为任意类型）
+    pub require_same_type: bool,
+    /// 每个新实体从存储中向量检索的候选实体数
+    pub vector_candidates: usize,
+}
+
+impl Default for EntityResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            normalized_match: true,
+            similarity_threshold: 0.92,
+            require_same_type: true,
+            vector_candidates: 5,
+        }
+    }
+}
+
+/// 实体匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityMatchKind {
+    /// 原文完全一致
+    Exact,
+    /// 规范化后一致
+    Normalized,
+    /// 向量相似度超过阈值
+    Embedding,
+}
+
+/// 实体匹配结果
+#[derive(Debug, Clone, Copy)]
+pub struct EntityMatch {
+    /// 匹配到的已有实体 ID
+    pub id: Uuid,
+    /// 匹配方式
+    pub kind: EntityMatchKind,
+    /// 匹配得分（名称匹配为 1.0，向量匹配为余弦相似度）
+    pub score: f32,
+}
+
+/// 规范化实体名称
+///
+/// 去掉空白与标点、全角字母数字转半角、转小写，并去掉"我的"一类所有格前缀
+pub fn normalize_entity_name(name: &str) -> String {
+    let normalized: String = name.chars()
+        .map(|c| match c as u32 {
+            0xFF01..=0xFF5E => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    for prefix in ["我们的", "我的", "我家的"] {
+        if let Some(rest) = normalized.strip_prefix(prefix) {
+            if !rest.is_empty() {
+                return rest.to_string();
+            }
+        }
+    }
+    normalized
+}
+
+/// 名称匹配时需要在存储中查找的候选名称
+///
+/// 包含原名、规范化后的名称及其加上"我的"等所有格前缀的写法，
+/// 供按名称精确查询可能对齐的已有实体
+pub fn name_variants(name: &str) -> Vec<String> {
+    let mut variants = vec![name.to_string()];
+    let key = normalize_entity_name(name);
+    if key.is_empty() {
+        return variants;
+    }
+    for variant in std::iter::once(key.clone())
+        .chain(["我们的", "我的", "我家的"].iter().map(|prefix| format!("{}{}", prefix, key)))
+    {
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+    variants
+}
+
+/// 已知实体
+#[derive(Debug, Clone)]
+struct KnownEntity {
+    id: Uuid,
+    entity_type: EntityType,
+    embedding: Vec<f32>,
+}
+
+/// 实体消解器
+///
+/// 以已有实体节点初始化；同一批次中新建的实体通过 `insert` 加入，
+/// 使后续出现的同名实体也能对齐到它
+#[derive(Debug, Clone)]
+pub struct EntityResolver {
+    config: EntityResolutionConfig,
+    entities: Vec<KnownEntity>,
+    by_content: HashMap<String, usize>,
+    by_normalized: HashMap<String, usize>,
+}
+
+impl EntityResolver {
+    /// 创建消解器
+    pub fn new(config: EntityResolutionConfig) -> Self {
+        Self {
+            config,
+            entities: Vec::new(),
+            by_content: HashMap::new(),
+            by_normalized: HashMap::new(),
+        }
+    }
+
+    /// 以已有节点创建消解器（非实体节点被忽略）
+    pub fn with_entities<'a>(
+        config: EntityResolutionConfig,
+        nodes: impl IntoIterator<Item = &'a MemoryNode>,
+    ) -> Self {
+        let mut resolver = Self::new(config);
+        for node in nodes {
+            resolver.insert(node);
+        }
+        resolver
+    }
+
+    /// 已知实体数量
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// 是否没有已知实体
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// 加入一个已知实体（同名时保留先加入的），名称与别名都建立索引
+    pub fn insert(&mut self, node: &MemoryNode) {
+        let Some(entity_type) = node.entity_type() else {
+            return;
+        };
+        let index = self.entities.len();
+        self.entities.push(KnownEntity {
+            id: node.id,
+            entity_type,
+            embedding: node.embedding.clone(),
+        });
+        for name in std::iter::once(&node.content).chain(node.aliases()) {
+            self.by_content.entry(name.clone()).or_insert(index);
+            let key = normalize_entity_name(name);
+            if !key.is_empty() {
+                self.by_normalized.entry(key).or_insert(index);
+            }
+        }
+    }
+
+    /// 为实体节点查找可复用的已有实体
+    ///
+    /// 非实体节点、以及未达到任何匹配条件时返回 None
+    pub fn resolve(&self, node: &MemoryNode) -> Option<EntityMatch> {
+        if !self.config.enabled {
+            return None;
+        }
+        let entity_type = node.entity_type()?;
+
+        if let Some(&i) = self.by_content.get(&node.content) {
+            return Some(EntityMatch {
+                id: self.entities[i].id,
+                kind: EntityMatchKind::Exact,
+                score: 1.0,
+            });
+        }
+
+        if self.config.normalized_match {
+            let key = normalize_entity_name(&node.content);
+            if let Some(&i) = self.by_normalized.get(&key) {
+                if self.type_compatible(entity_type, self.entities[i].entity_type) {
+                    return Some(EntityMatch {
+                        id: self.entities[i].id,
+                        kind: EntityMatchKind::Normalized,
+                        score: 1.0,
+                    });
+                }
+            }
+        }
+
+        if node.embedding.is_empty() || self.config.similarity_threshold > 1.0 {
+            return None;
+        }
+        self.entities.iter()
+            .filter(|known| known.id != node.id && !known.embedding.is_empty())
+            .filter(|known| self.type_compatible(entity_type, known.entity_type))
+            .map(|known| (known.id, cosine_similarity(&node.embedding, &known.embedding)))
+            .filter(|(_, score)| *score >= self.config.similarity_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, score)| EntityMatch {
+                id,
+                kind: EntityMatchKind::Embedding,
+                score,
+            })
+    }
+
+    fn type_compatible(&self, a: EntityType, b: EntityType) -> bool {
+        !self.config.require_same_type
+            || a == b
+            || a == EntityType::Other
+            || b == EntityType::Other
+    }
+}
+
+/// 节点合并对图中边的改动
+#[derive(Debug, Clone, Default)]
+pub struct NodeMergeEdges {
+    /// 被删除节点原有的边 ID
+    pub removed: Vec<Uuid>,
+    /// 改接到保留节点后新插入的边
+    pub inserted: Vec<Edge>,
+    /// 改接后与保留节点已有关系重合而被增强的边
+    pub reinforced: Vec<Edge>,
+}
+
+impl KnowledgeGraph {
+    /// 把一个节点合并进另一个节点
+    ///
+    /// `remove` 的所有边改接到 `keep` 上（相同关系合并并增强，两者之间的边丢弃），
+    /// 然后从图中删除 `remove`
+    ///
+    /// # Returns
+    /// 两个节点相同或任一不在图中时返回 None
+    pub fn merge_node_into(&mut self, remove: &Uuid, keep: &Uuid) -> Option<NodeMergeEdges> {
+        let merge = self.plan_node_merge(remove, keep)?;
+        self.apply_node_merge(remove, &merge);
+        Some(merge)
+    }
+
+    /// 计算节点合并对边的改动，不修改图
+    ///
+    /// 返回值与 [`merge_node_into`](Self::merge_node_into) 相同，调用方可以先写入存储，
+    /// 成功后再用 [`apply_node_merge`](Self::apply_node_merge) 更新图
+    pub fn plan_node_merge(&self, remove: &Uuid, keep: &Uuid) -> Option<NodeMergeEdges> {
+        if remove == keep || !self.contains_node(keep) {
+            return None;
+        }
+        let idx = *self.id_to_index.get(remove)?;
+
+        let edges: Vec<Edge> = self.graph.edges_directed(idx, petgraph::Direction::Outgoing)
+            .chain(self.graph.edges_directed(idx, petgraph::Direction::Incoming))
+            .map(|e| e.weight().clone())
+            .collect();
+        let removed: Vec<Uuid> = edges.iter().map(|e| e.id).collect();
+
+        // 改接后的边不再涉及 `remove`，在删除它之前规划结果与删除之后一致
+        let rewired = edges.into_iter().filter_map(|mut edge| {
+            if edge.source == *remove {
+                edge.source = *keep;
+            }
+            if edge.target == *remove {
+                edge.target = *keep;
+            }
+            (edge.source != edge.target).then_some(edge)
+        });
+        let (inserted, reinforced) = self.plan_upsert_edges(rewired);
+
+        Some(NodeMergeEdges {
+            removed,
+            inserted,
+            reinforced,
+        })
+    }
+
+    /// 把 [`plan_node_merge`](Self::plan_node_merge) 的结果写入图
+    pub fn apply_node_merge(&mut self, remove: &Uuid, merge: &NodeMergeEdges) {
+        self.detach_node(remove);
+        self.apply_upserted_edges(&merge.inserted, &merge.reinforced);
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{relation_types, EdgeBuilder, NodeBuilder};
+
+    fn entity(name: &str, entity_type: EntityType, embedding: Vec<f32>) -> MemoryNode {
+        NodeBuilder::entity(name)
+            .entity_type(entity_type)
+            .embedding(embedding)
+            .build()
+    }
+
+    #[test]
+    fn test_normalize_entity_name() {
+        assert_eq!(normalize_entity_name(" 我的妈妈！"), "妈妈");
+        assert_eq!(normalize_entity_name("ＡＢＣ Cafe"), "abccafe");
+        assert_eq!(normalize_entity_name("我的"), "我的");
+    }
+
+    #[test]
+    fn test_name_variants_and_alias_match() {
+        let variants = name_variants("我的 妈妈");
+        assert_eq!(variants[0], "我的 妈妈");
+        assert!(variants.contains(&"妈妈".to_string()));
+        assert!(variants.contains(&"我家的妈妈".to_string()));
+        assert_eq!(name_variants("!!"), vec!["!!".to_string()]);
+
+        let mut mom = entity("妈妈", EntityType::Person, vec![]);
+        if let NodeData::Entity { aliases, .. } = &mut mom.data {
+            aliases.push("老妈".to_string());
+        }
+        let resolver = EntityResolver::with_entities(EntityResolutionConfig::default(), [&mom]);
+        let alias = resolver.resolve(&entity("老妈", EntityType::Person, vec![])).unwrap();
+        assert_eq!((alias.id, alias.kind), (mom.id, EntityMatchKind::Exact));
+        let normalized = resolver.resolve(&entity("我的老妈", EntityType::Person, vec![])).unwrap();
+        assert_eq!(normalized.kind, EntityMatchKind::Normalized);
+    }
+
+    #[test]
+    fn test_resolver_match_kinds() {
+        let mom = entity("妈妈", EntityType::Person, vec![1.0, 0.0, 0.0]);
+        let hospital = entity("医院", EntityType::Place, vec![0.0, 1.0, 0.0]);
+        let resolver = EntityResolver::with_entities(
+            EntityResolutionConfig::default(),
+            [&mom, &hospital],
+        );
+
+        let exact = resolver.resolve(&entity("妈妈", EntityType::Person, vec![])).unwrap();
+        assert_eq!((exact.id, exact.kind), (mom.id, EntityMatchKind::Exact));
+
+        let normalized = resolver.resolve(&entity("我的妈妈", EntityType::Person, vec![])).unwrap();
+        assert_eq!((normalized.id, normalized.kind), (mom.id, EntityMatchKind::Normalized));
+
+        let similar = resolver.resolve(&entity("老妈", EntityType::Person, vec![0.99, 0.05, 0.0])).unwrap();
+        assert_eq!((similar.id, similar.kind), (mom.id, EntityMatchKind::Embedding));
+
+        // 向量相近但类型不同的实体不会被合并
+        assert!(resolver.resolve(&entity("诊所", EntityType::Person, vec![0.0, 0.99, 0.05])).is_none());
+        assert!(resolver.resolve(&entity("爸爸", EntityType::Person, vec![0.0, 0.0, 1.0])).is_none());
+    }
+
+    #[test]
+    fn test_merge_node_into_rewires_edges() {
+        let mut graph = KnowledgeGraph::new();
+        let (keep, remove) = (Uuid::new_v4(), Uuid::new_v4());
+        let (shared_event, other_event) = (Uuid::new_v4(), Uuid::new_v4());
+
+        graph.add_edge(EdgeBuilder::participates(keep, shared_event).weight(0.5).build());
+        graph.add_edge(EdgeBuilder::participates(remove, shared_event).weight(0.5).build());
+        graph.add_edge(EdgeBuilder::participates(remove, other_event).weight(0.5).build());
+        graph.add_edge(EdgeBuilder::new(keep, remove, "related_to").build());
+
+        let merge = graph.merge_node_into(&remove, &keep).unwrap();
+        assert_eq!(merge.removed.len(), 3);
+        assert_eq!(merge.inserted.len(), 1);
+        assert_eq!(merge.reinforced.len(), 1);
+
+        assert!(!graph.contains_node(&remove));
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.find_edge_by_key(&keep, &other_event, relation_types::PARTICIPATES_IN).is_some());
+        let shared = graph.find_edge_by_key(&keep, &shared_event, relation_types::PARTICIPATES_IN).unwrap();
+        assert!(shared.weight > 0.5);
+        assert!(graph.merge_node_into(&remove, &keep).is_none());
+    }
+
+    #[test]
+    fn test_plan_node_merge_leaves_graph_untouched() {
+        let mut graph = KnowledgeGraph::new();
+        let (keep, remove, event) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        graph.add_edge(EdgeBuilder::participates(remove, event).build());
+        graph.add_edge(EdgeBuilder::participates(keep, event).build());
+
+        let merge = graph.plan_node_merge(&remove, &keep).unwrap();
+        assert_eq!(merge.reinforced.len(), 1);
+        assert!(graph.contains_node(&remove));
+        assert_eq!(graph.edge_count(), 2);
+
+        graph.apply_node_merge(&remove, &merge);
+        assert!(!graph.contains_node(&remove));
+        assert_eq!(graph.edge_count(), 1);
+    }
+}
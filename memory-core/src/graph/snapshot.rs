@@ -0,0 +1,365 @@
+//! 图快照
+//!
+//! 将 KnowledgeGraph 序列化为紧凑的二进制快照并存放在数据库目录中，
+//! 启动时直接加载快照，只回放快照之后新增的边，避免每次全量重建图结构
+
+use super::KnowledgeGraph;
+use crate::error::{MemoryError, Result};
+use crate::types::{Edge, NodeType};
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+/// 快照文件名（位于数据库目录下）
+pub const SNAPSHOT_FILE_NAME: &str = "knowledge_graph.snapshot";
+
+/// 文件头魔数
+const SNAPSHOT_MAGIC: &[u8; 4] = b"MKGS";
+
+/// 快照格式版本，格式变化时递增
+const SNAPSHOT_FORMAT_VERSION: u16 = 4;
+
+/// 快照元数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotMeta {
+    /// 生成快照时边表的版本号
+    pub edge_table_version: u64,
+    /// 快照包含的边数量
+    pub edge_count: usize,
+    /// 快照中边的最近观测时间的最大值（用于增量回放新增和被增强的边）
+    pub max_edge_seen_at: i64,
+}
+
+impl KnowledgeGraph {
+    /// 序列化为二进制快照
+    ///
+    /// 格式（小端序）：
+    /// 魔数 | 格式版本 | 边表版本 | 最大观测时间 | 节点表（含类型） | 关系表 | 边表 | FNV-1a 校验和
+    ///
+    /// 边的元数据与边表 metadata 列相同，以 JSON 文本存放（长度为 0 表示没有元数据）
+    pub fn to_snapshot_bytes(&self, edge_table_version: u64) -> Vec<u8> {
+        let node_ids: Vec<Uuid> = self.graph.node_weights().copied().collect();
+        let node_pos: HashMap<Uuid, u32> = node_ids.iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i as u32))
+            .collect();
+
+        // 关系类型去重，边中只存关系表下标
+        let mut relations: Vec<&str> = Vec::new();
+        let mut relation_pos: HashMap<&str, u32> = HashMap::new();
+        for edge in self.graph.edge_weights() {
+            let relation = edge.relation.as_str();
+            if !relation_pos.contains_key(relation) {
+                relation_pos.insert(relation, relations.len() as u32);
+                relations.push(relation);
+            }
+        }
+
+        let max_seen_at = self.graph.edge_weights()
+            .map(|e| e.last_seen_at.max(e.created_at))
+            .max()
+            .unwrap_or(0);
+
+        let edge_count = self.graph.edge_count();
+        let mut buf = Vec::with_capacity(40 + node_ids.len() * 16 + edge_count * 48);
+        buf.extend_from_slice(SNAPSHOT_MAGIC);
+        buf.extend_from_slice(&SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+        buf.extend_from_slice(&edge_table_version.to_le_bytes());
+        buf.extend_from_slice(&max_seen_at.to_le_bytes());
+
+        buf.extend_from_slice(&(node_ids.len() as u32).to_le_bytes());
+        for id in &node_ids {
+            buf.extend_from_slice(id.as_bytes());
+            buf.push(encode_node_type(self.node_type(id)));
+        }
+
+        buf.extend_from_slice(&(relations.len() as u32).to_le_bytes());
+        for relation in &relations {
+            let bytes = relation.as_bytes();
+            buf.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+            buf.extend_from_slice(bytes);
+        }
+
+        buf.extend_from_slice(&(edge_count as u32).to_le_bytes());
+        for edge in self.graph.edge_weights() {
+            buf.extend_from_slice(edge.id.as_bytes());
+            buf.extend_from_slice(&node_pos[&edge.source].to_le_bytes());
+            buf.extend_from_slice(&node_pos[&edge.target].to_le_bytes());
+            buf.extend_from_slice(&relation_pos[edge.relation.as_str()].to_le_bytes());
+            buf.extend_from_slice(&edge.weight.to_le_bytes());
+            buf.extend_from_slice(&edge.created_at.to_le_bytes());
+            buf.extend_from_slice(&edge.last_seen_at.to_le_bytes());
+            let metadata = edge.metadata.as_ref().map(|m| m.to_string()).unwrap_or_default();
+            buf.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+            buf.extend_from_slice(metadata.as_bytes());
+        }
+
+        let checksum = fnv1a64(&buf);
+        buf.extend_from_slice(&checksum.to_le_bytes());
+        buf
+    }
+
+    /// 从二进制快照恢复图结构
+    pub fn from_snapshot_bytes(bytes: &[u8]) -> Result<(KnowledgeGraph, SnapshotMeta)> {
+        if bytes.len() < 8 {
+            return Err(snapshot_error("快照文件过短"));
+        }
+        let (body, checksum_bytes) = bytes.split_at(bytes.len() - 8);
+        let expected = u64::from_le_bytes(checksum_bytes.try_into().unwrap_or([0; 8]));
+        if fnv1a64(body) != expected {
+            return Err(snapshot_error("校验和不匹配"));
+        }
+
+        let mut reader = SnapshotReader { data: body, pos: 0 };
+        if reader.take(4)? != SNAPSHOT_MAGIC {
+            return Err(snapshot_error("文件头魔数不匹配"));
+        }
+        let format_version = reader.read_u16()?;
+        if format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(snapshot_error(&format!("不支持的快照格式版本: {}", format_version)));
+        }
+        let edge_table_version = reader.read_u64()?;
+        let max_edge_seen_at = reader.read_i64()?;
+
+        let node_count = reader.read_u32()? as usize;
+        let mut node_ids = Vec::with_capacity(node_count);
+        let mut graph = KnowledgeGraph::new();
+        for _ in 0..node_count {
+            let id = reader.read_uuid()?;
+            match decode_node_type(reader.read_u8()?) {
+                Some(node_type) => graph.add_typed_node(id, node_type),
+                None => graph.add_node(id),
+            };
+            node_ids.push(id);
+        }
+
+        let relation_count = reader.read_u32()? as usize;
+        let mut relations = Vec::with_capacity(relation_count);
+        for _ in 0..relation_count {
+            let len = reader.read_u16()? as usize;
+            let relation = std::str::from_utf8(reader.take(len)?)
+                .map_err(|_| snapshot_error("关系类型不是有效的 UTF-8"))?;
+            relations.push(relation.to_string());
+        }
+
+        let edge_count = reader.read_u32()? as usize;
+        for _ in 0..edge_count {
+            let id = reader.read_uuid()?;
+            let source = *node_ids.get(reader.read_u32()? as usize)
+                .ok_or_else(|| snapshot_error("边的源节点下标越界"))?;
+            let target = *node_ids.get(reader.read_u32()? as usize)
+                .ok_or_else(|| snapshot_error("边的目标节点下标越界"))?;
+            let relation = relations.get(reader.read_u32()? as usize)
+                .ok_or_else(|| snapshot_error("边的关系下标越界"))?
+                .clone();
+            let weight = reader.read_f32()?;
+            let created_at = reader.read_i64()?;
+            let last_seen_at = reader.read_i64()?;
+            let metadata = match reader.read_u32()? as usize {
+                0 => None,
+                len => Some(serde_json::from_slice(reader.take(len)?)
+                    .map_err(|_| snapshot_error("边的元数据不是有效的 JSON"))?),
+            };
+
+            graph.add_edge(Edge {
+                id,
+                source,
+                target,
+                relation,
+                weight,
+                created_at,
+                last_seen_at,
+                metadata,
+            });
+        }
+
+        if reader.pos != body.len() {
+            return Err(snapshot_error("快照末尾存在多余数据"));
+        }
+
+        let meta = SnapshotMeta {
+            edge_table_version,
+            edge_count: graph.edge_count(),
+            max_edge_seen_at,
+        };
+        Ok((graph, meta))
+    }
+
+    /// 将快照写入文件
+    ///
+    /// 先写临时文件再重命名，避免进程中断时留下半个快照
+    pub fn save_snapshot(&self, path: &Path, edge_table_version: u64) -> Result<()> {
+        let bytes = self.to_snapshot_bytes(edge_table_version);
+        let tmp_path = path.with_extension("snapshot.tmp");
+        std::fs::write(&tmp_path, &bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+        log::debug!("图快照已写入 {:?} ({} 字节)", path, bytes.len());
+        Ok(())
+    }
+
+    /// 从文件加载快照
+    ///
+    /// # Returns
+    /// 文件不存在时返回 None
+    pub fn load_snapshot(path: &Path) -> Result<Option<(KnowledgeGraph, SnapshotMeta)>> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_snapshot_bytes(&bytes).map(Some)
+    }
+}
+
+fn encode_node_type(node_type: Option<NodeType>) -> u8 {
+    match node_type {
+        None => 0,
+        Some(NodeType::Entity) => 1,
+        Some(NodeType::Event) => 2,
+        Some(NodeType::Concept) => 3,
+    }
+}
+
+fn decode_node_type(tag: u8) -> Option<NodeType> {
+    match tag {
+        1 => Some(NodeType::Entity),
+        2 => Some(NodeType::Event),
+        3 => Some(NodeType::Concept),
+        _ => None,
+    }
+}
+
+fn snapshot_error(msg: &str) -> MemoryError {
+    MemoryError::Graph(format!("图快照无效: {}", msg))
+}
+
+/// FNV-1a 64 位哈希（用于快照完整性校验）
+fn fnv1a64(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// 快照字节读取器
+struct SnapshotReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| snapshot_error("数据被截断"))?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut arr = [0u8; N];
+        arr.copy_from_slice(self.take(N)?);
+        Ok(arr)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_uuid(&mut self) -> Result<Uuid> {
+        Ok(Uuid::from_bytes(self.read_array()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_graph() -> KnowledgeGraph {
+        let mut graph = KnowledgeGraph::new();
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        graph.add_typed_node(ids[0], NodeType::Entity);
+        graph.add_typed_node(ids[1], NodeType::Event);
+        graph.add_edge(Edge::new(ids[0], ids[1], "participates_in".to_string()));
+        graph.add_edge(Edge::new(ids[2], ids[1], "participates_in".to_string()));
+        let mut reinforced = Edge::new(ids[0], ids[3], "conceptualized_as".to_string());
+        reinforced.last_seen_at = reinforced.created_at + 100;
+        reinforced.metadata = Some(serde_json::json!({"source": "import", "confidence": 0.8}));
+        graph.add_edge(reinforced);
+        graph.add_node(Uuid::new_v4()); // 孤立节点也应保留
+        graph
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let graph = sample_graph();
+        let bytes = graph.to_snapshot_bytes(42);
+
+        let (restored, meta) = KnowledgeGraph::from_snapshot_bytes(&bytes).unwrap();
+        assert_eq!(meta.edge_table_version, 42);
+        assert_eq!(meta.edge_count, 3);
+        let max_seen_at = graph.get_all_node_ids().iter()
+            .flat_map(|id| graph.get_outgoing_edges(id))
+            .map(|e| e.last_seen_at)
+            .max()
+            .unwrap();
+        assert_eq!(meta.max_edge_seen_at, max_seen_at);
+        assert_eq!(restored.node_count(), graph.node_count());
+        assert_eq!(restored.edge_count(), graph.edge_count());
+
+        for id in graph.get_all_node_ids() {
+            assert!(restored.contains_node(&id));
+            assert_eq!(restored.node_type(&id), graph.node_type(&id));
+            assert_eq!(restored.get_neighbors(&id, 1), graph.get_neighbors(&id, 1));
+            let metadata = |g: &KnowledgeGraph| -> Vec<Option<serde_json::Value>> {
+                g.get_outgoing_edges(&id).iter().map(|e| e.metadata.clone()).collect()
+            };
+            assert_eq!(metadata(&restored), metadata(&graph));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_rejects_corruption() {
+        let mut bytes = sample_graph().to_snapshot_bytes(1);
+        bytes[20] ^= 0xff;
+        assert!(KnowledgeGraph::from_snapshot_bytes(&bytes).is_err());
+        assert!(KnowledgeGraph::from_snapshot_bytes(&bytes[..10]).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SNAPSHOT_FILE_NAME);
+        assert!(KnowledgeGraph::load_snapshot(&path).unwrap().is_none());
+
+        let graph = sample_graph();
+        graph.save_snapshot(&path, 7).unwrap();
+
+        let (restored, meta) = KnowledgeGraph::load_snapshot(&path).unwrap().unwrap();
+        assert_eq!(meta.edge_table_version, 7);
+        assert_eq!(restored.edge_count(), graph.edge_count());
+    }
+}
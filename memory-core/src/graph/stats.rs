@@ -0,0 +1,131 @@
+//! 图结构统计
+//!
+//! 汇总关系类型分布、度分布与连通分量，供宿主展示用户记忆的整体形态
+
+use super::{find_connected_components, KnowledgeGraph};
+use petgraph::Direction;
+use std::collections::BTreeMap;
+
+/// 图结构统计结果
+#[derive(Debug, Clone, Default)]
+pub struct GraphStats {
+    /// 节点数
+    pub node_count: usize,
+    /// 边数
+    pub edge_count: usize,
+    /// 各关系类型的边数
+    pub edges_by_relation: BTreeMap<String, usize>,
+    /// 度分布：度数（入度 + 出度） -> 节点数
+    pub degree_distribution: BTreeMap<usize, usize>,
+    /// 弱连通分量数（孤立节点各自算一个）
+    pub component_count: usize,
+}
+
+impl GraphStats {
+    /// 平均度数
+    pub fn average_degree(&self) -> f64 {
+        if self.node_count == 0 {
+            return 0.0;
+        }
+        2.0 * self.edge_count as f64 / self.node_count as f64
+    }
+
+    /// 最大度数
+    pub fn max_degree(&self) -> usize {
+        self.degree_distribution.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// 按存储中的节点总数补齐不在图中的孤立节点
+    ///
+    /// 图只包含有边的节点，孤立节点计入度数 0 与连通分量，
+    /// 使节点数、度分布与平均度数出自同一口径
+    pub fn include_isolated_nodes(&mut self, total_nodes: usize) {
+        let isolated = total_nodes.saturating_sub(self.node_count);
+        if isolated == 0 {
+            return;
+        }
+        *self.degree_distribution.entry(0).or_default() += isolated;
+        self.component_count += isolated;
+        self.node_count = total_nodes;
+    }
+}
+
+impl KnowledgeGraph {
+    /// 统计图结构
+    pub fn stats(&self) -> GraphStats {
+        let mut edges_by_relation: BTreeMap<String, usize> = BTreeMap::new();
+        for edge in self.graph.edge_weights() {
+            *edges_by_relation.entry(edge.relation.clone()).or_default() += 1;
+        }
+
+        let mut degree_distribution: BTreeMap<usize, usize> = BTreeMap::new();
+        for idx in self.graph.node_indices() {
+            let degree = self.graph.edges_directed(idx, Direction::Outgoing).count()
+                + self.graph.edges_directed(idx, Direction::Incoming).count();
+            *degree_distribution.entry(degree).or_default() += 1;
+        }
+
+        GraphStats {
+            node_count: self.node_count(),
+            edge_count: self.edge_count(),
+            edges_by_relation,
+            degree_distribution,
+            component_count: find_connected_components(&self.graph).len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Edge;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_stats_counts_relations_degrees_and_components() {
+        let mut graph = KnowledgeGraph::new();
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        graph.add_edge(Edge::new(ids[0], ids[1], "participates_in".to_string()));
+        graph.add_edge(Edge::new(ids[2], ids[1], "participates_in".to_string()));
+        graph.add_edge(Edge::new(ids[1], ids[3], "before".to_string()));
+        graph.add_node(ids[4]);
+
+        let stats = graph.stats();
+        assert_eq!(stats.node_count, 5);
+        assert_eq!(stats.edge_count, 3);
+        assert_eq!(stats.edges_by_relation["participates_in"], 2);
+        assert_eq!(stats.edges_by_relation["before"], 1);
+        assert_eq!(stats.degree_distribution[&0], 1);
+        assert_eq!(stats.degree_distribution[&1], 3);
+        assert_eq!(stats.degree_distribution[&3], 1);
+        assert_eq!(stats.max_degree(), 3);
+        assert_eq!(stats.component_count, 2);
+        assert!((stats.average_degree() - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_on_empty_graph() {
+        let stats = KnowledgeGraph::new().stats();
+        assert_eq!(stats.component_count, 0);
+        assert_eq!(stats.max_degree(), 0);
+        assert_eq!(stats.average_degree(), 0.0);
+    }
+
+    #[test]
+    fn test_include_isolated_nodes() {
+        let mut graph = KnowledgeGraph::new();
+        let ids: Vec<Uuid> = (0..2).map(|_| Uuid::new_v4()).collect();
+        graph.add_edge(Edge::new(ids[0], ids[1], "before".to_string()));
+
+        let mut stats = graph.stats();
+        stats.include_isolated_nodes(4);
+        assert_eq!(stats.node_count, 4);
+        assert_eq!(stats.degree_distribution[&0], 2);
+        assert_eq!(stats.component_count, 3);
+        assert!((stats.average_degree() - 0.5).abs() < 1e-9);
+
+        // 存储中的节点数不多于图时保持不变
+        stats.include_isolated_nodes(1);
+        assert_eq!(stats.node_count, 4);
+    }
+}
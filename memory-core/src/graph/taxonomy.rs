@@ -0,0 +1,299 @@
+//! 概念层级
+//!
+//! 概念之间以 `is_a` 边组成分类树（如 家人 -[is_a]-> 人物），
+//! 实体通过 `conceptualized_as` / `instance_of` 归入概念（如 妈妈 -[instance_of]-> 家人）。
+//! 检索时概念桥接可沿层级向上泛化或向下细化若干层
+
+use super::{relation_types, EdgeBuilder, KnowledgeGraph, INITIAL_EDGE_WEIGHT};
+use crate::types::Edge;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// 是否为"节点归入概念"的关系
+pub fn is_membership_relation(relation: &str) -> bool {
+    relation == relation_types::CONCEPTUALIZED_AS || relation == relation_types::INSTANCE_OF
+}
+
+/// 上下位概念边：`child -[is_a]-> parent`
+pub fn taxonomy_edge(child: Uuid, parent: Uuid) -> Edge {
+    EdgeBuilder::new(child, parent, relation_types::IS_A)
+        .weight(INITIAL_EDGE_WEIGHT)
+        .build()
+}
+
+/// 概念桥接时每经过一层 is_a 扩展，相连边权重的折算系数
+pub const BRIDGE_LEVEL_DECAY: f32 = 0.5;
+
+/// 实例归属边：`node -[instance_of]-> concept`
+pub fn instance_edge(node: Uuid, concept: Uuid) -> Edge {
+    EdgeBuilder::new(node, concept, relation_types::INSTANCE_OF)
+        .weight(INITIAL_EDGE_WEIGHT)
+        .build()
+}
+
+impl KnowledgeGraph {
+    /// 上位概念
+    ///
+    /// # Returns
+    /// (概念 ID, 层数)，按层数由近到远排列，不含起始概念
+    pub fn concept_ancestors(&self, concept_id: &Uuid, levels: usize) -> Vec<(Uuid, usize)> {
+        self.walk_taxonomy(concept_id, Direction::Outgoing, levels)
+    }
+
+    /// 下位概念
+    ///
+    /// # Returns
+    /// (概念 ID, 层数)，按层数由近到远排列，不含起始概念
+    pub fn concept_descendants(&self, concept_id: &Uuid, levels: usize) -> Vec<(Uuid, usize)> {
+        self.walk_taxonomy(concept_id, Direction::Incoming, levels)
+    }
+
+    /// 直接归入该概念的节点（不含下位概念）
+    pub fn concept_instances(&self, concept_id: &Uuid) -> Vec<Uuid> {
+        self.membership_neighbors(concept_id, Direction::Incoming)
+    }
+
+    /// 概念的实例数：以 `conceptualized_as` 边指向该概念的不同节点数
+    pub fn concept_instance_count(&self, concept_id: &Uuid) -> usize {
+        let Some(&idx) = self.id_to_index.get(concept_id) else {
+            return 0;
+        };
+        self.graph.edges_directed(idx, Direction::Incoming)
+            .filter(|e| e.weight().relation == relation_types::CONCEPTUALIZED_AS)
+            .map(|e| e.weight().source)
+            .collect::<HashSet<Uuid>>()
+            .len()
+    }
+
+    /// 节点直接归入的概念
+    pub fn concepts_of(&self, node_id: &Uuid) -> Vec<Uuid> {
+        self.membership_neighbors(node_id, Direction::Outgoing)
+    }
+
+    /// 添加 `child -[is_a]-> parent` 是否会形成环
+    pub fn is_a_would_cycle(&self, child: &Uuid, parent: &Uuid) -> bool {
+        child == parent
+            || self.concept_ancestors(parent, usize::MAX)
+                .iter()
+                .any(|(id, _)| id == child)
+    }
+
+    /// 带层级的概念桥接
+    ///
+    /// 先取与源节点直接相连的概念，再沿 `is_a` 向上 `generalize_levels` 层、
+    /// 向下 `specialize_levels` 层扩展，返回这些概念的邻居以及扩展得到的概念本身；
+    /// 两个层数都为 0 时与 `find_concept_bridged` 相同
+    ///
+    /// 经泛化或细化得到的概念离源节点更远，其相连边的权重按层数乘以
+    /// [`BRIDGE_LEVEL_DECAY`] 折算，使直接概念的邻居优先保留
+    ///
+    /// # Returns
+    /// 按折算后的最大边权重降序排列的节点 ID，`limit` 为 Some 时只取前若干个
+    pub fn find_concept_bridged_with_levels(
+        &self,
+        source_nodes: &[Uuid],
+        concept_nodes: &HashSet<Uuid>,
+        generalize_levels: usize,
+        specialize_levels: usize,
+        limit: Option<usize>,
+    ) -> Vec<Uuid> {
+        let sources: HashSet<Uuid> = source_nodes.iter().copied().collect();
+
+        let direct: HashSet<Uuid> = source_nodes.iter()
+            .filter_map(|id| self.id_to_index.get(id))
+            .flat_map(|&idx| self.graph.neighbors_undirected(idx))
+            .map(|idx| self.graph[idx])
+            .filter(|id| concept_nodes.contains(id))
+            .collect();
+
+        // 概念 → 距直接概念的最少层数
+        let mut expanded: HashMap<Uuid, usize> = direct.iter().map(|id| (*id, 0)).collect();
+        for concept in &direct {
+            for (id, level) in self.concept_ancestors(concept, generalize_levels)
+                .into_iter()
+                .chain(self.concept_descendants(concept, specialize_levels))
+            {
+                let known = expanded.entry(id).or_insert(level);
+                *known = (*known).min(level);
+            }
+        }
+
+        // 扩展得到的概念都与链上的上一个概念以 is_a 边相连，同样按边权重计分
+        let mut scores: HashMap<Uuid, f32> = HashMap::new();
+        for (concept, &level) in &expanded {
+            let Some(&idx) = self.id_to_index.get(concept) else {
+                continue;
+            };
+            let decay = BRIDGE_LEVEL_DECAY.powi(level as i32);
+            for edge in self.graph.edges_directed(idx, Direction::Outgoing)
+                .chain(self.graph.edges_directed(idx, Direction::Incoming))
+            {
+                let edge = edge.weight();
+                let other = if edge.source == *concept { edge.target } else { edge.source };
+                if sources.contains(&other) {
+                    continue;
+                }
+                let score = scores.entry(other).or_insert(f32::MIN);
+                *score = score.max(edge.weight * decay);
+            }
+        }
+
+        let mut ranked: Vec<(Uuid, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        if let Some(limit) = limit {
+            ranked.truncate(limit);
+        }
+        ranked.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// 沿 is_a 边逐层遍历
+    fn walk_taxonomy(&self, start: &Uuid, direction: Direction, levels: usize) -> Vec<(Uuid, usize)> {
+        let mut visited: HashSet<Uuid> = HashSet::from([*start]);
+        let mut found = Vec::new();
+        let mut frontier = vec![*start];
+
+        for level in 1..=levels {
+            let next: Vec<Uuid> = frontier.iter()
+                .filter_map(|id| self.id_to_index.get(id))
+                .flat_map(|&idx| self.graph.edges_directed(idx, direction))
+                .filter(|e| e.weight().relation == relation_types::IS_A)
+                .map(|e| match direction {
+                    Direction::Outgoing => e.weight().target,
+                    Direction::Incoming => e.weight().source,
+                })
+                .filter(|id| visited.insert(*id))
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            found.extend(next.iter().map(|id| (*id, level)));
+            frontier = next;
+        }
+
+        found
+    }
+
+    fn membership_neighbors(&self, node_id: &Uuid, direction: Direction) -> Vec<Uuid> {
+        let Some(&idx) = self.id_to_index.get(node_id) else {
+            return vec![];
+        };
+        let mut seen = HashSet::new();
+        self.graph.edges_directed(idx, direction)
+            .filter(|e| is_membership_relation(&e.weight().relation))
+            .map(|e| match direction {
+                Direction::Outgoing => e.weight().target,
+                Direction::Incoming => e.weight().source,
+            })
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 妈妈 -[instance_of]-> 家人 -[is_a]-> 人物 <-[is_a]- 同事 <-[instance_of]- 小王
+    fn family_graph() -> (KnowledgeGraph, [Uuid; 5]) {
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let [mom, family, person, colleague, wang] = ids;
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(instance_edge(mom, family));
+        graph.add_edge(taxonomy_edge(family, person));
+        graph.add_edge(taxonomy_edge(colleague, person));
+        graph.add_edge(instance_edge(wang, colleague));
+        (graph, ids)
+    }
+
+    #[test]
+    fn test_ancestors_descendants_and_cycles() {
+        let (graph, [mom, family, person, colleague, wang]) = family_graph();
+
+        assert_eq!(graph.concept_ancestors(&family, 3), vec![(person, 1)]);
+        let mut descendants: Vec<Uuid> = graph.concept_descendants(&person, 1).into_iter().map(|(id, _)| id).collect();
+        descendants.sort();
+        let mut expected = vec![family, colleague];
+        expected.sort();
+        assert_eq!(descendants, expected);
+
+        assert_eq!(graph.concepts_of(&mom), vec![family]);
+        assert_eq!(graph.concept_instances(&colleague), vec![wang]);
+        assert_eq!(graph.concept_instance_count(&colleague), 0);
+
+        assert!(graph.is_a_would_cycle(&person, &family));
+        assert!(graph.is_a_would_cycle(&family, &family));
+        assert!(!graph.is_a_would_cycle(&colleague, &family));
+    }
+
+    #[test]
+    fn test_concept_instance_count_after_merge() {
+        let mut graph = KnowledgeGraph::new();
+        let (keep, duplicate) = (Uuid::new_v4(), Uuid::new_v4());
+        let (mom, dad) = (Uuid::new_v4(), Uuid::new_v4());
+        graph.add_edge(EdgeBuilder::conceptualizes(mom, keep).build());
+        graph.add_edge(EdgeBuilder::conceptualizes(mom, duplicate).build());
+        graph.add_edge(EdgeBuilder::conceptualizes(dad, duplicate).build());
+        assert_eq!(graph.concept_instance_count(&keep), 1);
+
+        // 两个重复概念共享的实例在合并后只计一次
+        graph.merge_node_into(&duplicate, &keep).unwrap();
+        assert_eq!(graph.concept_instance_count(&keep), 2);
+        assert_eq!(graph.concept_instance_count(&duplicate), 0);
+    }
+
+    #[test]
+    fn test_bridging_generalizes_and_specializes() {
+        let (mut graph, [mom, family, person, colleague, wang]) = family_graph();
+        let stranger = Uuid::new_v4();
+        graph.add_edge(instance_edge(stranger, person));
+        let concepts: HashSet<Uuid> = [family, person, colleague].into_iter().collect();
+
+        // 不扩展层级：只到直接相连的概念及其邻居
+        let flat = graph.find_concept_bridged_with_levels(&[mom], &concepts, 0, 0, None);
+        assert!(flat.contains(&person));
+        assert!(!flat.contains(&colleague));
+
+        // 向上泛化一层到"人物"，从而关联到同为人物的"同事"
+        let generalized = graph.find_concept_bridged_with_levels(&[mom], &concepts, 1, 0, None);
+        assert!(generalized.contains(&colleague));
+        assert!(!generalized.contains(&wang));
+
+        // 从"人物"向下细化一层，取回各下位概念的实例
+        let specialized = graph.find_concept_bridged_with_levels(&[stranger], &concepts, 0, 1, None);
+        assert!(specialized.contains(&mom));
+        assert!(specialized.contains(&wang));
+        assert!(!specialized.contains(&stranger));
+    }
+
+    #[test]
+    fn test_bridging_keeps_strongest_edges_within_limit() {
+        let mut graph = KnowledgeGraph::new();
+        let (source, concept) = (Uuid::new_v4(), Uuid::new_v4());
+        graph.add_edge(EdgeBuilder::conceptualizes(source, concept).weight(1.0).build());
+        let others: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        for (i, id) in others.iter().enumerate() {
+            graph.add_edge(EdgeBuilder::conceptualizes(*id, concept).weight(0.1 * (i + 1) as f32).build());
+        }
+        let concepts: HashSet<Uuid> = [concept].into_iter().collect();
+
+        let all = graph.find_concept_bridged_with_levels(&[source], &concepts, 0, 0, None);
+        assert_eq!(all.len(), 5);
+        let top = graph.find_concept_bridged_with_levels(&[source], &concepts, 0, 0, Some(2));
+        assert_eq!(top, vec![others[4], others[3]]);
+    }
+
+    #[test]
+    fn test_bridging_discounts_expanded_levels() {
+        let (mut graph, [mom, family, person, _, _]) = family_graph();
+        let (cousin, stranger) = (Uuid::new_v4(), Uuid::new_v4());
+        graph.add_edge(EdgeBuilder::new(cousin, family, relation_types::INSTANCE_OF).weight(0.3).build());
+        graph.add_edge(EdgeBuilder::new(stranger, person, relation_types::INSTANCE_OF).weight(0.5).build());
+        let concepts: HashSet<Uuid> = [family, person].into_iter().collect();
+
+        // 经"人物"泛化得到的邻居权重折半，低于直接概念"家人"的邻居
+        let ranked = graph.find_concept_bridged_with_levels(&[mom], &concepts, 1, 0, None);
+        let position = |id: Uuid| ranked.iter().position(|x| *x == id).unwrap();
+        assert!(position(cousin) < position(stranger));
+    }
+}
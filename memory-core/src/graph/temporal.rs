@@ -0,0 +1,144 @@
+//! 事件时序
+//!
+//! 为同一段对话中的相邻事件、以及时间上相邻的前后两次对话建立
+//! `before` / `after` 边，检索时据此取回命中事件的前因与后续
+
+use super::{relation_types, EdgeBuilder, KnowledgeGraph, INITIAL_EDGE_WEIGHT};
+use crate::types::Edge;
+use crate::utils;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// 不同对话的事件之间建立时序关系的最大时间间隔（分钟）
+pub const TEMPORAL_LINK_WINDOW_MINUTES: i64 = 60;
+
+/// 一对时序边：`earlier -[before]-> later` 与 `later -[after]-> earlier`
+pub fn temporal_pair(earlier: Uuid, later: Uuid) -> [Edge; 2] {
+    [
+        EdgeBuilder::temporal(earlier, later, relation_types::BEFORE)
+            .weight(INITIAL_EDGE_WEIGHT)
+            .build(),
+        EdgeBuilder::temporal(later, earlier, relation_types::AFTER)
+            .weight(INITIAL_EDGE_WEIGHT)
+            .build(),
+    ]
+}
+
+/// 按事件时间排序（稳定排序：时间相同的事件保持原顺序，无法解析的时间排在最后）
+///
+/// 抽取顺序是消息顺序，"明天去医院"之后提到的"昨天发烧"发生得更早，串联前需先排序
+pub fn sort_by_event_time(events: &mut [(Uuid, String)]) {
+    events.sort_by_key(|(_, time)| {
+        let parsed = utils::parse_event_time(time);
+        (parsed.is_none(), parsed)
+    });
+}
+
+/// 按发生顺序串联事件
+///
+/// # Arguments
+/// * `event_ids` - 按发生顺序排列的事件 ID
+pub fn link_event_sequence(event_ids: &[Uuid]) -> Vec<Edge> {
+    event_ids.windows(2)
+        .flat_map(|pair| temporal_pair(pair[0], pair[1]))
+        .collect()
+}
+
+/// 两个事件时间是否先后相邻（later 不早于 earlier，且间隔不超过 window_minutes）
+///
+/// 任一时间无法解析时返回 false
+pub fn within_time_window(earlier: &str, later: &str, window_minutes: i64) -> bool {
+    let (Some(earlier), Some(later)) = (utils::parse_event_time(earlier), utils::parse_event_time(later)) else {
+        return false;
+    };
+    let gap = later.signed_duration_since(earlier).num_minutes();
+    (0..=window_minutes).contains(&gap)
+}
+
+impl KnowledgeGraph {
+    /// 沿时序边取相邻事件
+    ///
+    /// # Arguments
+    /// * `event_id` - 起始事件
+    /// * `steps` - 向前、向后各走的步数
+    ///
+    /// # Returns
+    /// (之前的事件, 之后的事件)，均按距离由近到远排列，不含起始事件
+    pub fn adjacent_events(&self, event_id: &Uuid, steps: usize) -> (Vec<Uuid>, Vec<Uuid>) {
+        (
+            self.follow_relation(event_id, relation_types::AFTER, steps),
+            self.follow_relation(event_id, relation_types::BEFORE, steps),
+        )
+    }
+
+    /// 沿指定关系的出边逐层前进
+    fn follow_relation(&self, start: &Uuid, relation: &str, steps: usize) -> Vec<Uuid> {
+        let mut visited: HashSet<Uuid> = HashSet::from([*start]);
+        let mut found = Vec::new();
+        let mut frontier = vec![*start];
+
+        for _ in 0..steps {
+            let next: Vec<Uuid> = frontier.iter()
+                .flat_map(|id| self.get_outgoing_edges(id))
+                .filter(|e| e.relation == relation)
+                .map(|e| e.target)
+                .filter(|id| visited.insert(*id))
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            found.extend(&next);
+            frontier = next;
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_event_sequence_and_adjacent_events() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let edges = link_event_sequence(&ids);
+        assert_eq!(edges.len(), 6);
+        assert!(edges.iter().any(|e| e.source == ids[0] && e.target == ids[1] && e.relation == relation_types::BEFORE));
+        assert!(edges.iter().any(|e| e.source == ids[1] && e.target == ids[0] && e.relation == relation_types::AFTER));
+
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edges(edges);
+
+        let (before, after) = graph.adjacent_events(&ids[1], 1);
+        assert_eq!(before, vec![ids[0]]);
+        assert_eq!(after, vec![ids[2]]);
+
+        let (before, after) = graph.adjacent_events(&ids[1], 5);
+        assert_eq!(before, vec![ids[0]]);
+        assert_eq!(after, vec![ids[2], ids[3]]);
+    }
+
+    #[test]
+    fn test_sort_by_event_time() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut events = vec![
+            (ids[0], "2024-05-02-09-00".to_string()),
+            (ids[1], "unknown".to_string()),
+            (ids[2], "2024-05-01-20-00".to_string()),
+            (ids[3], "2024-05-02-09-00".to_string()),
+        ];
+        sort_by_event_time(&mut events);
+        let order: Vec<Uuid> = events.iter().map(|(id, _)| *id).collect();
+        assert_eq!(order, vec![ids[2], ids[0], ids[3], ids[1]]);
+    }
+
+    #[test]
+    fn test_within_time_window() {
+        assert!(within_time_window("2024-05-01-10-00", "2024-05-01-10-45", 60));
+        assert!(within_time_window("2024-05-01-23-30", "2024-05-02-00-10", 60));
+        assert!(!within_time_window("2024-05-01-10-00", "2024-05-01-12-00", 60));
+        assert!(!within_time_window("2024-05-01-10-45", "2024-05-01-10-00", 60));
+        assert!(!within_time_window("invalid", "2024-05-01-10-00", 60));
+    }
+}
@@ -3,11 +3,120 @@
 //! 提供多种图遍历和路径搜索算法
 
 use crate::types::{Edge, NodeType};
-use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::stable_graph::{NodeIndex, StableDiGraph};
 use petgraph::visit::EdgeRef;
 use std::collections::{HashMap, HashSet, VecDeque};
 use uuid::Uuid;
 
+/// 遍历方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraversalDirection {
+    /// 只沿出边（source → target）
+    Outgoing,
+    /// 只沿入边（target → source）
+    Incoming,
+    /// 忽略方向
+    #[default]
+    Both,
+}
+
+impl TraversalDirection {
+    /// 对应的 petgraph 方向列表
+    pub fn petgraph_directions(&self) -> &'static [petgraph::Direction] {
+        match self {
+            TraversalDirection::Outgoing => &[petgraph::Direction::Outgoing],
+            TraversalDirection::Incoming => &[petgraph::Direction::Incoming],
+            TraversalDirection::Both => &[petgraph::Direction::Outgoing, petgraph::Direction::Incoming],
+        }
+    }
+}
+
+/// 遍历选项
+///
+/// 所有遍历函数共用，用于只沿特定关系、方向或强度的边行走，
+/// 例如沿 before/after 走时间链而不混入参与关系
+#[derive(Debug, Clone, Default)]
+pub struct TraversalOptions {
+    /// 允许经过的关系类型（None 表示不限制）
+    pub allowed_relations: Option<HashSet<String>>,
+    /// 遍历方向
+    pub direction: TraversalDirection,
+    /// 最小边权重（低于该值的边不经过）
+    pub min_weight: f32,
+    /// 允许到达的节点类型（None 表示不限制；类型未知的节点视为不满足）
+    pub node_types: Option<HashSet<NodeType>>,
+}
+
+impl TraversalOptions {
+    /// 只允许指定的关系类型
+    pub fn with_relations<I, S>(mut self, relations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_relations = Some(relations.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// 设置遍历方向
+    pub fn with_direction(mut self, direction: TraversalDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// 设置最小边权重
+    pub fn with_min_weight(mut self, min_weight: f32) -> Self {
+        self.min_weight = min_weight;
+        self
+    }
+
+    /// 只允许到达指定类型的节点
+    pub fn with_node_types(mut self, node_types: impl IntoIterator<Item = NodeType>) -> Self {
+        self.node_types = Some(node_types.into_iter().collect());
+        self
+    }
+
+    /// 边是否满足关系与权重条件
+    pub fn allows_edge(&self, edge: &Edge) -> bool {
+        edge.weight >= self.min_weight
+            && self.allowed_relations
+                .as_ref()
+                .is_none_or(|relations| relations.contains(&edge.relation))
+    }
+
+    /// 节点类型是否满足过滤条件
+    pub fn allows_node_type(&self, node_type: Option<NodeType>) -> bool {
+        match &self.node_types {
+            None => true,
+            Some(types) => node_type.is_some_and(|t| types.contains(&t)),
+        }
+    }
+}
+
+/// 按遍历选项列出节点的邻居
+///
+/// # Returns
+/// (邻居节点索引, 经过的边)，平行边会产生重复的邻居
+pub fn filtered_neighbors<'a>(
+    graph: &'a StableDiGraph<Uuid, Edge>,
+    node_types: &'a HashMap<Uuid, NodeType>,
+    node: NodeIndex,
+    options: &'a TraversalOptions,
+) -> impl Iterator<Item = (NodeIndex, &'a Edge)> + 'a {
+    options.direction.petgraph_directions()
+        .iter()
+        .flat_map(move |&direction| {
+            graph.edges_directed(node, direction).map(move |edge_ref| {
+                let neighbor = if edge_ref.source() == node { edge_ref.target() } else { edge_ref.source() };
+                (neighbor, edge_ref.weight())
+            })
+        })
+        .filter(move |(neighbor, edge)| {
+            options.allows_edge(edge)
+                && options.allows_node_type(node_types.get(&graph[*neighbor]).copied())
+        })
+}
+
 /// 遍历结果
 #[derive(Debug, Clone)]
 pub struct TraversalResult {
@@ -21,12 +130,14 @@ pub struct TraversalResult {
 
 /// BFS（广度优先搜索）遍历
 /// 
-/// 从起始节点开始，逐层扩展访问邻居节点
+/// 从起始节点开始，逐层扩展访问满足遍历选项的邻居节点
 pub fn bfs_traverse(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
     id_to_index: &HashMap<Uuid, NodeIndex>,
+    node_types: &HashMap<Uuid, NodeType>,
     start: &Uuid,
     max_depth: usize,
+    options: &TraversalOptions,
 ) -> TraversalResult {
     let mut result = TraversalResult {
         visited_nodes: Vec::new(),
@@ -52,8 +163,7 @@ pub fn bfs_traverse(
             continue;
         }
 
-        // 遍历所有邻居（包括入边和出边）
-        for neighbor_idx in graph.neighbors_undirected(current_idx) {
+        for (neighbor_idx, _) in filtered_neighbors(graph, node_types, current_idx, options) {
             if !visited.contains(&neighbor_idx) {
                 visited.insert(neighbor_idx);
                 
@@ -76,10 +186,12 @@ pub fn bfs_traverse(
 
 /// DFS（深度优先搜索）遍历
 pub fn dfs_traverse(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
     id_to_index: &HashMap<Uuid, NodeIndex>,
+    node_types: &HashMap<Uuid, NodeType>,
     start: &Uuid,
     max_depth: usize,
+    options: &TraversalOptions,
 ) -> TraversalResult {
     let mut result = TraversalResult {
         visited_nodes: Vec::new(),
@@ -91,30 +203,36 @@ pub fn dfs_traverse(
         return result;
     };
 
+    struct DfsContext<'a> {
+        graph: &'a StableDiGraph<Uuid, Edge>,
+        node_types: &'a HashMap<Uuid, NodeType>,
+        options: &'a TraversalOptions,
+        max_depth: usize,
+    }
+
     fn dfs_helper(
-        graph: &DiGraph<Uuid, Edge>,
+        ctx: &DfsContext,
         current: NodeIndex,
         depth: usize,
-        max_depth: usize,
         path: &mut Vec<Uuid>,
         visited: &mut HashSet<NodeIndex>,
         result: &mut TraversalResult,
     ) {
-        if depth > max_depth {
+        if depth > ctx.max_depth {
             return;
         }
 
         visited.insert(current);
         
-        if let Some(node_id) = graph.node_weight(current) {
+        if let Some(node_id) = ctx.graph.node_weight(current) {
             path.push(*node_id);
             result.visited_nodes.push(*node_id);
             result.depths.insert(*node_id, depth);
             result.paths.insert(*node_id, path.clone());
 
-            for neighbor in graph.neighbors_undirected(current) {
+            for (neighbor, _) in filtered_neighbors(ctx.graph, ctx.node_types, current, ctx.options) {
                 if !visited.contains(&neighbor) {
-                    dfs_helper(graph, neighbor, depth + 1, max_depth, path, visited, result);
+                    dfs_helper(ctx, neighbor, depth + 1, path, visited, result);
                 }
             }
 
@@ -122,19 +240,22 @@ pub fn dfs_traverse(
         }
     }
 
+    let ctx = DfsContext { graph, node_types, options, max_depth };
     let mut visited = HashSet::new();
     let mut path = Vec::new();
-    dfs_helper(graph, start_idx, 0, max_depth, &mut path, &mut visited, &mut result);
+    dfs_helper(&ctx, start_idx, 0, &mut path, &mut visited, &mut result);
 
     result
 }
 
-/// 查找两点之间的最短路径
+/// 查找两点之间的最短路径（按跳数）
 pub fn find_shortest_path(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
     id_to_index: &HashMap<Uuid, NodeIndex>,
+    node_types: &HashMap<Uuid, NodeType>,
     from: &Uuid,
     to: &Uuid,
+    options: &TraversalOptions,
 ) -> Option<Vec<Uuid>> {
     let start_idx = id_to_index.get(from)?;
     let end_idx = id_to_index.get(to)?;
@@ -151,7 +272,7 @@ pub fn find_shortest_path(
             return Some(path);
         }
 
-        for neighbor in graph.neighbors_undirected(current) {
+        for (neighbor, _) in filtered_neighbors(graph, node_types, current, options) {
             if !visited.contains(&neighbor) {
                 visited.insert(neighbor);
                 if let Some(neighbor_id) = graph.node_weight(neighbor) {
@@ -175,6 +296,8 @@ pub struct SubgraphConfig {
     pub include_concepts: bool,
     /// 节点类型过滤（None 表示不过滤）
     pub node_type_filter: Option<Vec<NodeType>>,
+    /// 扩展时使用的遍历选项
+    pub traversal: TraversalOptions,
 }
 
 impl Default for SubgraphConfig {
@@ -183,6 +306,7 @@ impl Default for SubgraphConfig {
             max_nodes: 30,
             include_concepts: true,
             node_type_filter: None,
+            traversal: TraversalOptions::default(),
         }
     }
 }
@@ -191,8 +315,9 @@ impl Default for SubgraphConfig {
 /// 
 /// 从一组种子节点开始，提取包含相关节点和边的子图
 pub fn extract_subgraph(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
     id_to_index: &HashMap<Uuid, NodeIndex>,
+    node_types: &HashMap<Uuid, NodeType>,
     seed_nodes: &[Uuid],
     hop_depth: usize,
     config: &SubgraphConfig,
@@ -207,12 +332,21 @@ pub fn extract_subgraph(
             break;
         }
 
-        let result = bfs_traverse(graph, id_to_index, seed, hop_depth);
+        let result = bfs_traverse(graph, id_to_index, node_types, seed, hop_depth, &config.traversal);
         
         for node_id in result.visited_nodes {
             if collected_nodes.len() >= config.max_nodes {
                 break;
             }
+            let node_type = node_types.get(&node_id).copied();
+            if !config.include_concepts && node_type == Some(NodeType::Concept) {
+                continue;
+            }
+            if let (Some(filter), Some(node_type)) = (&config.node_type_filter, node_type) {
+                if !filter.contains(&node_type) {
+                    continue;
+                }
+            }
             collected_nodes.insert(node_id);
         }
     }
@@ -223,7 +357,7 @@ pub fn extract_subgraph(
             for edge_ref in graph.edges(idx) {
                 let target_idx = edge_ref.target();
                 if let Some(target_id) = graph.node_weight(target_idx) {
-                    if collected_nodes.contains(target_id) {
+                    if collected_nodes.contains(target_id) && config.traversal.allows_edge(edge_ref.weight()) {
                         let edge_key = (node_id, *target_id);
                         if !edge_set.contains(&edge_key) {
                             edge_set.insert(edge_key);
@@ -242,7 +376,7 @@ pub fn extract_subgraph(
 /// 
 /// 基于节点的连接数量
 pub fn calculate_centrality(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
     id_to_index: &HashMap<Uuid, NodeIndex>,
     node_id: &Uuid,
 ) -> f64 {
@@ -264,7 +398,7 @@ pub fn calculate_centrality(
 
 /// 查找所有连通分量
 pub fn find_connected_components(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
 ) -> Vec<HashSet<NodeIndex>> {
     let mut visited: HashSet<NodeIndex> = HashSet::new();
     let mut components: Vec<HashSet<NodeIndex>> = Vec::new();
@@ -306,7 +440,7 @@ pub struct NeighborInfo {
 
 /// 获取节点的所有邻居及其关系
 pub fn get_neighbors_with_relations(
-    graph: &DiGraph<Uuid, Edge>,
+    graph: &StableDiGraph<Uuid, Edge>,
     id_to_index: &HashMap<Uuid, NodeIndex>,
     node_id: &Uuid,
 ) -> Vec<NeighborInfo> {
@@ -345,8 +479,8 @@ pub fn get_neighbors_with_relations(
 mod tests {
     use super::*;
 
-    fn create_test_graph() -> (DiGraph<Uuid, Edge>, HashMap<Uuid, NodeIndex>) {
-        let mut graph = DiGraph::new();
+    fn create_test_graph() -> (StableDiGraph<Uuid, Edge>, HashMap<Uuid, NodeIndex>) {
+        let mut graph = StableDiGraph::new();
         let mut id_to_index = HashMap::new();
 
         let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
@@ -377,7 +511,7 @@ mod tests {
         let (graph, id_to_index) = create_test_graph();
         let start = *id_to_index.keys().next().unwrap();
         
-        let result = bfs_traverse(&graph, &id_to_index, &start, 2);
+        let result = bfs_traverse(&graph, &id_to_index, &HashMap::new(), &start, 2, &TraversalOptions::default());
         
         assert!(result.visited_nodes.len() >= 1);
         assert!(result.depths.contains_key(&start));
@@ -389,8 +523,45 @@ mod tests {
         let (graph, id_to_index) = create_test_graph();
         let ids: Vec<Uuid> = id_to_index.keys().copied().collect();
         
-        let path = find_shortest_path(&graph, &id_to_index, &ids[0], &ids[0]);
+        let path = find_shortest_path(
+            &graph, &id_to_index, &HashMap::new(), &ids[0], &ids[0], &TraversalOptions::default(),
+        );
         assert!(path.is_some());
         assert_eq!(path.unwrap().len(), 1);
     }
+
+    #[test]
+    fn test_traversal_options() {
+        // e1 -before-> e2 -before-> e3，另有 p -participates_in-> e2
+        let mut graph = StableDiGraph::new();
+        let mut id_to_index = HashMap::new();
+        let mut node_types = HashMap::new();
+        let [e1, e2, e3, p] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        for (id, node_type) in [(e1, NodeType::Event), (e2, NodeType::Event), (e3, NodeType::Event), (p, NodeType::Entity)] {
+            id_to_index.insert(id, graph.add_node(id));
+            node_types.insert(id, node_type);
+        }
+        for (source, target, relation) in [(e1, e2, "before"), (e2, e3, "before"), (p, e2, "participates_in")] {
+            graph.add_edge(id_to_index[&source], id_to_index[&target],
+                Edge::new(source, target, relation.to_string()));
+        }
+
+        let temporal = TraversalOptions::default().with_relations(["before"]);
+        let result = bfs_traverse(&graph, &id_to_index, &node_types, &e1, 3, &temporal);
+        assert_eq!(result.visited_nodes, vec![e1, e2, e3]);
+
+        // 只沿出边：从 e3 出发无法回到 e2
+        let outgoing = temporal.clone().with_direction(TraversalDirection::Outgoing);
+        let result = dfs_traverse(&graph, &id_to_index, &node_types, &e3, 3, &outgoing);
+        assert_eq!(result.visited_nodes, vec![e3]);
+
+        // 节点类型过滤：只到达实体节点
+        let entities = TraversalOptions::default().with_node_types([NodeType::Entity]);
+        let result = bfs_traverse(&graph, &id_to_index, &node_types, &e2, 1, &entities);
+        assert_eq!(result.visited_nodes, vec![e2, p]);
+
+        // 最小权重过滤
+        let strong_only = TraversalOptions::default().with_min_weight(1.5);
+        assert!(find_shortest_path(&graph, &id_to_index, &node_types, &e1, &e3, &strong_only).is_none());
+    }
 }
@@ -0,0 +1,206 @@
+//! 外部知识导入
+//!
+//! 解析 JSON / CSV 格式的三元组（Head/Relation/Tail）与提取事件（Event/EventTime/Entities），
+//! 由 `MemorySystem::import_triples` 写入记忆，用于以用户资料等外部数据初始化记忆
+
+use crate::error::{MemoryError, Result};
+use crate::types::{ExtractedEvent, RawTriple};
+use serde::Deserialize;
+
+/// 导入数据格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// JSON 数组，元素为三元组或事件对象（可混合）
+    Json,
+    /// 带表头的 CSV：`head,relation,tail` 或 `event,event_time,entities`
+    /// （entities 以 `;` 或 `|` 分隔）
+    Csv,
+}
+
+/// 解析后的导入数据
+#[derive(Debug, Clone, Default)]
+pub struct ImportBatch {
+    /// 三元组
+    pub triples: Vec<RawTriple>,
+    /// 事件
+    pub events: Vec<ExtractedEvent>,
+}
+
+impl ImportBatch {
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.triples.is_empty() && self.events.is_empty()
+    }
+}
+
+/// JSON 数组元素
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonRecord {
+    Triple(RawTriple),
+    Event(ExtractedEvent),
+}
+
+/// 解析导入数据
+pub fn parse_import(data: &str, format: ImportFormat) -> Result<ImportBatch> {
+    match format {
+        ImportFormat::Json => parse_json(data),
+        ImportFormat::Csv => parse_csv(data),
+    }
+}
+
+fn parse_json(data: &str) -> Result<ImportBatch> {
+    let records: Vec<JsonRecord> = serde_json::from_str(data)?;
+    let mut batch = ImportBatch::default();
+    for record in records {
+        match record {
+            JsonRecord::Triple(triple) => batch.triples.push(triple),
+            JsonRecord::Event(event) => batch.events.push(event),
+        }
+    }
+    Ok(batch)
+}
+
+fn parse_csv(data: &str) -> Result<ImportBatch> {
+    let mut rows = parse_csv_rows(data)?.into_iter();
+    let header: Vec<String> = rows.next()
+        .ok_or_else(|| MemoryError::InvalidInput("CSV 缺少表头".to_string()))?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+
+    let mut batch = ImportBatch::default();
+    if let (Some(head), Some(relation), Some(tail)) = (column("head"), column("relation"), column("tail")) {
+        for (line, row) in rows.enumerate() {
+            let field = |i: usize| row.get(i).map(|s| s.trim().to_string()).unwrap_or_default();
+            check_row_width(&row, header.len(), line)?;
+            batch.triples.push(RawTriple {
+                head: field(head),
+                relation: field(relation),
+                tail: field(tail),
+            });
+        }
+    } else if let (Some(event), Some(entities)) = (column("event"), column("entities")) {
+        let event_time = column("event_time");
+        for (line, row) in rows.enumerate() {
+            let field = |i: usize| row.get(i).map(|s| s.trim().to_string()).unwrap_or_default();
+            check_row_width(&row, header.len(), line)?;
+            batch.events.push(ExtractedEvent {
+                event: field(event),
+                event_time: event_time.map(field).unwrap_or_default(),
+                entities: field(entities)
+                    .split([';', '|'])
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            });
+        }
+    } else {
+        return Err(MemoryError::InvalidInput(
+            "CSV 表头需包含 head,relation,tail 或 event,entities".to_string(),
+        ));
+    }
+    Ok(batch)
+}
+
+/// 数据行的字段数必须与表头一致（`line` 为数据行下标，报错时换算为文件行号）
+fn check_row_width(row: &[String], width: usize, line: usize) -> Result<()> {
+    if row.len() != width {
+        return Err(MemoryError::InvalidInput(format!(
+            "CSV 第 {} 行有 {} 个字段，表头有 {} 列",
+            line + 2, row.len(), width
+        )));
+    }
+    Ok(())
+}
+
+/// 按 RFC 4180 拆分 CSV（支持双引号包裹、引号转义与字段内换行），跳过空行
+fn parse_csv_rows(data: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = data.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.trim().is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(MemoryError::InvalidInput("CSV 引号未闭合".to_string()));
+    }
+    row.push(field);
+    if row.iter().any(|f| !f.trim().is_empty()) {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_mixed() {
+        let data = r#"[
+            {"Head": "小明", "Relation": "认识", "Tail": "小红"},
+            {"Event": "小明和小红去公园", "EventTime": "2024-05-01-10-00", "Entities": ["小明", "小红", "公园"]}
+        ]"#;
+        let batch = parse_import(data, ImportFormat::Json).unwrap();
+        assert_eq!(batch.triples.len(), 1);
+        assert_eq!(batch.triples[0].tail, "小红");
+        assert_eq!(batch.events.len(), 1);
+        assert_eq!(batch.events[0].entities.len(), 3);
+
+        assert!(parse_import("{\"Head\": 1}", ImportFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_triples_with_quotes() {
+        let data = "Head,Relation,Tail\r\n小明,位于,\"北京, 海淀\"\n\n\"他说\"\"你好\"\"\",认识,小红\n";
+        let batch = parse_import(data, ImportFormat::Csv).unwrap();
+        assert_eq!(batch.triples.len(), 2);
+        assert_eq!(batch.triples[0].tail, "北京, 海淀");
+        assert_eq!(batch.triples[1].head, "他说\"你好\"");
+        assert!(batch.events.is_empty());
+    }
+
+    #[test]
+    fn test_parse_csv_events_and_errors() {
+        let data = "event,event_time,entities\n去医院看病,2024-03-01-09-00,妈妈;医院\n";
+        let batch = parse_import(data, ImportFormat::Csv).unwrap();
+        assert_eq!(batch.events[0].entities, vec!["妈妈", "医院"]);
+        assert_eq!(batch.events[0].event_time, "2024-03-01-09-00");
+
+        assert!(parse_import("a,b\n1,2\n", ImportFormat::Csv).is_err());
+        assert!(parse_import("head,relation,tail\n\"x,y,z\n", ImportFormat::Csv).is_err());
+        assert!(parse_import("head,relation,tail\nx,y\n", ImportFormat::Csv).is_err());
+        // 未加引号的逗号会多出字段，整行拒绝而不是截断
+        assert!(parse_import("head,relation,tail\n小明,位于,北京,海淀\n", ImportFormat::Csv).is_err());
+    }
+}
@@ -27,6 +27,8 @@ pub mod graph;
 pub mod retrieval;
 pub mod embedding;
 pub mod utils;
+pub mod import;
+pub mod extraction;
 
 // Re-export main types
 pub use error::{MemoryError, Result};
@@ -41,37 +43,155 @@ pub struct MemorySystem {
     graph: Arc<RwLock<graph::KnowledgeGraph>>,
     retrieval: Arc<retrieval::RetrievalService>,
     embedding_client: Arc<embedding::EmbeddingClient>,
+    extractor: Arc<dyn extraction::Extractor>,
+    importance_scorer: Option<Arc<dyn extraction::ImportanceScorer>>,
+    entity_resolution: graph::EntityResolutionConfig,
     initialized: bool,
 }
 
-impl MemorySystem {
-    /// 创建新的记忆系统实例
+/// 记忆系统构建器
+///
+/// 未指定的部分使用默认值：数据库路径 `./memory_db`、基于规则的抽取器（事件重要性由其内置的规则评分给出）、
+/// 默认消息分组、默认实体消解配置、默认检索配置
+#[derive(Default)]
+pub struct MemorySystemBuilder {
+    db_path: Option<String>,
+    extractor: Option<Arc<dyn extraction::Extractor>>,
+    llm_extraction: Option<extraction::LlmExtractorConfig>,
+    importance_scorer: Option<Arc<dyn extraction::ImportanceScorer>>,
+    llm_importance: Option<extraction::LlmScorerConfig>,
+    message_grouping: Option<extraction::MessageGrouping>,
+    entity_resolution: Option<graph::EntityResolutionConfig>,
+    retrieval_config: Option<RetrievalConfig>,
+}
+
+impl MemorySystemBuilder {
+    /// 创建构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置数据库路径
+    pub fn db_path(mut self, db_path: impl Into<String>) -> Self {
+        self.db_path = Some(db_path.into());
+        self
+    }
+
+    /// 注册保存对话时使用的抽取器
+    pub fn extractor(mut self, extractor: Arc<dyn extraction::Extractor>) -> Self {
+        self.extractor = Some(extractor);
+        self
+    }
+
+    /// 使用对话模型抽取三元组与事件（与嵌入服务共享服务端 URL 和 Token）
     ///
-    /// # Arguments
-    /// * `db_path` - 数据库路径，为 None 时使用默认路径
+    /// 同时注册了自定义抽取器时以自定义抽取器为准
+    pub fn llm_extraction(mut self, config: extraction::LlmExtractorConfig) -> Self {
+        self.llm_extraction = Some(config);
+        self
+    }
+
+    /// 注册事件重要性评分器，覆盖抽取器给出的事件重要性
+    pub fn importance_scorer(mut self, scorer: Arc<dyn extraction::ImportanceScorer>) -> Self {
+        self.importance_scorer = Some(scorer);
+        self
+    }
+
+    /// 使用对话模型为事件重要性打分（与嵌入服务共享服务端 URL 和 Token）
     ///
-    /// # Returns
-    /// 新的 MemorySystem 实例
-    pub fn new(db_path: Option<&str>) -> Result<Self> {
-        let db_path = db_path.unwrap_or("./memory_db");
+    /// 同时注册了自定义评分器时以自定义评分器为准
+    pub fn llm_importance(mut self, config: extraction::LlmScorerConfig) -> Self {
+        self.llm_importance = Some(config);
+        self
+    }
+
+    /// 设置消息分组配置（角色处理方式、多轮合并窗口、参与实体抽取的消息）
+    ///
+    /// 作用于内置的规则抽取器与大模型抽取器，自定义抽取器自行决定如何处理各角色的消息
+    pub fn message_grouping(mut self, grouping: extraction::MessageGrouping) -> Self {
+        self.message_grouping = Some(grouping);
+        self
+    }
+
+    /// 设置实体消解配置
+    pub fn entity_resolution(mut self, config: graph::EntityResolutionConfig) -> Self {
+        self.entity_resolution = Some(config);
+        self
+    }
+
+    /// 设置检索配置（扩展策略、图加分、边增强等）
+    pub fn retrieval_config(mut self, config: RetrievalConfig) -> Self {
+        self.retrieval_config = Some(config);
+        self
+    }
+
+    /// 构建记忆系统（尚未初始化）
+    pub fn build(self) -> Result<MemorySystem> {
+        let db_path = self.db_path.as_deref().unwrap_or("./memory_db");
 
         let storage = Arc::new(RwLock::new(storage::LanceDBStorage::new(db_path)?));
         let graph = Arc::new(RwLock::new(graph::KnowledgeGraph::new()));
         let embedding_client = Arc::new(embedding::EmbeddingClient::new()?);
-        let retrieval = Arc::new(retrieval::RetrievalService::new(
+        let retrieval = Arc::new(retrieval::RetrievalService::with_config(
             storage.clone(),
             graph.clone(),
             embedding_client.clone(),
+            self.retrieval_config.unwrap_or_default(),
         ));
 
-        Ok(Self {
+        let grouping = self.message_grouping.unwrap_or_default();
+        let extractor: Arc<dyn extraction::Extractor> = match (self.extractor, self.llm_extraction) {
+            (Some(extractor), _) => extractor,
+            (None, Some(config)) => Arc::new(
+                extraction::LlmExtractor::with_shared_config(embedding_client.shared_server_config(), config)?
+                    .with_grouping(grouping),
+            ),
+            (None, None) => Arc::new(extraction::RuleBasedExtractor::new().with_grouping(grouping)),
+        };
+
+        let importance_scorer: Option<Arc<dyn extraction::ImportanceScorer>> =
+            match (self.importance_scorer, self.llm_importance) {
+                (Some(scorer), _) => Some(scorer),
+                (None, Some(config)) => Some(Arc::new(extraction::LlmScorer::with_shared_config(
+                    embedding_client.shared_server_config(),
+                    config,
+                )?)),
+                (None, None) => None,
+            };
+
+        Ok(MemorySystem {
             storage,
             graph,
             retrieval,
             embedding_client,
+            extractor,
+            importance_scorer,
+            entity_resolution: self.entity_resolution.unwrap_or_default(),
             initialized: false,
         })
     }
+}
+
+impl MemorySystem {
+    /// 创建新的记忆系统实例
+    ///
+    /// # Arguments
+    /// * `db_path` - 数据库路径，为 None 时使用默认路径
+    ///
+    /// # Returns
+    /// 新的 MemorySystem 实例
+    pub fn new(db_path: Option<&str>) -> Result<Self> {
+        let mut builder = MemorySystemBuilder::new();
+        if let Some(db_path) = db_path {
+            builder = builder.db_path(db_path);
+        }
+        builder.build()
+    }
+
+    /// 创建构建器，用于注册自定义抽取器等
+    pub fn builder() -> MemorySystemBuilder {
+        MemorySystemBuilder::new()
+    }
 
     /// 初始化记忆系统
     ///
@@ -89,19 +209,14 @@ impl MemorySystem {
             storage.initialize().await?;
         }
 
-        // 从存储加载图结构
-        {
-            let storage = self.storage.read().await;
-            let mut graph = self.graph.write().await;
-
-            let edges = storage.get_all_edges().await?;
-            log::info!("从存储加载了 {} 条边到图结构", edges.len());
-            for edge in edges {
-                graph.add_edge(edge);
-            }
-        }
+        // 从快照或存储加载图结构
+        self.load_graph().await?;
 
         self.initialized = true;
+
+        // 旧数据中的同名概念节点合并为一个
+        self.consolidate_concepts().await?;
+
         log::info!("记忆系统初始化完成");
         Ok(())
     }
@@ -124,10 +239,10 @@ impl MemorySystem {
     /// 保存对话到记忆
     ///
     /// 核心流程：
-    /// 1. 将消息转换为事件节点
-    /// 2. 简单实体提取（关键词匹配）
-    /// 3. 概念归类
-    /// 4. 生成嵌入向量
+    /// 1. 抽取器将消息转换为事件、实体、概念节点和边
+    /// 2. 概念复用：同名概念对齐到已有节点
+    /// 3. 生成嵌入向量
+    /// 4. 实体消解：与已有实体对齐并复用已有节点
     /// 5. 写入 LanceDB 和图结构
     ///
     /// # Arguments
@@ -143,23 +258,60 @@ impl MemorySystem {
 
         log::info!("正在保存 {} 条消息到记忆", messages.len());
 
-        // Step 1: 消息 → 事件节点
-        let event_nodes = self.messages_to_events(messages);
-        log::debug!("创建了 {} 个事件节点", event_nodes.len());
+        // 没有时间戳的消息以收到的时间为准，抽取器据此解析"昨天"等相对时间
+        let received_at = chrono::Utc::now().timestamp();
+        let messages: Vec<Message> = messages.iter()
+            .map(|msg| Message {
+                timestamp: msg.timestamp.or(Some(received_at)),
+                ..msg.clone()
+            })
+            .collect();
+
+        // Step 1: 抽取事件、实体、概念与边
+        let extraction::Extraction { mut events, entities, concepts, edges, scoring_texts } =
+            self.extractor.extract(&messages).await?;
+        log::debug!(
+            "抽取器 {} 产出 {} 个事件、{} 个实体、{} 个概念、{} 条边",
+            self.extractor.name(), events.len(), entities.len(), concepts.len(), edges.len()
+        );
+
+        // 注册了评分器时重新评估事件重要性（失败时保留抽取器给出的值）
+        // 评分文本取抽取器给出的用户消息原文，避免助手回复与角色标注影响分数
+        if let Some(scorer) = &self.importance_scorer {
+            let texts: Vec<String> = events.iter()
+                .map(|n| scoring_texts.get(&n.id).cloned().unwrap_or_else(|| n.content.clone()))
+                .collect();
+            match scorer.score(&texts).await {
+                Ok(scores) if scores.len() == events.len() => {
+                    for (event, score) in events.iter_mut().zip(scores) {
+                        match extraction::clamp_importance(score) {
+                            Some(score) => event.importance = score,
+                            None => log::warn!(
+                                "评分器 {} 给事件 {} 的分数不是数值，保留原重要性", scorer.name(), event.id
+                            ),
+                        }
+                    }
+                }
+                Ok(scores) => log::warn!(
+                    "评分器 {} 返回 {} 个分数，与 {} 个事件不一致，保留原重要性",
+                    scorer.name(), scores.len(), events.len()
+                ),
+                Err(e) => log::warn!("评分器 {} 评分失败，保留原重要性: {}", scorer.name(), e),
+            }
+        }
 
-        // Step 2: 简单实体提取
-        let (entity_nodes, entity_to_events) = self.extract_entities(messages, &event_nodes);
-        log::debug!("提取了 {} 个实体节点", entity_nodes.len());
+        // Step 2: 概念复用（同名概念复用已有节点）
+        let (concept_nodes, mut reused_concepts, mut resolved_ids) = self.reuse_concepts(concepts).await?;
+        log::debug!("创建了 {} 个概念节点，复用 {} 个", concept_nodes.len(), reused_concepts.len());
 
-        // Step 3: 概念归类
-        let (concept_nodes, concept_edges) =
-            self.conceptualize_entities(&entity_nodes);
-        log::debug!("创建了 {} 个概念节点", concept_nodes.len());
+        let event_sequence: Vec<(uuid::Uuid, String)> = events.iter()
+            .map(|n| (n.id, n.event_time().unwrap_or_default().to_string()))
+            .collect();
 
-        // Step 4: 生成嵌入向量
+        // Step 3: 生成嵌入向量
         let mut all_nodes: Vec<MemoryNode> = Vec::new();
-        all_nodes.extend(event_nodes);
-        all_nodes.extend(entity_nodes);
+        all_nodes.extend(events);
+        all_nodes.extend(entities);
         all_nodes.extend(concept_nodes);
 
         // 批量嵌入
@@ -176,51 +328,902 @@ impl MemorySystem {
             }
         }
 
-        // Step 5: 构建边（实体 ↔ 事件的参与关系）
-        let mut all_edges: Vec<Edge> = Vec::new();
-        for (entity_id, event_ids) in &entity_to_events {
-            for event_id in event_ids {
-                all_edges.push(Edge::new(
-                    *entity_id,
-                    *event_id,
-                    graph::relation_types::PARTICIPATES_IN.to_string(),
-                ));
-            }
+        // Step 4: 实体消解（已有实体复用原节点）
+        let (mut all_nodes, reused_entities, resolved_entities) = self.resolve_entities(all_nodes).await?;
+        resolved_ids.extend(resolved_entities);
+        let resolve_id = |id: uuid::Uuid| resolved_ids.get(&id).copied().unwrap_or(id);
+
+        // Step 5: 抽取的边改接到复用的节点上
+        let mut all_edges: Vec<Edge> = edges.into_iter()
+            .map(|mut edge| {
+                edge.source = resolve_id(edge.source);
+                edge.target = resolve_id(edge.target);
+                edge
+            })
+            .filter(|edge| edge.source != edge.target)
+            .collect();
+
+        // 事件之间的时序关系
+        all_edges.extend(self.link_temporal_events(&event_sequence).await?);
+
+        // Step 6: 计算边的写入结果（相同关系合并为一条边并增强权重）；
+        // 持有图写锁直到存储写入完成，写入失败时内存图保持不变
+        let mut graph = self.graph.write().await;
+        let (new_edges, reinforced_edges) = graph.plan_upsert_edges(all_edges);
+
+        // 概念实例数按新建立的概念化边累加
+        let mut new_instances: std::collections::HashMap<uuid::Uuid, u32> = std::collections::HashMap::new();
+        for edge in new_edges.iter().filter(|e| e.relation == graph::relation_types::CONCEPTUALIZED_AS) {
+            *new_instances.entry(edge.target).or_default() += 1;
+        }
+        for concept in all_nodes.iter_mut()
+            .chain(reused_concepts.iter_mut())
+            .filter(|n| n.node_type() == NodeType::Concept)
+        {
+            graph::touch_concept(concept, new_instances.get(&concept.id).copied().unwrap_or(0));
         }
-        all_edges.extend(concept_edges);
 
-        // Step 6: 写入 LanceDB
+        // Step 7: 写入 LanceDB
         {
             let storage = self.storage.read().await;
             storage.add_nodes(&all_nodes).await?;
-            storage.add_edges(&all_edges).await?;
+            for node in reused_entities.iter().chain(&reused_concepts) {
+                storage.update_node(node).await?;
+            }
+            storage.add_edges(&new_edges).await?;
+            storage.update_edges(&reinforced_edges).await?;
 
-            // 更新概念池
-            let concept_names: Vec<String> = all_nodes.iter()
+            // 概念池与概念节点同步
+            let concepts: Vec<MemoryNode> = all_nodes.iter()
+                .chain(&reused_concepts)
                 .filter(|n| n.node_type() == NodeType::Concept)
-                .map(|n| n.content.clone())
+                .cloned()
                 .collect();
-            if !concept_names.is_empty() {
-                storage.upsert_concepts(&concept_names).await?;
+            storage.upsert_concepts(&concepts).await?;
+        }
+
+        // Step 8: 存储写入成功后更新内存图结构
+        for node in &all_nodes {
+            graph.set_node_type(node.id, node.node_type());
+        }
+        graph.apply_upserted_edges(&new_edges, &reinforced_edges);
+        drop(graph);
+
+        log::info!(
+            "保存完成: {} 个新节点, {} 个实体复用已有节点, {} 条新边, {} 条边被增强",
+            all_nodes.len(),
+            reused_entities.len(),
+            new_edges.len(),
+            reinforced_edges.len()
+        );
+        Ok(())
+    }
+
+    /// 查找两条记忆之间的关联
+    ///
+    /// 在知识图谱上按边权重搜索最短路径，并附带节点内容，
+    /// 用于向用户解释两件记住的事情是如何联系在一起的
+    ///
+    /// # Returns
+    /// 两者不连通时返回 None
+    pub async fn find_connection(
+        &self,
+        a: &uuid::Uuid,
+        b: &uuid::Uuid,
+    ) -> Result<Option<ConnectionPath>> {
+        let paths = self.find_connections(a, b, 1, &graph::PathOptions::default()).await?;
+        Ok(paths.into_iter().next())
+    }
+
+    /// 查找两条记忆之间的前 k 条关联路径
+    ///
+    /// # Arguments
+    /// * `k` - 最多返回的路径数量
+    /// * `options` - 关系类型、方向和跳数限制
+    pub async fn find_connections(
+        &self,
+        a: &uuid::Uuid,
+        b: &uuid::Uuid,
+        k: usize,
+        options: &graph::PathOptions,
+    ) -> Result<Vec<ConnectionPath>> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let paths = {
+            let graph = self.graph.read().await;
+            graph.find_k_shortest_paths(a, b, k, options)
+        };
+        if paths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let ids: Vec<uuid::Uuid> = paths.iter()
+            .flat_map(|p| p.nodes.iter().copied())
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect();
+        let nodes: std::collections::HashMap<uuid::Uuid, MemoryNode> = {
+            let storage = self.storage.read().await;
+            storage.get_nodes(&ids).await?
+                .into_iter()
+                .map(|n| (n.id, n))
+                .collect()
+        };
+
+        Ok(paths.iter().map(|path| graph::describe_path(path, &nodes)).collect())
+    }
+
+    /// 列出记忆话题
+    ///
+    /// 在知识图谱上做社区发现，每个社区附带若干代表节点，
+    /// 供应用向用户展示其记忆涉及的主要话题
+    ///
+    /// # Arguments
+    /// * `config` - 社区发现配置（如忽略度数过高的泛化概念）
+    pub async fn list_communities(
+        &self,
+        config: &graph::CommunityConfig,
+    ) -> Result<Vec<MemoryCommunity>> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let communities = {
+            let graph = self.graph.read().await;
+            graph.detect_communities(config)
+        };
+        if communities.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let ids: Vec<uuid::Uuid> = communities.iter()
+            .flat_map(|c| c.representatives.iter().copied())
+            .collect();
+        let nodes: std::collections::HashMap<uuid::Uuid, MemoryNode> = {
+            let storage = self.storage.read().await;
+            storage.get_nodes(&ids).await?
+                .into_iter()
+                .map(|n| (n.id, n))
+                .collect()
+        };
+
+        Ok(communities.into_iter()
+            .map(|community| {
+                let representatives: Vec<PathNode> = community.representatives.iter()
+                    .filter_map(|id| nodes.get(id))
+                    .map(|node| PathNode {
+                        id: node.id,
+                        content: node.content.clone(),
+                        node_type: node.node_type(),
+                    })
+                    .collect();
+                MemoryCommunity {
+                    id: community.id,
+                    size: community.members.len(),
+                    label: representatives.first()
+                        .map(|n| n.content.clone())
+                        .unwrap_or_default(),
+                    representatives,
+                }
+            })
+            .collect())
+    }
+
+    /// 用图中心性提升节点重要性
+    ///
+    /// 计算全图 PageRank 与介数中心性，把综合得分比当前 importance 高出
+    /// `graph::MIN_IMPORTANCE_GAIN` 以上的节点提升到该得分并写回存储，使串联大量记忆的人物、地点自动被视为重要
+    ///
+    /// # Returns
+    /// 被更新的节点数量
+    pub async fn apply_centrality_to_importance(
+        &self,
+        config: &graph::CentralityConfig,
+    ) -> Result<usize> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let scores: std::collections::HashMap<uuid::Uuid, f32> = {
+            let graph = self.graph.read().await;
+            graph.compute_centrality(config)
+                .into_iter()
+                .map(|(id, s)| (id, s.combined(config.pagerank_weight) as f32))
+                .filter(|(_, score)| *score > 0.0)
+                .collect()
+        };
+        let ids: Vec<uuid::Uuid> = scores.keys().copied().collect();
+
+        let storage = self.storage.read().await;
+        let mut updated = 0usize;
+        for chunk in ids.chunks(500) {
+            for mut node in storage.get_nodes(chunk).await? {
+                let score = scores[&node.id];
+                if score > node.importance + graph::MIN_IMPORTANCE_GAIN {
+                    node.importance = score.min(1.0);
+                    storage.update_node(&node).await?;
+                    updated += 1;
+                }
             }
         }
 
-        // Step 7: 更新内存图结构
+        log::info!("根据图中心性更新了 {} 个节点的重要性", updated);
+        Ok(updated)
+    }
+
+    /// 执行类 Cypher 的图模式查询
+    ///
+    /// 例如查找妈妈和医院共同参与的事件：
+    /// `MATCH (m:entity {content: "妈妈"})-[:participates_in]->(e:event)<-[:participates_in]-(h:entity {content: "医院"}) RETURN e`
+    pub async fn pattern_query(&self, query: &str) -> Result<PatternQueryResult> {
+        let query = graph::PatternQuery::parse(query)?;
+        self.run_pattern_query(&query).await
+    }
+
+    /// 执行以构建器构造的图模式查询
+    ///
+    /// 带内容条件的节点先在存储中解析为候选集合，再在图上匹配结构，
+    /// 最后从存储取回返回变量对应的节点内容
+    pub async fn run_pattern_query(&self, query: &graph::PatternQuery) -> Result<PatternQueryResult> {
+        use std::collections::{HashMap, HashSet};
+
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+        query.validate()?;
+
+        let storage = self.storage.read().await;
+        let mut candidates: HashMap<String, HashSet<uuid::Uuid>> = HashMap::new();
+        for node in query.nodes.iter().filter(|n| !n.content.is_empty()) {
+            let ids = storage.find_nodes_by_content(node.node_type, &node.content).await?
+                .into_iter()
+                .map(|n| n.id)
+                .collect();
+            candidates.insert(node.variable.clone(), ids);
+        }
+
+        let rows = {
+            let graph = self.graph.read().await;
+            graph.match_pattern(query, &candidates)?
+        };
+
+        let ids: Vec<uuid::Uuid> = rows.iter()
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut nodes: HashMap<uuid::Uuid, MemoryNode> = HashMap::new();
+        for chunk in ids.chunks(500) {
+            nodes.extend(storage.get_nodes(chunk).await?.into_iter().map(|n| (n.id, n)));
+        }
+
+        let rows: Vec<Vec<PathNode>> = rows.into_iter()
+            .filter_map(|row| {
+                row.iter()
+                    .map(|id| nodes.get(id).map(|n| PathNode {
+                        id: n.id,
+                        content: n.content.clone(),
+                        node_type: n.node_type(),
+                    }))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect();
+
+        log::info!("模式查询返回 {} 行", rows.len());
+        Ok(PatternQueryResult {
+            columns: query.returns.clone(),
+            rows,
+        })
+    }
+
+    /// 清理衰减后过弱的边
+    ///
+    /// 边权重自最近一次观测或被检索使用起按天衰减，低于 `prune_below` 的边
+    /// 从存储和图中删除并刷新图快照；适合在应用空闲时定期调用
+    ///
+    /// # Returns
+    /// 被删除的边数量
+    pub async fn prune_weak_edges(&self, config: &graph::EdgeDecayConfig) -> Result<usize> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let weak = {
+            let graph = self.graph.read().await;
+            graph.weak_edges(config)
+        };
+        if weak.is_empty() {
+            return Ok(0);
+        }
+
+        {
+            let storage = self.storage.read().await;
+            storage.delete_edges(&weak).await?;
+        }
         {
             let mut graph = self.graph.write().await;
-            for edge in &all_edges {
-                graph.add_edge(edge.clone());
+            for id in &weak {
+                graph.remove_edge(id);
             }
         }
 
+        // 快照的增量回放无法感知删除，立即写入新快照
+        if let Err(e) = self.persist_graph_snapshot().await {
+            log::warn!("写入图快照失败: {}", e);
+        }
+
+        log::info!("清理了 {} 条衰减后过弱的边", weak.len());
+        Ok(weak.len())
+    }
+
+    /// 导出知识图谱
+    ///
+    /// 将图结构与存储中的节点内容、类型和重要性拼接后序列化，
+    /// 可在 Gephi、Graphviz 或网页查看器中查看
+    ///
+    /// # Arguments
+    /// * `format` - GraphML / DOT / JSON node-link
+    /// * `options` - 节点类型、关系过滤，或只导出种子节点周围的子图
+    pub async fn export_graph(
+        &self,
+        format: graph::ExportFormat,
+        options: &graph::ExportOptions,
+    ) -> Result<String> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let (mut node_ids, edges, mut node_types) = {
+            let graph = self.graph.read().await;
+            let (node_ids, edges) = graph.select_for_export(options);
+            let node_types: Vec<Option<NodeType>> = node_ids.iter()
+                .map(|id| graph.node_type(id))
+                .collect();
+            (node_ids, edges, node_types)
+        };
+
+        let mut stored: std::collections::HashMap<uuid::Uuid, MemoryNode> = std::collections::HashMap::new();
+        {
+            let storage = self.storage.read().await;
+
+            // 没有任何边的节点不在图结构中，导出整张图时从存储补齐
+            if options.seed_ids.is_empty() {
+                let selected: std::collections::HashSet<uuid::Uuid> = node_ids.iter().copied().collect();
+                let mut isolated: Vec<(uuid::Uuid, NodeType)> = storage.get_node_types(None).await?
+                    .into_iter()
+                    .filter(|(id, node_type)| {
+                        !selected.contains(id) && options.traversal.allows_node_type(Some(*node_type))
+                    })
+                    .collect();
+                isolated.sort_by_key(|(id, _)| *id);
+                for (id, node_type) in isolated {
+                    node_ids.push(id);
+                    node_types.push(Some(node_type));
+                }
+            }
+
+            for chunk in node_ids.chunks(500) {
+                stored.extend(storage.get_nodes(chunk).await?.into_iter().map(|n| (n.id, n)));
+            }
+        }
+
+        let nodes: Vec<graph::ExportNode> = node_ids.iter()
+            .zip(node_types)
+            .map(|(id, node_type)| match stored.get(id) {
+                Some(node) => graph::ExportNode {
+                    id: *id,
+                    content: node.content.clone(),
+                    node_type: Some(node.node_type()),
+                    importance: node.importance,
+                },
+                None => graph::ExportNode {
+                    id: *id,
+                    content: id.to_string(),
+                    node_type,
+                    importance: 0.0,
+                },
+            })
+            .collect();
+
+        log::info!("导出图: {} 个节点, {} 条边", nodes.len(), edges.len());
+        Ok(graph::export_graph(format, &nodes, &edges))
+    }
+
+    /// 导入外部知识
+    ///
+    /// 解析 JSON / CSV 中的三元组与事件，同名实体在本批次内只建一个节点，
+    /// 再经实体消解与已有实体对齐（与保存对话时相同的规则）；
+    /// 关系经 `normalize_relation` 规范化后写入边
+    ///
+    /// # Arguments
+    /// * `data` - 导入数据
+    /// * `format` - 数据格式
+    pub async fn import_triples(
+        &self,
+        data: &str,
+        format: import::ImportFormat,
+    ) -> Result<ImportReport> {
+        let batch = import::parse_import(data, format)?;
+        self.import_batch(&batch).await
+    }
+
+    /// 导入已解析的三元组与事件
+    pub async fn import_batch(&self, batch: &import::ImportBatch) -> Result<ImportReport> {
+        use std::collections::{HashMap, HashSet};
+
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let mut report = ImportReport::default();
+        if batch.is_empty() {
+            return Ok(report);
+        }
+
+        // 有效的三元组与事件
+        let mut triples: Vec<(&str, String, &str)> = Vec::new();
+        for triple in &batch.triples {
+            let (head, tail) = (triple.head.trim(), triple.tail.trim());
+            let relation = graph::normalize_relation(&triple.relation);
+            if head.is_empty() || tail.is_empty() || relation.is_empty() {
+                report.skipped += 1;
+            } else {
+                triples.push((head, relation, tail));
+            }
+        }
+        let mut events: Vec<(&str, &str, &ExtractedEvent)> = Vec::new();
+        for event in &batch.events {
+            let content = event.event.trim();
+            let event_time = event.event_time.trim();
+            if content.is_empty() {
+                report.skipped += 1;
+            } else if !event_time.is_empty() && utils::parse_event_time(event_time).is_none() {
+                log::debug!("跳过时间格式不正确的事件: \"{}\" ({})", content, event_time);
+                report.skipped += 1;
+            } else {
+                events.push((content, event_time, event));
+            }
+        }
+
+        // 本批次内的实体按名称去重，每个名称只生成一个候选节点
+        let mut candidate_ids: HashMap<&str, uuid::Uuid> = HashMap::new();
+        let mut candidates: Vec<MemoryNode> = Vec::new();
+        let names = triples.iter()
+            .flat_map(|(head, _, tail)| [*head, *tail])
+            .chain(events.iter().flat_map(|(_, _, e)| e.entities.iter().map(|name| name.trim())))
+            .filter(|name| !name.is_empty());
+        for name in names {
+            candidate_ids.entry(name).or_insert_with(|| {
+                let node = graph::NodeBuilder::entity(name)
+                    .entity_type(graph::infer_entity_type(name))
+                    .build();
+                let id = node.id;
+                candidates.push(node);
+                id
+            });
+        }
+
+        // 候选实体先生成向量，再与已有实体对齐
+        self.embed_import_nodes(&mut candidates).await;
+        let (mut new_nodes, reused_entities, resolved_ids) = self.resolve_entities(candidates).await?;
+        let entity_id = |name: &str| {
+            let id = candidate_ids[name];
+            resolved_ids.get(&id).copied().unwrap_or(id)
+        };
+        report.entities_created = new_nodes.len();
+        report.entities_reused = reused_entities.len();
+
+        // 三元组：头实体 -[关系]-> 尾实体
+        let mut observed_edges: Vec<Edge> = Vec::new();
+        for (head, relation, tail) in triples {
+            let (head_id, tail_id) = (entity_id(head), entity_id(tail));
+            if head_id == tail_id {
+                report.skipped += 1;
+                continue;
+            }
+            observed_edges.push(
+                graph::EdgeBuilder::new(head_id, tail_id, relation)
+                    .weight(graph::INITIAL_EDGE_WEIGHT)
+                    .build(),
+            );
+        }
+
+        // 事件：参与实体 -[participates_in]-> 事件
+        let mut event_nodes: Vec<MemoryNode> = Vec::new();
+        for (content, event_time, event) in events {
+            let participants: Vec<uuid::Uuid> = event.entities.iter()
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .map(entity_id)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let event_time = if event_time.is_empty() {
+                utils::now_event_time()
+            } else {
+                event_time.to_string()
+            };
+
+            let node = graph::NodeBuilder::event(content)
+                .event_time(event_time)
+                .participants(participants.clone())
+                .build();
+            for participant in participants {
+                observed_edges.push(
+                    graph::EdgeBuilder::participates(participant, node.id)
+                        .weight(graph::INITIAL_EDGE_WEIGHT)
+                        .build(),
+                );
+            }
+            event_nodes.push(node);
+        }
+        report.events_created = event_nodes.len();
+        self.embed_import_nodes(&mut event_nodes).await;
+        new_nodes.extend(event_nodes);
+
+        // 已存在的关系只增强权重，不产生平行边；先写入存储，成功后再更新内存图
+        let mut graph = self.graph.write().await;
+        let (new_edges, reinforced_edges) = graph.plan_upsert_edges(observed_edges);
+        {
+            let storage = self.storage.read().await;
+            storage.add_nodes(&new_nodes).await?;
+            for node in &reused_entities {
+                storage.update_node(node).await?;
+            }
+            storage.add_edges(&new_edges).await?;
+            storage.update_edges(&reinforced_edges).await?;
+        }
+        for node in &new_nodes {
+            graph.add_typed_node(node.id, node.node_type());
+        }
+        graph.apply_upserted_edges(&new_edges, &reinforced_edges);
+        drop(graph);
+
+        report.edges_created = new_edges.len();
+        report.edges_reinforced = reinforced_edges.len();
         log::info!(
-            "保存完成: {} 个节点, {} 条边",
-            all_nodes.len(),
-            all_edges.len()
+            "导入完成: 新建 {} 个实体、{} 个事件，写入 {} 条边，增强 {} 条边，跳过 {} 条记录",
+            report.entities_created, report.events_created, report.edges_created,
+            report.edges_reinforced, report.skipped
         );
+        Ok(report)
+    }
+
+    /// 为导入的节点生成嵌入向量（失败时节点不带向量存储）
+    async fn embed_import_nodes(&self, nodes: &mut [MemoryNode]) {
+        if nodes.is_empty() {
+            return;
+        }
+        let texts: Vec<String> = nodes.iter().map(|n| n.content.clone()).collect();
+        match self.embedding_client.embed_batch(&texts).await {
+            Ok(embeddings) => {
+                for (node, emb) in nodes.iter_mut().zip(embeddings) {
+                    node.embedding = emb;
+                }
+            }
+            Err(e) => {
+                log::error!("嵌入向量生成失败: {}，导入的节点将不带向量存储", e);
+            }
+        }
+    }
+
+    /// 合并两个实体节点
+    ///
+    /// `remove` 的所有边改接到 `keep` 上（重复关系合并增强），事件的参与者列表
+    /// 随之替换，`remove` 的访问次数、重要性等合并进 `keep` 后删除，
+    /// 其名称记为 `keep` 的别名；用于修正实体消解未能自动对齐的别名，如"老妈"与"妈妈"
+    pub async fn merge_entities(&self, keep: &uuid::Uuid, remove: &uuid::Uuid) -> Result<()> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+        if keep == remove {
+            return Err(MemoryError::InvalidInput("不能将实体与自身合并".to_string()));
+        }
+
+        let storage = self.storage.read().await;
+        let (mut keep_node, remove_node) = match (storage.get_node(keep).await?, storage.get_node(remove).await?) {
+            (Some(k), Some(r)) if k.node_type() == NodeType::Entity && r.node_type() == NodeType::Entity => (k, r),
+            _ => return Err(MemoryError::InvalidInput(format!("实体不存在: {} / {}", keep, remove))),
+        };
+
+        // 在图的写锁内规划改动，先写入存储，成功后再更新图
+        let mut graph = self.graph.write().await;
+        let merge = graph.plan_node_merge(remove, keep).unwrap_or_default();
+        storage.delete_edges(&merge.removed).await?;
+        storage.add_edges(&merge.inserted).await?;
+        storage.update_edges(&merge.reinforced).await?;
+        graph.apply_node_merge(remove, &merge);
+        drop(graph);
+
+        // 参与的事件中把被删除的实体替换为保留的实体
+        let event_ids: Vec<uuid::Uuid> = merge.inserted.iter()
+            .chain(&merge.reinforced)
+            .filter(|e| e.source == *keep && e.relation == graph::relation_types::PARTICIPATES_IN)
+            .map(|e| e.target)
+            .collect();
+        for chunk in event_ids.chunks(500) {
+            for mut event in storage.get_nodes(chunk).await? {
+                if let NodeData::Event { participants, .. } = &mut event.data {
+                    if !participants.contains(remove) {
+                        continue;
+                    }
+                    participants.retain(|p| p != remove);
+                    if !participants.contains(keep) {
+                        participants.push(*keep);
+                    }
+                    storage.update_node(&event).await?;
+                }
+            }
+        }
+
+        if storage.is_custom_memory(remove).await? {
+            storage.mark_custom_memory(keep).await?;
+            storage.unmark_custom_memory(remove).await?;
+        }
+
+        graph::merge_nodes(&mut keep_node, &remove_node);
+        keep_node.access_count = keep_node.access_count.saturating_add(remove_node.access_count);
+        storage.update_node(&keep_node).await?;
+        storage.delete_node(remove).await?;
+        drop(storage);
+
+        // 快照的增量回放无法感知删除，立即写入新快照
+        if let Err(e) = self.persist_graph_snapshot().await {
+            log::warn!("写入图快照失败: {}", e);
+        }
+
+        log::info!(
+            "已将实体 \"{}\" 合并到 \"{}\"，改接 {} 条边",
+            remove_node.content,
+            keep_node.content,
+            merge.removed.len()
+        );
+        Ok(())
+    }
+
+    /// 添加上下位概念关系
+    ///
+    /// 建立 `child -[is_a]-> parent`（如 家人 → 人物），概念不存在时自动创建；
+    /// 会形成环的关系被拒绝
+    pub async fn add_concept_parent(&self, child: &str, parent: &str) -> Result<()> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+        let (child, parent) = (child.trim(), parent.trim());
+        if child.is_empty() || parent.is_empty() {
+            return Err(MemoryError::InvalidInput("概念名称不能为空".to_string()));
+        }
+
+        let concepts = self.ensure_concepts(&[child, parent]).await?;
+        let (child_id, parent_id) = (concepts[child].id, concepts[parent].id);
+
+        // 先写入存储，成功后再更新内存图
+        let mut graph = self.graph.write().await;
+        if graph.is_a_would_cycle(&child_id, &parent_id) {
+            return Err(MemoryError::InvalidInput(format!(
+                "\"{}\" 已是 \"{}\" 的上位概念，不能形成环", child, parent
+            )));
+        }
+        let (new_edges, reinforced_edges) = graph.plan_upsert_edges([graph::taxonomy_edge(child_id, parent_id)]);
+        {
+            let storage = self.storage.read().await;
+            storage.add_edges(&new_edges).await?;
+            storage.update_edges(&reinforced_edges).await?;
+        }
+        graph.apply_upserted_edges(&new_edges, &reinforced_edges);
+        drop(graph);
+
+        log::info!("添加概念关系: {} -[is_a]-> {}", child, parent);
         Ok(())
     }
 
+    /// 将节点归入概念
+    ///
+    /// 建立 `node -[instance_of]-> concept`（如 妈妈 → 家人），概念不存在时自动创建
+    pub async fn add_instance_of(&self, node_id: &uuid::Uuid, concept: &str) -> Result<()> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+        let concept = concept.trim();
+        if concept.is_empty() {
+            return Err(MemoryError::InvalidInput("概念名称不能为空".to_string()));
+        }
+        {
+            let storage = self.storage.read().await;
+            if storage.get_node(node_id).await?.is_none() {
+                return Err(MemoryError::InvalidInput(format!("节点不存在: {}", node_id)));
+            }
+        }
+
+        let mut concept_node = self.ensure_concepts(&[concept]).await?
+            .remove(concept)
+            .ok_or_else(|| MemoryError::Storage(format!("概念节点缺失: {}", concept)))?;
+        if concept_node.id == *node_id {
+            return Err(MemoryError::InvalidInput("不能将概念归入自身".to_string()));
+        }
+
+        // 先写入存储（边与概念实例数），成功后再更新内存图
+        let mut graph = self.graph.write().await;
+        let (new_edges, reinforced_edges) = graph.plan_upsert_edges([graph::instance_edge(*node_id, concept_node.id)]);
+        {
+            let storage = self.storage.read().await;
+            storage.add_edges(&new_edges).await?;
+            storage.update_edges(&reinforced_edges).await?;
+            if !new_edges.is_empty() {
+                graph::touch_concept(&mut concept_node, 1);
+                storage.update_node(&concept_node).await?;
+                storage.upsert_concept(&concept_node).await?;
+            }
+        }
+        graph.apply_upserted_edges(&new_edges, &reinforced_edges);
+        Ok(())
+    }
+
+    /// 查询概念层级
+    ///
+    /// # Arguments
+    /// * `name` - 概念名称
+    /// * `levels` - 向上、向下各展开的层数
+    ///
+    /// # Returns
+    /// 概念不存在时返回 None
+    pub async fn concept_hierarchy(&self, name: &str, levels: usize) -> Result<Option<ConceptHierarchy>> {
+        use std::collections::HashMap;
+
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let storage = self.storage.read().await;
+        let Some(concept) = storage.get_concept_nodes(&[name.trim().to_string()]).await?
+            .into_values()
+            .next()
+        else {
+            return Ok(None);
+        };
+
+        let (ancestors, descendants, instances) = {
+            let graph = self.graph.read().await;
+            (
+                graph.concept_ancestors(&concept.id, levels),
+                graph.concept_descendants(&concept.id, levels),
+                graph.concept_instances(&concept.id),
+            )
+        };
+
+        let ids: Vec<uuid::Uuid> = ancestors.iter()
+            .chain(&descendants)
+            .map(|(id, _)| *id)
+            .chain(instances.iter().copied())
+            .collect();
+        let mut nodes: HashMap<uuid::Uuid, PathNode> = HashMap::new();
+        for chunk in ids.chunks(500) {
+            nodes.extend(storage.get_nodes(chunk).await?.into_iter().map(|n| (n.id, PathNode {
+                id: n.id,
+                node_type: n.node_type(),
+                content: n.content,
+            })));
+        }
+        let lookup = |id: &uuid::Uuid| nodes.get(id).cloned();
+
+        Ok(Some(ConceptHierarchy {
+            ancestors: ancestors.iter().filter_map(|(id, _)| lookup(id)).collect(),
+            descendants: descendants.iter().filter_map(|(id, _)| lookup(id)).collect(),
+            instances: instances.iter().filter_map(lookup).collect(),
+            concept: PathNode {
+                id: concept.id,
+                node_type: NodeType::Concept,
+                content: concept.content,
+            },
+        }))
+    }
+
+    /// 统计记忆概况
+    ///
+    /// 汇总各类节点、实体和关系的数量，图的度分布与连通分量，
+    /// 缺少向量嵌入的节点、概念池大小、数据库磁盘占用以及事件时间跨度
+    pub async fn stats(&self) -> Result<MemoryStats> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        let mut graph_stats = {
+            let graph = self.graph.read().await;
+            graph.stats()
+        };
+
+        // 只做计数与单列投影查询，不把全部节点加载到内存
+        let storage = self.storage.read().await;
+        let mut nodes_by_type = std::collections::BTreeMap::new();
+        for node_type in [NodeType::Entity, NodeType::Event, NodeType::Concept] {
+            let count = storage.count_nodes(Some(node_type)).await?;
+            if count > 0 {
+                nodes_by_type.insert(node_type.as_str().to_string(), count);
+            }
+        }
+        let entities_by_type = storage.count_entities_by_type().await?;
+        let nodes_without_embedding = storage.count_nodes_without_embedding().await?;
+        let event_time_range = storage.event_time_range().await?;
+        let concept_pool_size = storage.count_concepts().await?;
+        let disk_size_bytes = utils::dir_size(std::path::Path::new(storage.db_path()))
+            .map_err(|e| MemoryError::Storage(format!("统计数据库大小失败: {}", e)))?;
+        drop(storage);
+
+        // 图中只有带边的节点，按存储的节点总数补齐孤立节点，
+        // 节点数、度分布与平均度数都以存储为准
+        let node_count: usize = nodes_by_type.values().sum();
+        graph_stats.include_isolated_nodes(node_count);
+
+        let (oldest_event_time, newest_event_time) = event_time_range.unzip();
+        let stats = MemoryStats {
+            node_count,
+            nodes_by_type,
+            entities_by_type,
+            nodes_without_embedding,
+            edge_count: graph_stats.edge_count,
+            average_degree: graph_stats.average_degree(),
+            edges_by_relation: graph_stats.edges_by_relation,
+            degree_distribution: graph_stats.degree_distribution,
+            component_count: graph_stats.component_count,
+            concept_pool_size,
+            disk_size_bytes,
+            oldest_event_time,
+            newest_event_time,
+        };
+
+        Ok(stats)
+    }
+
+    /// 立即写入检索时攒下的边增强（仅在 `RetrievalConfig::reinforce_edges` 开启时有内容）
+    ///
+    /// # Returns
+    /// 被增强的边数
+    pub async fn flush_reinforcement(&self) -> Result<usize> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+        self.retrieval.flush_reinforcement().await
+    }
+
+    /// 将当前图结构写入快照文件
+    ///
+    /// 宿主可在应用进入后台、退出前等时机调用，缩短下次启动时的增量回放；
+    /// 写入前会先写入尚未写入的边增强
+    pub async fn persist_graph_snapshot(&self) -> Result<()> {
+        if !self.initialized {
+            return Err(MemoryError::NotInitialized);
+        }
+
+        if let Err(e) = self.retrieval.flush_reinforcement().await {
+            log::warn!("写入边增强失败: {}", e);
+        }
+
+        let storage = self.storage.read().await;
+        let version = storage.edge_table_version().await?;
+        let graph = self.graph.read().await;
+        graph.save_snapshot(&self.snapshot_path(&storage), version)
+    }
+
+    /// 替换保存对话时使用的抽取器
+    pub fn set_extractor(&mut self, extractor: Arc<dyn extraction::Extractor>) {
+        self.extractor = extractor;
+    }
+
+    /// 替换事件重要性评分器（None 表示使用抽取器给出的重要性）
+    pub fn set_importance_scorer(&mut self, scorer: Option<Arc<dyn extraction::ImportanceScorer>>) {
+        self.importance_scorer = scorer;
+    }
+
+    /// 设置实体消解配置
+    ///
+    /// 影响后续 `save` 中新抽取实体与已有实体的对齐方式
+    pub fn set_entity_resolution_config(&mut self, config: graph::EntityResolutionConfig) {
+        self.entity_resolution = config;
+    }
+
     /// 检查系统是否已初始化
     pub fn is_initialized(&self) -> bool {
         self.initialized
@@ -241,187 +1244,409 @@ impl MemorySystem {
     }
 
     // ============================================
-    // save() 内部工具方法
+    // 图结构加载
     // ============================================
 
-    /// 将消息列表转换为事件节点
-    fn messages_to_events(&self, messages: &[Message]) -> Vec<MemoryNode> {
-        let now_time = utils::now_event_time();
-        let mut events = Vec::new();
-
-        // 将用户消息和助手回复配对形成对话事件
-        let mut i = 0;
-        while i < messages.len() {
-            let msg = &messages[i];
+    /// 图快照文件路径（与数据库放在同一目录）
+    fn snapshot_path(&self, storage: &storage::LanceDBStorage) -> std::path::PathBuf {
+        std::path::Path::new(storage.db_path()).join(graph::SNAPSHOT_FILE_NAME)
+    }
 
-            if msg.role == "user" {
-                // 查看是否有紧跟的 assistant 回复
-                let reply = messages.get(i + 1)
-                    .filter(|m| m.role == "assistant");
+    /// 加载图结构
+    ///
+    /// 优先使用快照：边表版本一致时直接使用；边表只有新增时回放新边；
+    /// 快照缺失、损坏或边表发生过删除时，从边表全量重建并重写快照
+    async fn load_graph(&self) -> Result<()> {
+        let started = std::time::Instant::now();
+        let storage = self.storage.read().await;
+        let snapshot_path = self.snapshot_path(&storage);
+        let table_version = storage.edge_table_version().await?;
+
+        let snapshot = match graph::KnowledgeGraph::load_snapshot(&snapshot_path) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::warn!("图快照不可用，将从边表重建: {}", e);
+                None
+            }
+        };
 
-                let event_content = if let Some(reply_msg) = reply {
-                    format!("用户说：{}\n回复：{}", msg.content, reply_msg.content)
+        let (loaded, needs_persist) = match snapshot {
+            Some((graph, meta)) if meta.edge_table_version == table_version => {
+                log::info!("从快照加载了 {} 条边到图结构", graph.edge_count());
+                (Some(graph), false)
+            }
+            Some((mut graph, meta)) => {
+                let edge_count = storage.count_edges().await?;
+                if edge_count < meta.edge_count {
+                    log::info!("边表自快照后发生过删除，放弃快照");
+                    (None, true)
                 } else {
-                    format!("用户说：{}", msg.content)
-                };
-
-                let event_time = msg.timestamp
-                    .map(|ts| {
-                        chrono::DateTime::from_timestamp(ts, 0)
-                            .map(|dt| dt.format("%Y-%m-%d-%H-%M").to_string())
-                            .unwrap_or_else(|| now_time.clone())
-                    })
-                    .unwrap_or_else(|| now_time.clone());
-
-                let mut event = MemoryNode::new_event(event_content, event_time);
-                // 事件重要性：根据消息长度简单判断
-                let msg_len = msg.content.len();
-                event.importance = if msg_len > 200 { 0.8 }
-                    else if msg_len > 50 { 0.6 }
-                    else { 0.4 };
-
-                events.push(event);
+                    let new_edges = storage.get_edges_since(meta.max_edge_seen_at).await?;
+                    let mut replayed = 0usize;
+                    for edge in new_edges {
+                        // 已在快照中的边可能被增强过，刷新其权重
+                        if !graph.refresh_edge(&edge) {
+                            graph.add_edge(edge);
+                        }
+                        replayed += 1;
+                    }
 
-                if reply.is_some() {
-                    i += 2;
-                } else {
-                    i += 1;
+                    if graph.edge_count() == edge_count {
+                        log::info!(
+                            "从快照加载了 {} 条边，增量回放 {} 条新增或更新的边",
+                            meta.edge_count, replayed
+                        );
+                        (Some(graph), true)
+                    } else {
+                        log::info!(
+                            "快照回放后边数不一致（{} / {}），放弃快照",
+                            graph.edge_count(), edge_count
+                        );
+                        (None, true)
+                    }
                 }
+            }
+            None => (None, true),
+        };
+
+        let (mut loaded, rebuilt) = match loaded {
+            Some(graph) => (graph, false),
+            None => {
+                let mut graph = graph::KnowledgeGraph::new();
+                let edges = storage.get_all_edges().await?;
+                log::info!("从存储加载了 {} 条边到图结构", edges.len());
+                graph.add_edges(edges);
+                (graph, true)
+            }
+        };
+
+        // 补全节点类型（全量重建时扫描节点表，否则只查询类型未知的节点）
+        let untyped = loaded.untyped_node_ids();
+        let needs_persist = if untyped.is_empty() {
+            needs_persist
+        } else {
+            let types = if rebuilt {
+                storage.get_node_types(None).await?
             } else {
-                // 单独的 assistant/system 消息，跳过或作为独立事件
-                i += 1;
+                storage.get_node_types(Some(&untyped)).await?
+            };
+            for (id, node_type) in types {
+                if loaded.contains_node(&id) {
+                    loaded.set_node_type(id, node_type);
+                }
+            }
+            true
+        };
+
+        if needs_persist {
+            if let Err(e) = loaded.save_snapshot(&snapshot_path, table_version) {
+                log::warn!("写入图快照失败: {}", e);
             }
         }
 
-        events
+        *self.graph.write().await = loaded;
+        log::info!("图结构加载耗时 {} ms", started.elapsed().as_millis());
+        Ok(())
     }
 
-    /// 从消息中提取实体（基于关键词的简单提取）
-    fn extract_entities(
-        &self,
-        messages: &[Message],
-        event_nodes: &[MemoryNode],
-    ) -> (Vec<MemoryNode>, std::collections::HashMap<uuid::Uuid, Vec<uuid::Uuid>>) {
-        use std::collections::{HashMap, HashSet};
+    // ============================================
+    // save() 内部工具方法
+    // ============================================
 
-        let mut entity_map: HashMap<String, MemoryNode> = HashMap::new();
-        let mut entity_to_events: HashMap<uuid::Uuid, Vec<uuid::Uuid>> = HashMap::new();
-        let mut seen_entities: HashSet<String> = HashSet::new();
+    /// 为新事件建立时序边
+    ///
+    /// 本次保存的事件按事件时间串联（时间相同时保持对话顺序）；存储中若有时间窗口内
+    /// 更早的事件，把其中最晚的一个与本次最早的事件相连
+    ///
+    /// # Arguments
+    /// * `events` - 按对话顺序排列的 (事件 ID, 事件时间)
+    async fn link_temporal_events(&self, events: &[(uuid::Uuid, String)]) -> Result<Vec<Edge>> {
+        let mut events = events.to_vec();
+        graph::sort_by_event_time(&mut events);
+        let ids: Vec<uuid::Uuid> = events.iter().map(|(id, _)| *id).collect();
+        let mut edges = graph::link_event_sequence(&ids);
+
+        let Some((first_id, first_time)) = events.first() else {
+            return Ok(edges);
+        };
+        let Some(start) = utils::parse_event_time(first_time) else {
+            return Ok(edges);
+        };
+        let window_start = (start - chrono::Duration::minutes(graph::TEMPORAL_LINK_WINDOW_MINUTES))
+            .format("%Y-%m-%d-%H-%M")
+            .to_string();
+
+        let candidates = {
+            let storage = self.storage.read().await;
+            storage.get_events_between(&window_start, first_time).await?
+        };
+        let previous = candidates.into_iter()
+            .filter(|n| !ids.contains(&n.id))
+            .filter(|n| n.event_time().is_some_and(|t| {
+                graph::within_time_window(t, first_time, graph::TEMPORAL_LINK_WINDOW_MINUTES)
+            }))
+            .max_by(|a, b| {
+                a.event_time().cmp(&b.event_time()).then(a.created_at.cmp(&b.created_at))
+            });
+        if let Some(previous) = previous {
+            log::debug!("与上一段对话的事件 {} 建立时序关系", previous.id);
+            edges.extend(graph::temporal_pair(previous.id, *first_id));
+        }
 
-        for (msg_idx, msg) in messages.iter().enumerate() {
-            if msg.role != "user" {
-                continue;
-            }
+        Ok(edges)
+    }
 
-            let content = &msg.content;
+    /// 实体消解
+    ///
+    /// 新抽取的实体依次与已有实体、本批次中先出现的实体对齐；
+    /// 命中时丢弃新节点，把其信息合并进已有节点。已有实体不做全量加载，
+    /// 只按名称（含别名与规范化写法）查询，并按向量检索限量取出候选
+    ///
+    /// # Returns
+    /// (需要新写入的节点, 被复用并需更新的已有实体, 新实体 ID → 复用的实体 ID)
+    async fn resolve_entities(
+        &self,
+        nodes: Vec<MemoryNode>,
+    ) -> Result<(
+        Vec<MemoryNode>,
+        Vec<MemoryNode>,
+        std::collections::HashMap<uuid::Uuid, uuid::Uuid>,
+    )> {
+        use std::collections::HashMap;
 
-            // 简单的实体抽取：按中文标点和空格分词
-            let segments = Self::simple_segment(content);
+        let mut resolved_ids: HashMap<uuid::Uuid, uuid::Uuid> = HashMap::new();
+        if !self.entity_resolution.enabled
+            || !nodes.iter().any(|n| n.node_type() == NodeType::Entity)
+        {
+            return Ok((nodes, Vec::new(), resolved_ids));
+        }
 
-            for segment in &segments {
-                let trimmed = segment.trim();
-                if trimmed.len() < 2 || trimmed.len() > 20 {
-                    continue;
+        // 只从存储中取出可能对齐的候选实体：名称或别名命中的，以及向量最相近的若干个
+        let mut existing: HashMap<uuid::Uuid, MemoryNode> = HashMap::new();
+        {
+            let entities: Vec<&MemoryNode> = nodes.iter()
+                .filter(|n| n.node_type() == NodeType::Entity)
+                .collect();
+            let mut names: Vec<String> = Vec::new();
+            for node in &entities {
+                for name in graph::name_variants(&node.content) {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
                 }
+            }
 
-                let entity_type = graph::infer_entity_type(trimmed);
-                if entity_type == EntityType::Other {
-                    continue; // 只提取可识别类型的实体
+            let storage = self.storage.read().await;
+            for node in storage.find_entities_by_names(&names).await? {
+                existing.entry(node.id).or_insert(node);
+            }
+            if self.entity_resolution.similarity_threshold <= 1.0 && self.entity_resolution.vector_candidates > 0 {
+                for node in entities.iter().filter(|n| !n.embedding.is_empty()) {
+                    let hits = storage.vector_search(
+                        &node.embedding,
+                        self.entity_resolution.vector_candidates,
+                        Some(storage::VectorSearchFilter { node_type: Some(NodeType::Entity) }),
+                    ).await?;
+                    for (hit, _) in hits {
+                        existing.entry(hit.id).or_insert(hit);
+                    }
                 }
+            }
+        }
+        let mut resolver = graph::EntityResolver::with_entities(
+            self.entity_resolution.clone(),
+            existing.values(),
+        );
 
-                let key = trimmed.to_string();
-                if !seen_entities.contains(&key) {
-                    seen_entities.insert(key.clone());
-                    let node = MemoryNode::new_entity(key.clone(), entity_type);
-                    entity_map.insert(key, node);
-                }
+        let mut kept: Vec<MemoryNode> = Vec::with_capacity(nodes.len());
+        let mut reused: HashMap<uuid::Uuid, MemoryNode> = HashMap::new();
+        for node in nodes {
+            let Some(matched) = resolver.resolve(&node) else {
+                resolver.insert(&node);
+                kept.push(node);
+                continue;
+            };
 
-                // 关联到对应的事件节点
-                if let Some(entity) = entity_map.get(segment.trim()) {
-                    // 对应的事件索引（每个 user 消息可能映射到一个事件）
-                    let event_idx = messages.iter().take(msg_idx + 1)
-                        .filter(|m| m.role == "user")
-                        .count()
-                        .saturating_sub(1);
-
-                    if let Some(event_node) = event_nodes.get(event_idx) {
-                        entity_to_events
-                            .entry(entity.id)
-                            .or_default()
-                            .push(event_node.id);
-                    }
-                }
+            log::debug!("实体 \"{}\" 以 {:?} 方式对齐到已有节点 {}", node.content, matched.kind, matched.id);
+            resolved_ids.insert(node.id, matched.id);
+            if let Some(mut target) = existing.remove(&matched.id) {
+                graph::merge_nodes(&mut target, &node);
+                reused.insert(target.id, target);
+            } else if let Some(target) = reused.get_mut(&matched.id) {
+                graph::merge_nodes(target, &node);
             }
+            // 对齐到本批次中的新实体时无需额外处理，边会改接到该实体上
         }
 
-        let entities: Vec<MemoryNode> = entity_map.into_values().collect();
-        (entities, entity_to_events)
+        Ok((kept, reused.into_values().collect(), resolved_ids))
     }
 
-    /// 将实体节点归类为概念并创建概念化边
-    fn conceptualize_entities(
+    /// 概念复用
+    ///
+    /// 概念节点按名称唯一：已存在的概念直接复用，本批次中的同名概念合并为一个，
+    /// 只为新名称保留节点（实例数在写入前按新建立的概念化边累加）
+    ///
+    /// # Returns
+    /// (新建的概念节点, 复用的已有概念节点, 被替换的概念 ID → 保留的概念 ID)
+    async fn reuse_concepts(
         &self,
-        entities: &[MemoryNode],
-    ) -> (Vec<MemoryNode>, Vec<Edge>) {
+        concepts: Vec<MemoryNode>,
+    ) -> Result<(
+        Vec<MemoryNode>,
+        Vec<MemoryNode>,
+        std::collections::HashMap<uuid::Uuid, uuid::Uuid>,
+    )> {
         use std::collections::HashMap;
 
-        let mut concept_map: HashMap<&str, MemoryNode> = HashMap::new();
-        let mut edges = Vec::new();
+        let mut remapped: HashMap<uuid::Uuid, uuid::Uuid> = HashMap::new();
+        if concepts.is_empty() {
+            return Ok((vec![], vec![], remapped));
+        }
 
-        for entity in entities {
-            let concept_name = match entity.entity_type() {
-                Some(EntityType::Person) => "人物",
-                Some(EntityType::Place) => "地点",
-                Some(EntityType::Time) => "时间",
-                Some(EntityType::Object) => "物品",
-                _ => continue,
-            };
+        let mut names: Vec<String> = concepts.iter().map(|n| n.content.clone()).collect();
+        names.sort();
+        names.dedup();
+        let mut existing = {
+            let storage = self.storage.read().await;
+            storage.get_concept_nodes(&names).await?
+        };
+
+        let mut created: HashMap<String, MemoryNode> = HashMap::new();
+        let mut reused: HashMap<String, MemoryNode> = HashMap::new();
+        for concept in concepts {
+            if let Some(kept) = reused.get(&concept.content).or_else(|| created.get(&concept.content)) {
+                remapped.insert(concept.id, kept.id);
+            } else if let Some(node) = existing.remove(&concept.content) {
+                remapped.insert(concept.id, node.id);
+                reused.insert(node.content.clone(), node);
+            } else {
+                created.insert(concept.content.clone(), concept);
+            }
+        }
+
+        Ok((
+            created.into_values().collect(),
+            reused.into_values().collect(),
+            remapped,
+        ))
+    }
+
+    /// 按名称取概念节点，不存在的新建（生成嵌入后写入存储、概念池与图）
+    async fn ensure_concepts(
+        &self,
+        names: &[&str],
+    ) -> Result<std::collections::HashMap<String, MemoryNode>> {
+        let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        let mut concepts = {
+            let storage = self.storage.read().await;
+            storage.get_concept_nodes(&names).await?
+        };
 
-            let concept_node = concept_map
-                .entry(concept_name)
-                .or_insert_with(|| MemoryNode::new_concept(concept_name.to_string()));
+        let mut created: Vec<MemoryNode> = Vec::new();
+        for name in &names {
+            if !concepts.contains_key(name) && !created.iter().any(|n| &n.content == name) {
+                created.push(graph::NodeBuilder::concept(name.as_str()).instance_count(0).build());
+            }
+        }
+        if created.is_empty() {
+            return Ok(concepts);
+        }
 
-            // 创建 entity → concept 边
-            edges.push(Edge::new(
-                entity.id,
-                concept_node.id,
-                graph::relation_types::CONCEPTUALIZED_AS.to_string(),
-            ));
+        let texts: Vec<String> = created.iter().map(|n| n.content.clone()).collect();
+        match self.embedding_client.embed_batch(&texts).await {
+            Ok(embeddings) => {
+                for (node, emb) in created.iter_mut().zip(embeddings) {
+                    node.embedding = emb;
+                }
+            }
+            Err(e) => log::error!("概念嵌入向量生成失败: {}，节点将不带向量存储", e),
+        }
+
+        {
+            let storage = self.storage.read().await;
+            storage.add_nodes(&created).await?;
+            storage.upsert_concepts(&created).await?;
+        }
+        {
+            let mut graph = self.graph.write().await;
+            for node in &created {
+                graph.add_typed_node(node.id, NodeType::Concept);
+            }
         }
 
-        let concepts: Vec<MemoryNode> = concept_map.into_values().collect();
-        (concepts, edges)
+        concepts.extend(created.into_iter().map(|n| (n.content.clone(), n)));
+        Ok(concepts)
     }
 
-    /// 简单中文分词（按标点和空格切分）
-    fn simple_segment(text: &str) -> Vec<String> {
-        let delimiters = [
-            '，', '。', '！', '？', '、', '；', '：', '"', '"',
-            '（', '）', '《', '》', '\n', '\r', '\t', ' ',
-            ',', '.', '!', '?', ':', ';', '"', '\'', '(', ')',
-        ];
+    /// 合并同名概念节点并关联概念池
+    ///
+    /// 旧版本每次保存都会新建概念节点：同名节点合并到最早创建的那个并改接其边，
+    /// 实例数按改接后指向它的概念化边重新计算，概念池记录补上节点 ID。
+    /// 没有需要处理的数据时不做任何写入
+    async fn consolidate_concepts(&self) -> Result<()> {
+        use std::collections::HashMap;
 
-        let mut result = Vec::new();
-        let mut current = String::new();
+        let storage = self.storage.read().await;
+        let mut by_name: HashMap<String, Vec<MemoryNode>> = HashMap::new();
+        for node in storage.get_nodes_by_type(NodeType::Concept).await? {
+            by_name.entry(node.content.clone()).or_default().push(node);
+        }
+        let any_duplicates = by_name.values().any(|nodes| nodes.len() > 1);
+        let has_unlinked = storage.get_all_concepts().await?
+            .iter()
+            .any(|entry| entry.node_id.is_none() && by_name.contains_key(&entry.name));
+        if !any_duplicates && !has_unlinked {
+            return Ok(());
+        }
 
-        for ch in text.chars() {
-            if delimiters.contains(&ch) {
-                let trimmed = current.trim().to_string();
-                if !trimmed.is_empty() {
-                    result.push(trimmed);
+        let mut merged = 0usize;
+        let mut canonical: Vec<MemoryNode> = Vec::with_capacity(by_name.len());
+        for mut nodes in by_name.into_values() {
+            nodes.sort_by_key(|n| n.created_at);
+            let mut keep = nodes.remove(0);
+            let has_duplicates = !nodes.is_empty();
+
+            for duplicate in nodes {
+                // 在图的写锁内规划改动，先写入存储，成功后再更新图
+                let mut graph = self.graph.write().await;
+                let edges = graph.plan_node_merge(&duplicate.id, &keep.id).unwrap_or_default();
+                storage.delete_edges(&edges.removed).await?;
+                storage.add_edges(&edges.inserted).await?;
+                storage.update_edges(&edges.reinforced).await?;
+                storage.delete_node(&duplicate.id).await?;
+                graph.apply_node_merge(&duplicate.id, &edges);
+                drop(graph);
+
+                if let (
+                    NodeData::Concept { last_used_at, .. },
+                    NodeData::Concept { last_used_at: dup_used, .. },
+                ) = (&mut keep.data, &duplicate.data) {
+                    *last_used_at = (*last_used_at).max(*dup_used);
                 }
-                current.clear();
-            } else {
-                current.push(ch);
+                merged += 1;
+            }
+            if has_duplicates {
+                // 重复节点可能归纳了相同的实例，实例数按改接后的概念化边重新计算
+                let instances = self.graph.read().await.concept_instance_count(&keep.id);
+                if let NodeData::Concept { instance_count, .. } = &mut keep.data {
+                    *instance_count = u32::try_from(instances).unwrap_or(u32::MAX);
+                }
+                storage.update_node(&keep).await?;
             }
+            canonical.push(keep);
         }
+        storage.upsert_concepts(&canonical).await?;
+        drop(storage);
 
-        let trimmed = current.trim().to_string();
-        if !trimmed.is_empty() {
-            result.push(trimmed);
+        if merged > 0 {
+            // 快照的增量回放无法感知删除，立即写入新快照
+            if let Err(e) = self.persist_graph_snapshot().await {
+                log::warn!("写入图快照失败: {}", e);
+            }
         }
 
-        result
+        log::info!("概念整理完成: 合并了 {} 个重复概念节点，关联 {} 条概念池记录", merged, canonical.len());
+        Ok(())
     }
 }
 
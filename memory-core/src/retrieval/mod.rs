@@ -2,27 +2,126 @@
 //!
 //! 基于 HippoRAG 检索方法实现：
 //! - 向量相似度检索
-//! - 子图扩展（多跳邻居）
+//! - 子图扩展（多跳邻居 / Personalized PageRank）
 //! - 概念桥接（通过概念节点连接不同子图）
+//! - 时序上下文（命中事件的前后事件）
 //! - 重排序
+//! - 召回来源（种子节点、路径与阶段）
 
 mod vector;
 mod subgraph;
 mod rerank;
+mod provenance;
 
 pub use vector::*;
 pub use subgraph::*;
 pub use rerank::*;
+pub use provenance::*;
 
 use crate::error::Result;
 use crate::types::*;
 use crate::utils;
 use crate::storage::LanceDBStorage;
-use crate::graph::KnowledgeGraph;
+use crate::graph::{self, CentralityConfig, CentralityScores, CommunityConfig, KnowledgeGraph, PageRankConfig};
 use crate::embedding::EmbeddingClient;
 use std::sync::Arc;
-use std::collections::HashSet;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+/// 全图计算结果的缓存
+struct GraphCache<T> {
+    /// 计算时的图修改计数
+    generation: u64,
+    /// 计算时间
+    computed_at: Instant,
+    value: Arc<T>,
+}
+
+impl<T> GraphCache<T> {
+    fn new(generation: u64, value: T) -> Self {
+        Self { generation, computed_at: Instant::now(), value: Arc::new(value) }
+    }
+
+    /// 图未修改，或距上次计算不足 `max_age` 时仍然可用
+    fn is_fresh(&self, generation: u64, max_age: Duration) -> bool {
+        self.generation == generation || self.computed_at.elapsed() < max_age
+    }
+}
+
+/// 攒够这么多条待增强的边时写入存储
+const REINFORCE_BATCH_EDGES: usize = 64;
+
+/// 距上次写入超过该时间时，下一次检索会写入待增强的边
+const REINFORCE_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 等待写入的边增强
+struct PendingReinforcement {
+    /// 边 ID → 被检索路径经过的次数
+    traversals: HashMap<uuid::Uuid, u32>,
+    /// 上次写入时间
+    last_flush: Instant,
+}
+
+impl PendingReinforcement {
+    fn new() -> Self {
+        Self { traversals: HashMap::new(), last_flush: Instant::now() }
+    }
+
+    /// 取出全部待写入的增强
+    fn take(&mut self) -> HashMap<uuid::Uuid, u32> {
+        self.last_flush = Instant::now();
+        std::mem::take(&mut self.traversals)
+    }
+}
+
+/// 写入攒批的边增强
+///
+/// 持有图写锁直到存储写入完成，写入失败时内存图保持不变
+///
+/// # Returns
+/// 被增强的边数
+async fn write_reinforcement(
+    graph: &RwLock<KnowledgeGraph>,
+    storage: &RwLock<LanceDBStorage>,
+    traversals: &HashMap<uuid::Uuid, u32>,
+    decay_rate: f64,
+) -> Result<usize> {
+    if traversals.is_empty() {
+        return Ok(0);
+    }
+    let mut graph = graph.write().await;
+    let reinforced = graph.plan_edge_reinforcement(traversals, decay_rate);
+    if reinforced.is_empty() {
+        return Ok(0);
+    }
+    storage.read().await.update_edges(&reinforced).await?;
+    for edge in &reinforced {
+        graph.refresh_edge(edge);
+    }
+    Ok(reinforced.len())
+}
+
+/// 计算全图中心性
+///
+/// 只在读锁内克隆图快照，介数中心性等耗时计算在阻塞线程池中进行，不阻塞图的读写
+async fn compute_centrality_snapshot(
+    graph: &RwLock<KnowledgeGraph>,
+    config: CentralityConfig,
+) -> GraphCache<HashMap<uuid::Uuid, CentralityScores>> {
+    let snapshot = graph.read().await.clone();
+    let generation = snapshot.generation();
+    let started = Instant::now();
+    let scores = tokio::task::spawn_blocking(move || snapshot.compute_centrality(&config))
+        .await
+        .unwrap_or_else(|e| {
+            log::error!("节点中心性计算失败: {}", e);
+            HashMap::new()
+        });
+    log::debug!("节点中心性已更新，耗时 {} ms", started.elapsed().as_millis());
+    GraphCache::new(generation, scores)
+}
 
 /// 检索服务
 ///
@@ -34,6 +133,14 @@ pub struct RetrievalService {
     config: RetrievalConfig,
     /// 使用 RwLock 允许通过 &self 修改（解决可变引用问题）
     custom_memory_ids: RwLock<HashSet<uuid::Uuid>>,
+    /// 社区划分缓存（节点 → 社区编号）
+    community_cache: RwLock<Option<GraphCache<HashMap<uuid::Uuid, uuid::Uuid>>>>,
+    /// 节点中心性缓存（由后台任务刷新）
+    centrality_cache: Arc<RwLock<Option<GraphCache<HashMap<uuid::Uuid, CentralityScores>>>>>,
+    /// 是否有正在运行的中心性刷新任务
+    centrality_refreshing: Arc<AtomicBool>,
+    /// 等待写入的边增强
+    pending_reinforcement: Mutex<PendingReinforcement>,
 }
 
 impl RetrievalService {
@@ -49,6 +156,10 @@ impl RetrievalService {
             embedding_client,
             config: RetrievalConfig::default(),
             custom_memory_ids: RwLock::new(HashSet::new()),
+            community_cache: RwLock::new(None),
+            centrality_cache: Arc::new(RwLock::new(None)),
+            centrality_refreshing: Arc::new(AtomicBool::new(false)),
+            pending_reinforcement: Mutex::new(PendingReinforcement::new()),
         }
     }
 
@@ -65,6 +176,10 @@ impl RetrievalService {
             embedding_client,
             config,
             custom_memory_ids: RwLock::new(HashSet::new()),
+            community_cache: RwLock::new(None),
+            centrality_cache: Arc::new(RwLock::new(None)),
+            centrality_refreshing: Arc::new(AtomicBool::new(false)),
+            pending_reinforcement: Mutex::new(PendingReinforcement::new()),
         }
     }
 
@@ -74,9 +189,10 @@ impl RetrievalService {
     /// 1. 向量化查询
     /// 2. 初始向量检索
     /// 3. 子图扩展
-    /// 4. 概念桥接
+    /// 4. 概念桥接、时序上下文
     /// 5. 计算权重并排序
-    /// 6. 格式化输出
+    /// 6. 增强扩展路径上通向返回结果的边
+    /// 7. 格式化输出（include_raw 时附带每条记忆的召回来源）
     pub async fn retrieve(&self, params: &QueryParams) -> Result<QueryResult> {
         log::info!("开始检索: \"{}...\"",
             &params.user_message.chars().take(50).collect::<String>());
@@ -105,39 +221,119 @@ impl RetrievalService {
             });
         }
 
+        let seed_weights: HashMap<uuid::Uuid, f64> = initial_results.iter()
+            .map(|(node, similarity)| (node.id, *similarity as f64))
+            .collect();
         let initial_nodes: Vec<MemoryNode> = initial_results.into_iter()
             .map(|(node, _)| node)
             .collect();
-
-        // Step 3: 子图扩展
-        let subgraph_nodes = self.expand_subgraph(
-            &initial_nodes,
-            self.config.hop_depth,
-            self.config.max_subgraph_nodes,
-        ).await?;
+        let mut tracker = ProvenanceTracker::new();
+        tracker.record(RetrievalStage::VectorMatch, &initial_nodes);
+
+        // Step 3: 子图扩展（PageRank 扩展不经过具体的边，不记录路径）
+        let mut trail = ExpansionTrail::new();
+        let subgraph_nodes = match self.config.expansion_strategy {
+            ExpansionStrategy::HopExpansion => {
+                let (hop_nodes, hop_trail) = self.expand_subgraph(
+                    &initial_nodes,
+                    self.config.hop_depth,
+                    self.config.max_subgraph_nodes,
+                ).await?;
+                trail = hop_trail;
+                hop_nodes
+            }
+            ExpansionStrategy::PersonalizedPageRank => {
+                self.expand_by_pagerank(
+                    &initial_nodes,
+                    &seed_weights,
+                    self.config.max_subgraph_nodes,
+                ).await?
+            }
+            ExpansionStrategy::Hybrid => {
+                let (hop_nodes, hop_trail) = self.expand_subgraph(
+                    &initial_nodes,
+                    self.config.hop_depth,
+                    self.config.max_subgraph_nodes,
+                ).await?;
+                trail = hop_trail;
+                let ppr_nodes = self.expand_by_pagerank(
+                    &initial_nodes,
+                    &seed_weights,
+                    self.config.max_subgraph_nodes,
+                ).await?;
+                self.merge_and_dedupe(hop_nodes, ppr_nodes)
+            }
+        };
+        tracker.record(RetrievalStage::SubgraphExpansion, &subgraph_nodes);
         log::debug!("子图扩展至 {} 个节点", subgraph_nodes.len());
 
         // Step 4: 概念桥接
         let bridged_nodes = self.find_concept_bridged_nodes(&subgraph_nodes).await?;
+        tracker.record(RetrievalStage::ConceptBridge, &bridged_nodes);
         log::debug!("概念桥接发现 {} 个额外节点", bridged_nodes.len());
 
         // Step 5: 合并所有候选节点
         let all_candidates = self.merge_and_dedupe(subgraph_nodes, bridged_nodes);
+
+        // 命中事件的前后事件
+        let (temporal_nodes, temporal_boosts) = self.temporal_context(
+            &initial_nodes,
+            &seed_weights,
+            &all_candidates,
+        ).await?;
+        tracker.record(RetrievalStage::TemporalContext, &temporal_nodes);
+        let all_candidates = self.merge_and_dedupe(all_candidates, temporal_nodes);
         log::debug!("总候选数: {}", all_candidates.len());
 
-        // Step 6: 计算权重并排序
-        let scored_memories = self.score_and_rank(
+        // Step 6: 计算权重并排序（同社区、中心性高、与命中事件相邻的候选获得加分）
+        let mut graph_boosts = self.community_boosts(&seed_weights, &all_candidates).await;
+        for (id, boost) in temporal_boosts {
+            *graph_boosts.entry(id).or_insert(0.0) += boost;
+        }
+        for (id, boost) in self.centrality_boosts(&all_candidates).await {
+            *graph_boosts.entry(id).or_insert(0.0) += boost;
+        }
+        let ranked = self.score_and_rank(
             all_candidates,
             &query_embedding,
+            &graph_boosts,
             self.config.rerank_top_n,
         ).await;
 
+        // 增强扩展路径上通向返回结果的边（攒批后在后台写入）
+        if self.config.reinforce_edges {
+            self.queue_reinforcement(&ranked, &trail).await;
+        }
+
+        let include_raw = params.include_raw.unwrap_or(false);
+        let mut provenances = if include_raw {
+            match self.explain_provenance(&ranked, &tracker, &seed_weights, &trail).await {
+                Ok(provenances) => provenances,
+                Err(e) => {
+                    log::warn!("生成召回来源失败: {}", e);
+                    HashMap::new()
+                }
+            }
+        } else {
+            HashMap::new()
+        };
+
+        let scored_memories: Vec<RetrievedMemory> = ranked.into_iter()
+            .map(|(node, score)| {
+                let provenance = provenances.remove(&node.id);
+                RetrievedMemory {
+                    provenance,
+                    ..Self::node_to_retrieved_memory(node, score)
+                }
+            })
+            .collect();
+
         log::info!("检索完成，返回 {} 条记忆", scored_memories.len());
 
         // 格式化输出
         let formatted_context = self.format_memories(&scored_memories);
         let count = scored_memories.len();
-        let raw = if params.include_raw.unwrap_or(false) {
+        let raw = if include_raw {
             Some(scored_memories)
         } else {
             None
@@ -163,14 +359,18 @@ impl RetrievalService {
     /// 子图扩展（获取多跳邻居）
     /// 
     /// 优化：减少锁持有时间，使用批量查询
+    ///
+    /// # Returns
+    /// (子图节点, 每个扩展节点的来源节点与经过的边)
     async fn expand_subgraph(
         &self,
         seed_nodes: &[MemoryNode],
         hop_depth: usize,
         max_nodes: usize,
-    ) -> Result<Vec<MemoryNode>> {
+    ) -> Result<(Vec<MemoryNode>, ExpansionTrail)> {
         let mut visited_ids = HashSet::with_capacity(max_nodes);
         let mut all_nodes = Vec::with_capacity(max_nodes);
+        let mut trail = ExpansionTrail::new();
 
         let mut current_layer_ids: Vec<uuid::Uuid> = seed_nodes.iter()
             .map(|n| n.id)
@@ -199,8 +399,11 @@ impl RetrievalService {
                         break;
                     }
                     
-                    for neighbor_id in graph.get_neighbors(node_id, 1) {
+                    // 连接越强的邻居越先加入，节点数受限时保留强关联
+                    let neighbors = graph.ranked_neighbor_edges(node_id, &self.config.traversal, self.config.edge_decay_rate);
+                    for (neighbor_id, edge, _) in neighbors {
                         if visited_ids.insert(neighbor_id) {
+                            trail.insert(neighbor_id, (*node_id, edge.clone()));
                             new_neighbors.push(neighbor_id);
                             if all_nodes.len() + new_neighbors.len() >= max_nodes {
                                 break;
@@ -219,27 +422,75 @@ impl RetrievalService {
             // 批量查询节点（释放graph锁后再获取storage锁）
             {
                 let storage = self.storage.read().await;
-                // 限制批量查询数量
-                let batch: Vec<uuid::Uuid> = neighbor_ids_to_query
-                    .into_iter()
-                    .take(max_nodes - all_nodes.len())
-                    .collect();
-                
-                let nodes = storage.get_nodes(&batch).await?;
+                let nodes = storage.get_nodes(&neighbor_ids_to_query).await?;
                 all_nodes.extend(nodes);
             }
 
             // 准备下一层
-            current_layer_ids = visited_ids.iter()
-                .skip(all_nodes.len() - current_layer_ids.len())
-                .copied()
-                .collect();
+            current_layer_ids = neighbor_ids_to_query;
+        }
+
+        Ok((all_nodes, trail))
+    }
+
+    /// 基于 Personalized PageRank 的子图扩展
+    ///
+    /// 以向量命中节点为种子（按相似度加权）在图上做随机游走，
+    /// 取得分最高的节点作为候选，能召回跳数扩展遗漏的多跳关联
+    async fn expand_by_pagerank(
+        &self,
+        seed_nodes: &[MemoryNode],
+        seed_weights: &HashMap<uuid::Uuid, f64>,
+        max_nodes: usize,
+    ) -> Result<Vec<MemoryNode>> {
+        let ranked_ids: Vec<uuid::Uuid> = {
+            let graph = self.graph.read().await;
+            let config = PageRankConfig {
+                damping: self.config.ppr_damping,
+                max_iterations: self.config.ppr_max_iterations,
+                ..PageRankConfig::default()
+            };
+            graph.personalized_pagerank(seed_weights, &config)
+                .into_iter()
+                .take(max_nodes)
+                .map(|(id, _)| id)
+                .collect()
+        };
+
+        let seed_ids: HashSet<uuid::Uuid> = seed_nodes.iter().map(|n| n.id).collect();
+        let to_fetch: Vec<uuid::Uuid> = ranked_ids.iter()
+            .filter(|id| !seed_ids.contains(id))
+            .copied()
+            .collect();
+
+        let mut fetched: HashMap<uuid::Uuid, MemoryNode> = if to_fetch.is_empty() {
+            HashMap::new()
+        } else {
+            let storage = self.storage.read().await;
+            storage.get_nodes(&to_fetch).await?
+                .into_iter()
+                .map(|n| (n.id, n))
+                .collect()
+        };
+
+        // 种子节点始终保留，其余按 PageRank 得分顺序排列
+        let mut all_nodes: Vec<MemoryNode> = seed_nodes.to_vec();
+        for id in ranked_ids {
+            if all_nodes.len() >= max_nodes.max(seed_nodes.len()) {
+                break;
+            }
+            if let Some(node) = fetched.remove(&id) {
+                all_nodes.push(node);
+            }
         }
 
         Ok(all_nodes)
     }
 
     /// 通过概念节点发现相关的其他节点
+    ///
+    /// 按配置沿概念层级向上泛化、向下细化后再取概念的邻居，
+    /// 只保留与概念相连的边权重最高的 `max_bridged_nodes` 个
     async fn find_concept_bridged_nodes(
         &self,
         existing_nodes: &[MemoryNode],
@@ -248,7 +499,6 @@ impl RetrievalService {
         let graph = self.graph.read().await;
 
         let mut bridged_nodes = Vec::new();
-        let existing_ids: HashSet<_> = existing_nodes.iter().map(|n| n.id).collect();
 
         let concept_node_ids: HashSet<_> = existing_nodes.iter()
             .filter(|n| n.node_type() == NodeType::Concept)
@@ -260,17 +510,129 @@ impl RetrievalService {
         }
 
         let source_ids: Vec<_> = existing_nodes.iter().map(|n| n.id).collect();
-        let bridged_ids = graph.find_concept_bridged(&source_ids, &concept_node_ids);
+        // 源节点即全部已有节点，返回结果中不含已有节点，可以直接按上限截断
+        let bridged_ids = graph.find_concept_bridged_with_levels(
+            &source_ids,
+            &concept_node_ids,
+            self.config.concept_generalize_levels,
+            self.config.concept_specialize_levels,
+            Some(self.config.max_bridged_nodes),
+        );
+
+        for chunk in bridged_ids.chunks(500) {
+            bridged_nodes.extend(storage.get_nodes(chunk).await?);
+        }
+
+        Ok(bridged_nodes)
+    }
+
+    /// 取回向量命中事件的前后事件
+    ///
+    /// 沿 `before` / `after` 边向前、向后各走 `temporal_context_steps` 步，
+    /// 加分按命中相似度与距离衰减
+    ///
+    /// # Returns
+    /// (尚不在候选中的相邻事件节点, 相邻事件的加分)
+    async fn temporal_context(
+        &self,
+        seed_nodes: &[MemoryNode],
+        seed_weights: &HashMap<uuid::Uuid, f64>,
+        existing_nodes: &[MemoryNode],
+    ) -> Result<(Vec<MemoryNode>, HashMap<uuid::Uuid, f32>)> {
+        let steps = self.config.temporal_context_steps;
+        if steps == 0 {
+            return Ok((Vec::new(), HashMap::new()));
+        }
 
-        for bridged_id in bridged_ids {
-            if !existing_ids.contains(&bridged_id) {
-                if let Some(node) = storage.get_node(&bridged_id).await? {
-                    bridged_nodes.push(node);
+        let mut boosts: HashMap<uuid::Uuid, f32> = HashMap::new();
+        {
+            let graph = self.graph.read().await;
+            for seed in seed_nodes.iter().filter(|n| n.node_type() == NodeType::Event) {
+                let similarity = seed_weights.get(&seed.id).copied().unwrap_or(0.0) as f32;
+                let (before, after) = graph.adjacent_events(&seed.id, steps);
+                for sequence in [before, after] {
+                    for (distance, id) in sequence.into_iter().enumerate() {
+                        let boost = self.config.temporal_boost * similarity / (distance + 1) as f32;
+                        let entry = boosts.entry(id).or_insert(0.0);
+                        *entry = entry.max(boost);
+                    }
                 }
             }
         }
 
-        Ok(bridged_nodes)
+        let existing_ids: HashSet<_> = existing_nodes.iter().map(|n| n.id).collect();
+        let missing: Vec<uuid::Uuid> = boosts.keys()
+            .filter(|id| !existing_ids.contains(id))
+            .copied()
+            .collect();
+        let nodes = if missing.is_empty() {
+            Vec::new()
+        } else {
+            let storage = self.storage.read().await;
+            storage.get_nodes(&missing).await?
+        };
+        log::debug!("时序上下文补充 {} 个事件", nodes.len());
+
+        Ok((nodes, boosts))
+    }
+
+    /// 为排序结果生成召回来源
+    ///
+    /// 子图扩展得到的节点沿扩展时实际经过的来源节点与边回溯到种子；
+    /// 其他阶段召回的节点取一条连到种子或扩展节点的边，没有时只给出阶段说明。
+    /// 路径上的节点内容从存储批量读取
+    async fn explain_provenance(
+        &self,
+        ranked: &[(MemoryNode, f32)],
+        tracker: &ProvenanceTracker,
+        seed_weights: &HashMap<uuid::Uuid, f64>,
+        trail: &ExpansionTrail,
+    ) -> Result<HashMap<uuid::Uuid, MemoryProvenance>> {
+        let seed_ids: Vec<uuid::Uuid> = seed_weights.keys().copied().collect();
+
+        let mut paths: HashMap<uuid::Uuid, graph::WeightedPath> = HashMap::new();
+        {
+            let graph = self.graph.read().await;
+            for (node, _) in ranked {
+                if seed_weights.contains_key(&node.id) {
+                    continue;
+                }
+                let path = trail_path(trail, &node.id)
+                    .or_else(|| one_hop_path(&graph, trail, &seed_ids, &node.id));
+                if let Some(path) = path {
+                    paths.insert(node.id, path);
+                }
+            }
+        }
+
+        let mut nodes: HashMap<uuid::Uuid, MemoryNode> = ranked.iter()
+            .map(|(node, _)| (node.id, node.clone()))
+            .collect();
+        let missing: Vec<uuid::Uuid> = paths.values()
+            .flat_map(|p| p.nodes.iter())
+            .filter(|id| !nodes.contains_key(id))
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if !missing.is_empty() {
+            let storage = self.storage.read().await;
+            for chunk in missing.chunks(500) {
+                nodes.extend(storage.get_nodes(chunk).await?.into_iter().map(|n| (n.id, n)));
+            }
+        }
+
+        Ok(ranked.iter()
+            .map(|(node, _)| {
+                let stage = if seed_weights.contains_key(&node.id) {
+                    RetrievalStage::VectorMatch
+                } else {
+                    tracker.stage_of(&node.id).unwrap_or(RetrievalStage::SubgraphExpansion)
+                };
+                let path = paths.get(&node.id).map(|p| graph::describe_path(p, &nodes));
+                (node.id, build_provenance(node, stage, path, seed_weights))
+            })
+            .collect())
     }
 
     /// 合并并去重节点
@@ -292,29 +654,218 @@ impl RetrievalService {
         result
     }
 
+    /// 获取社区划分（图未修改时复用缓存）
+    async fn community_membership(&self) -> Arc<HashMap<uuid::Uuid, uuid::Uuid>> {
+        let graph = self.graph.read().await;
+        let generation = graph.generation();
+        if let Some(cache) = self.community_cache.read().await.as_ref() {
+            if cache.is_fresh(generation, Duration::ZERO) {
+                return cache.value.clone();
+            }
+        }
+
+        // 持有缓存写锁计算，并发查询只计算一次
+        let mut slot = self.community_cache.write().await;
+        if let Some(cache) = slot.as_ref() {
+            if cache.is_fresh(generation, Duration::ZERO) {
+                return cache.value.clone();
+            }
+        }
+        let communities = graph.detect_communities(&CommunityConfig::default());
+        log::debug!("社区划分已更新: {} 个社区", communities.len());
+        let cache = GraphCache::new(generation, graph::community_membership(&communities));
+        let membership = cache.value.clone();
+        *slot = Some(cache);
+        membership
+    }
+
+    /// 中心性计算配置
+    fn centrality_config(&self) -> CentralityConfig {
+        CentralityConfig {
+            pagerank_weight: self.config.centrality_pagerank_weight,
+            betweenness_samples: self.config.betweenness_samples,
+            ..CentralityConfig::default()
+        }
+    }
+
+    /// 获取节点中心性（缓存过期时等待重算完成）
+    pub async fn centrality_scores(&self) -> Arc<HashMap<uuid::Uuid, CentralityScores>> {
+        let (fresh, cached) = self.cached_centrality().await;
+        if let (true, Some(scores)) = (fresh, cached) {
+            return scores;
+        }
+        let cache = compute_centrality_snapshot(&self.graph, self.centrality_config()).await;
+        let scores = cache.value.clone();
+        *self.centrality_cache.write().await = Some(cache);
+        scores
+    }
+
+    /// 读取中心性缓存
+    ///
+    /// # Returns
+    /// (缓存是否仍然可用, 缓存的结果)；图未修改或距上次计算不足 `centrality_refresh_secs` 秒时可用
+    async fn cached_centrality(&self) -> (bool, Option<Arc<HashMap<uuid::Uuid, CentralityScores>>>) {
+        let generation = self.graph.read().await.generation();
+        let max_age = Duration::from_secs(self.config.centrality_refresh_secs);
+        match self.centrality_cache.read().await.as_ref() {
+            Some(cache) => (cache.is_fresh(generation, max_age), Some(cache.value.clone())),
+            None => (false, None),
+        }
+    }
+
+    /// 在后台刷新中心性缓存（已有刷新任务时不重复启动）
+    fn spawn_centrality_refresh(&self) {
+        if self.centrality_refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let graph = self.graph.clone();
+        let cache = self.centrality_cache.clone();
+        let refreshing = self.centrality_refreshing.clone();
+        let config = self.centrality_config();
+        tokio::spawn(async move {
+            let computed = compute_centrality_snapshot(&graph, config).await;
+            *cache.write().await = Some(computed);
+            refreshing.store(false, Ordering::Release);
+        });
+    }
+
+    /// 计算候选节点的中心性加分
+    ///
+    /// 不在查询路径上计算中心性：缓存过期时触发后台刷新并沿用旧结果，
+    /// 尚无缓存时本次查询不加分
+    async fn centrality_boosts(&self, candidates: &[MemoryNode]) -> HashMap<uuid::Uuid, f32> {
+        if self.config.centrality_weight <= 0.0 {
+            return HashMap::new();
+        }
+        let (fresh, cached) = self.cached_centrality().await;
+        if !fresh {
+            self.spawn_centrality_refresh();
+        }
+        let Some(scores) = cached else {
+            return HashMap::new();
+        };
+
+        candidates.iter()
+            .filter_map(|node| {
+                let score = scores.get(&node.id)?.combined(self.config.centrality_pagerank_weight);
+                Some((node.id, self.config.centrality_weight * score as f32))
+            })
+            .collect()
+    }
+
+    /// 计算候选节点的社区加分
+    ///
+    /// 每个社区的加分按其中向量命中节点的相似度占比分配，
+    /// 与多数命中节点同属一个话题的候选加分最多
+    async fn community_boosts(
+        &self,
+        seed_weights: &HashMap<uuid::Uuid, f64>,
+        candidates: &[MemoryNode],
+    ) -> HashMap<uuid::Uuid, f32> {
+        if self.config.community_boost <= 0.0 {
+            return HashMap::new();
+        }
+        let membership = self.community_membership().await;
+
+        let mut community_weights: HashMap<uuid::Uuid, f64> = HashMap::new();
+        let mut total = 0.0;
+        for (id, weight) in seed_weights {
+            if let Some(community) = membership.get(id) {
+                *community_weights.entry(*community).or_insert(0.0) += weight.max(0.0);
+            }
+            total += weight.max(0.0);
+        }
+        if total <= 0.0 {
+            return HashMap::new();
+        }
+
+        candidates.iter()
+            .filter_map(|node| {
+                let community = membership.get(&node.id)?;
+                let share = community_weights.get(community)? / total;
+                Some((node.id, self.config.community_boost * share as f32))
+            })
+            .collect()
+    }
+
+    /// 记录检索路径经过的边，攒够一批或间隔足够久时在后台写入
+    ///
+    /// 只增强子图扩展时从种子走到返回结果所经过的边，同一次检索中每条边只计一次
+    async fn queue_reinforcement(&self, ranked: &[(MemoryNode, f32)], trail: &ExpansionTrail) {
+        let mut traversed: HashSet<uuid::Uuid> = HashSet::new();
+        for (node, _) in ranked {
+            let mut current = node.id;
+            while let Some((parent, edge)) = trail.get(&current) {
+                // 共享的上游路径已经计入
+                if !traversed.insert(edge.id) {
+                    break;
+                }
+                current = *parent;
+            }
+        }
+        if traversed.is_empty() {
+            return;
+        }
+
+        let batch = {
+            let mut pending = self.pending_reinforcement.lock().await;
+            for id in traversed {
+                *pending.traversals.entry(id).or_insert(0) += 1;
+            }
+            if pending.traversals.len() < REINFORCE_BATCH_EDGES
+                && pending.last_flush.elapsed() < REINFORCE_FLUSH_INTERVAL
+            {
+                return;
+            }
+            pending.take()
+        };
+
+        let graph = self.graph.clone();
+        let storage = self.storage.clone();
+        let decay_rate = self.config.edge_decay_rate;
+        tokio::spawn(async move {
+            match write_reinforcement(&graph, &storage, &batch, decay_rate).await {
+                Ok(count) => log::debug!("增强了 {} 条检索路径上的边", count),
+                Err(e) => log::warn!("增强检索路径上的边失败: {}", e),
+            }
+        });
+    }
+
+    /// 立即写入尚未写入的边增强（如退出前调用）
+    ///
+    /// # Returns
+    /// 被增强的边数
+    pub async fn flush_reinforcement(&self) -> Result<usize> {
+        let batch = self.pending_reinforcement.lock().await.take();
+        write_reinforcement(&self.graph, &self.storage, &batch, self.config.edge_decay_rate).await
+    }
+
     /// 计算权重并排序
+    ///
+    /// # Returns
+    /// 得分最高的 top_n 个节点及其得分
     async fn score_and_rank(
         &self,
         candidates: Vec<MemoryNode>,
         query_embedding: &[f32],
+        graph_boosts: &HashMap<uuid::Uuid, f32>,
         top_n: usize,
-    ) -> Vec<RetrievedMemory> {
+    ) -> Vec<(MemoryNode, f32)> {
         // 读取自定义记忆 ID 快照
         let custom_ids = self.custom_memory_ids.read().await;
 
         let mut scored_items: Vec<(MemoryNode, f32)> = candidates.into_iter()
             .map(|node| {
-                let score = Self::calculate_node_weight(&node, query_embedding, &custom_ids);
+                let score = Self::calculate_node_weight(&node, query_embedding, &custom_ids)
+                    + graph_boosts.get(&node.id).copied().unwrap_or(0.0);
                 (node, score)
             })
             .collect();
 
         scored_items.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-        scored_items.into_iter()
-            .take(top_n)
-            .map(|(node, score)| Self::node_to_retrieved_memory(node, score))
-            .collect()
+        scored_items.truncate(top_n);
+        scored_items
     }
 
     /// 计算节点权重
@@ -369,6 +920,7 @@ impl RetrievalService {
             relevance: score,
             event_time,
             time_ago,
+            provenance: None,
         }
     }
 
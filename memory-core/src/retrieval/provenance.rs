@@ -0,0 +1,249 @@
+//! 召回来源追踪
+//!
+//! 记录每个候选节点由哪个检索阶段首次加入，检索结束后结合子图扩展时
+//! 实际经过的路径生成可读的召回理由，帮助宿主解释"为什么想起了这件事"
+
+use crate::graph::{edge_cost, KnowledgeGraph, WeightedPath};
+use crate::types::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 子图扩展的路径：节点 → (扩展到它的来源节点, 经过的边)，种子节点不在其中
+pub type ExpansionTrail = HashMap<Uuid, (Uuid, Edge)>;
+
+/// 召回来源追踪器
+///
+/// 同一节点被多个阶段召回时保留最先记录的阶段
+#[derive(Debug, Clone, Default)]
+pub struct ProvenanceTracker {
+    stages: HashMap<Uuid, RetrievalStage>,
+}
+
+impl ProvenanceTracker {
+    /// 创建追踪器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一批节点由某阶段召回（已记录的节点不变）
+    pub fn record<'a>(&mut self, stage: RetrievalStage, nodes: impl IntoIterator<Item = &'a MemoryNode>) {
+        for node in nodes {
+            self.stages.entry(node.id).or_insert(stage);
+        }
+    }
+
+    /// 节点的召回阶段
+    pub fn stage_of(&self, id: &Uuid) -> Option<RetrievalStage> {
+        self.stages.get(id).copied()
+    }
+
+    /// 已记录的节点数
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// 是否没有记录
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+}
+
+/// 沿子图扩展记录回溯出从种子节点到目标节点的路径
+///
+/// 目标不是扩展得到的节点时返回 None
+pub fn trail_path(trail: &ExpansionTrail, target: &Uuid) -> Option<WeightedPath> {
+    trail.get(target)?;
+
+    let mut nodes = vec![*target];
+    let mut edges = Vec::new();
+    let mut current = *target;
+    while let Some((parent, edge)) = trail.get(&current) {
+        if nodes.contains(parent) {
+            break;
+        }
+        edges.push(edge.clone());
+        nodes.push(*parent);
+        current = *parent;
+    }
+    nodes.reverse();
+    edges.reverse();
+
+    let cost = edges.iter().map(edge_cost).sum();
+    Some(WeightedPath { nodes, edges, cost })
+}
+
+/// 不经子图扩展召回的节点（PageRank、概念桥接、时序上下文）的路径
+///
+/// 取一条把目标直接连到种子或扩展节点上的边，接在该节点的扩展路径之后；
+/// 有多条时取总代价最小的，都没有时返回 None，只给出阶段说明
+pub fn one_hop_path(
+    graph: &KnowledgeGraph,
+    trail: &ExpansionTrail,
+    seeds: &[Uuid],
+    target: &Uuid,
+) -> Option<WeightedPath> {
+    seeds.iter()
+        .chain(trail.keys())
+        .filter(|anchor| *anchor != target)
+        .filter_map(|anchor| {
+            let edge = graph.get_edge(anchor, target).or_else(|| graph.get_edge(target, anchor))?;
+            let mut path = trail_path(trail, anchor).unwrap_or_else(|| WeightedPath {
+                nodes: vec![*anchor],
+                edges: Vec::new(),
+                cost: 0.0,
+            });
+            path.nodes.push(*target);
+            path.cost += edge_cost(edge);
+            path.edges.push(edge.clone());
+            Some(path)
+        })
+        .min_by(|a, b| a.cost.total_cmp(&b.cost).then_with(|| a.nodes[0].cmp(&b.nodes[0])))
+}
+
+/// 生成可读的召回理由
+pub fn provenance_reason(
+    stage: RetrievalStage,
+    seed: Option<&PathNode>,
+    path: Option<&ConnectionPath>,
+) -> String {
+    let via = path
+        .filter(|p| !p.relations.is_empty())
+        .map(|p| format!("（{}）", p.description))
+        .unwrap_or_default();
+
+    match (stage, seed) {
+        (RetrievalStage::VectorMatch, _) => "与当前消息语义相近".to_string(),
+        (RetrievalStage::SubgraphExpansion, Some(seed)) => {
+            format!("因为你提到了「{}」，沿关联找到{}", seed.content, via)
+        }
+        (RetrievalStage::ConceptBridge, Some(seed)) => {
+            format!("与「{}」归属相同或相近的概念{}", seed.content, via)
+        }
+        (RetrievalStage::TemporalContext, Some(seed)) => {
+            format!("发生在「{}」前后{}", seed.content, via)
+        }
+        (RetrievalStage::SubgraphExpansion, None) => "通过图扩展关联到的记忆".to_string(),
+        (RetrievalStage::ConceptBridge, None) => "通过概念桥接关联到的记忆".to_string(),
+        (RetrievalStage::TemporalContext, None) => "时间上相邻的事件".to_string(),
+    }
+}
+
+/// 组装单条记忆的召回来源
+///
+/// 向量直接命中的记忆以自身为种子；其余记忆以路径起点为种子，
+/// 找不到路径时只给出阶段说明
+pub fn build_provenance(
+    node: &MemoryNode,
+    stage: RetrievalStage,
+    path: Option<ConnectionPath>,
+    seed_similarities: &HashMap<Uuid, f64>,
+) -> MemoryProvenance {
+    let seed = if stage == RetrievalStage::VectorMatch {
+        Some(PathNode {
+            id: node.id,
+            content: node.content.clone(),
+            node_type: node.node_type(),
+        })
+    } else {
+        path.as_ref().and_then(|p| p.nodes.first().cloned())
+    };
+    let seed_similarity = seed.as_ref()
+        .and_then(|s| seed_similarities.get(&s.id))
+        .map(|s| *s as f32);
+    let path = path.filter(|_| stage != RetrievalStage::VectorMatch);
+    let reason = provenance_reason(stage, seed.as_ref(), path.as_ref());
+
+    MemoryProvenance {
+        stage,
+        seed,
+        seed_similarity,
+        path,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::NodeBuilder;
+
+    fn path_node(node: &MemoryNode) -> PathNode {
+        PathNode {
+            id: node.id,
+            content: node.content.clone(),
+            node_type: node.node_type(),
+        }
+    }
+
+    #[test]
+    fn test_tracker_keeps_first_stage() {
+        let seed = NodeBuilder::entity("妈妈").build();
+        let event = NodeBuilder::event("陪妈妈去医院").build();
+
+        let mut tracker = ProvenanceTracker::new();
+        tracker.record(RetrievalStage::VectorMatch, [&seed]);
+        tracker.record(RetrievalStage::SubgraphExpansion, [&seed, &event]);
+
+        assert_eq!(tracker.len(), 2);
+        assert_eq!(tracker.stage_of(&seed.id), Some(RetrievalStage::VectorMatch));
+        assert_eq!(tracker.stage_of(&event.id), Some(RetrievalStage::SubgraphExpansion));
+        assert_eq!(tracker.stage_of(&Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_trail_and_one_hop_paths() {
+        let (seed, entity, event, neighbor) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let to_entity = Edge::new(seed, entity, "related_to".to_string());
+        let to_event = Edge::new(entity, event, "participates_in".to_string());
+        let trail: ExpansionTrail = HashMap::from([
+            (entity, (seed, to_entity.clone())),
+            (event, (entity, to_event.clone())),
+        ]);
+
+        let path = trail_path(&trail, &event).unwrap();
+        assert_eq!(path.nodes, vec![seed, entity, event]);
+        assert_eq!(path.edges.iter().map(|e| e.id).collect::<Vec<_>>(), vec![to_entity.id, to_event.id]);
+        assert!(trail_path(&trail, &seed).is_none());
+
+        // 时序上下文召回的节点接在扩展路径之后
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(to_entity);
+        graph.add_edge(to_event);
+        graph.add_edge(Edge::new(event, neighbor, "before".to_string()));
+        let attached = one_hop_path(&graph, &trail, &[seed], &neighbor).unwrap();
+        assert_eq!(attached.nodes, vec![seed, entity, event, neighbor]);
+        assert!(one_hop_path(&graph, &trail, &[seed], &Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn test_build_provenance_from_path() {
+        let seed = NodeBuilder::entity("妈妈").build();
+        let event = NodeBuilder::event("陪妈妈去医院").build();
+        let similarities = HashMap::from([(seed.id, 0.9)]);
+
+        let direct = build_provenance(&seed, RetrievalStage::VectorMatch, None, &similarities);
+        assert_eq!(direct.seed.as_ref().map(|s| s.id), Some(seed.id));
+        assert_eq!(direct.seed_similarity, Some(0.9));
+        assert!(direct.path.is_none());
+
+        let path = ConnectionPath {
+            nodes: vec![path_node(&seed), path_node(&event)],
+            relations: vec![PathRelation {
+                relation: "participates_in".to_string(),
+                weight: 1.0,
+                forward: true,
+            }],
+            cost: 1.0,
+            description: "妈妈 -[participates_in]-> 陪妈妈去医院".to_string(),
+        };
+        let expanded = build_provenance(&event, RetrievalStage::SubgraphExpansion, Some(path), &similarities);
+        assert_eq!(expanded.seed.as_ref().map(|s| s.id), Some(seed.id));
+        assert_eq!(expanded.seed_similarity, Some(0.9));
+        assert!(expanded.reason.contains("「妈妈」"));
+        assert!(expanded.reason.contains("participates_in"));
+
+        let orphan = build_provenance(&event, RetrievalStage::TemporalContext, None, &similarities);
+        assert!(orphan.seed.is_none());
+        assert_eq!(orphan.reason, "时间上相邻的事件");
+    }
+}
@@ -239,6 +239,7 @@ pub fn scored_to_retrieved(scored: &[ScoredMemory]) -> Vec<RetrievedMemory> {
             relevance: s.final_score,
             event_time: s.node.event_time().map(|s| s.to_string()),
             time_ago: s.node.event_time().map(utils::calculate_time_ago),
+            provenance: None,
         })
         .collect()
 }
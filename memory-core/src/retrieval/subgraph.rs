@@ -4,7 +4,7 @@
 //! 从初始检索结果扩展到相关的邻居节点
 
 use crate::types::{MemoryNode, NodeType, Edge, RetrievedMemory};
-use crate::graph::KnowledgeGraph;
+use crate::graph::{calculate_decayed_edge_weight, KnowledgeGraph, TraversalOptions, DEFAULT_EDGE_DECAY_RATE};
 use crate::utils;
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
@@ -116,17 +116,28 @@ pub struct SubgraphExpansionConfig {
     pub max_nodes: usize,
     /// 是否包含概念节点
     pub include_concepts: bool,
-    /// 最小边权重阈值
+    /// 最小边权重阈值（按衰减后的权重比较，默认 0 表示只用 `traversal.min_weight`）
+    ///
+    /// 创建扩展器时与 `traversal.min_weight` 取较大者
+    #[deprecated(note = "请改用 `traversal.min_weight`")]
     pub min_edge_weight: f32,
+    /// 扩展时的关系类型、方向、节点类型与最小边权重限制
+    /// （`min_weight` 与衰减后的权重比较，扩展和筛选子图内的边时一致）
+    pub traversal: TraversalOptions,
+    /// 边权重每天的衰减速率
+    pub edge_decay_rate: f64,
 }
 
 impl Default for SubgraphExpansionConfig {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             hop_depth: 2,
             max_nodes: 30,
             include_concepts: true,
-            min_edge_weight: 0.1,
+            min_edge_weight: 0.0,
+            traversal: TraversalOptions::default().with_min_weight(0.1),
+            edge_decay_rate: DEFAULT_EDGE_DECAY_RATE,
         }
     }
 }
@@ -138,7 +149,10 @@ pub struct SubgraphExpander {
 
 impl SubgraphExpander {
     /// 创建新的扩展器
-    pub fn new(config: SubgraphExpansionConfig) -> Self {
+    #[allow(deprecated)]
+    pub fn new(mut config: SubgraphExpansionConfig) -> Self {
+        // 兼容旧的 min_edge_weight 字段
+        config.traversal.min_weight = config.traversal.min_weight.max(config.min_edge_weight);
         Self { config }
     }
 
@@ -181,10 +195,10 @@ impl SubgraphExpander {
                 subgraph.add_node(node.clone());
             }
 
-            // 如果还没到达最大深度，继续扩展
+            // 如果还没到达最大深度，继续扩展（逆序入栈，连接最强的邻居最先访问）
             if depth < self.config.hop_depth {
-                let neighbors = graph.get_neighbors(&node_id, 1);
-                for neighbor_id in neighbors {
+                let neighbors = graph.ranked_neighbors(&node_id, &self.config.traversal, self.config.edge_decay_rate);
+                for (neighbor_id, _) in neighbors.into_iter().rev() {
                     if !visited.contains(&neighbor_id) {
                         to_visit.push((neighbor_id, depth + 1));
                     }
@@ -197,7 +211,9 @@ impl SubgraphExpander {
         for node in &subgraph.nodes {
             let outgoing = graph.get_outgoing_edges(&node.id);
             for edge in outgoing {
-                if edge.weight >= self.config.min_edge_weight 
+                let weight = calculate_decayed_edge_weight(edge, self.config.edge_decay_rate);
+                if weight >= self.config.traversal.min_weight as f64
+                    && self.config.traversal.allows_edge(edge)
                     && subgraph.contains_node(&edge.target) {
                     edges_to_add.push(edge.clone());
                 }
@@ -301,6 +317,7 @@ pub fn subgraph_to_memories(subgraph: &Subgraph) -> Vec<RetrievedMemory> {
             relevance: node.importance,
             event_time: node.event_time().map(|s| s.to_string()),
             time_ago: node.event_time().map(utils::calculate_time_ago),
+            provenance: None,
         })
         .collect()
 }
@@ -333,4 +350,60 @@ mod tests {
         
         assert_eq!(sg1.node_count(), 2);
     }
+
+    #[test]
+    fn test_expand_prefers_strong_edges() {
+        let center = MemoryNode::new_event("中心".to_string(), "2026-01-01-00-00".to_string());
+        let strong = MemoryNode::new_event("强关联".to_string(), "2026-01-01-00-00".to_string());
+        let weak = MemoryNode::new_event("弱关联".to_string(), "2026-01-01-00-00".to_string());
+
+        let mut graph = KnowledgeGraph::new();
+        for (target, weight) in [(&weak, 0.2), (&strong, 0.9)] {
+            let mut edge = Edge::new(center.id, target.id, "relates".to_string());
+            edge.weight = weight;
+            graph.add_edge(edge);
+        }
+        let lookup: HashMap<Uuid, MemoryNode> = [&center, &strong, &weak].iter()
+            .map(|n| (n.id, (*n).clone()))
+            .collect();
+
+        let expander = SubgraphExpander::new(SubgraphExpansionConfig {
+            max_nodes: 2,
+            ..SubgraphExpansionConfig::default()
+        });
+        let subgraph = expander.expand(&graph, &[center.id], &lookup);
+        assert!(subgraph.contains_node(&strong.id));
+        assert!(!subgraph.contains_node(&weak.id));
+        assert_eq!(subgraph.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_expand_skips_decayed_edges() {
+        let center = MemoryNode::new_event("中心".to_string(), "2026-01-01-00-00".to_string());
+        let stale = MemoryNode::new_event("久未使用".to_string(), "2026-01-01-00-00".to_string());
+
+        // 原始权重很高，但闲置一年后衰减到最小权重以下
+        let mut edge = Edge::new(center.id, stale.id, "relates".to_string());
+        edge.weight = 0.9;
+        edge.created_at -= 365 * 86400;
+        edge.last_seen_at = edge.created_at;
+        let mut graph = KnowledgeGraph::new();
+        graph.add_edge(edge);
+        let lookup: HashMap<Uuid, MemoryNode> = [&center, &stale].iter()
+            .map(|n| (n.id, (*n).clone()))
+            .collect();
+
+        let subgraph = SubgraphExpander::with_defaults().expand(&graph, &[center.id], &lookup);
+        assert!(!subgraph.contains_node(&stale.id));
+        assert_eq!(subgraph.edge_count(), 0);
+
+        // 不衰减时沿用旧字段设置的阈值
+        #[allow(deprecated)]
+        let expander = SubgraphExpander::new(SubgraphExpansionConfig {
+            min_edge_weight: 0.95,
+            edge_decay_rate: 0.0,
+            ..SubgraphExpansionConfig::default()
+        });
+        assert_eq!(expander.expand(&graph, &[center.id], &lookup).node_count(), 1);
+    }
 }
@@ -6,8 +6,8 @@ use crate::error::{MemoryError, Result};
 use crate::storage::schema::*;
 use crate::types::*;
 use futures::TryStreamExt;
-use lancedb::query::{QueryBase, ExecutableQuery};
-use std::collections::HashSet;
+use lancedb::query::{QueryBase, ExecutableQuery, Select};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 /// LanceDB 存储服务
@@ -47,6 +47,12 @@ impl LanceDBStorage {
 
         self.db = Some(db);
         self.initialized = true;
+
+        // 旧版边表迁移：补充 last_seen_at、metadata 列并合并平行边
+        self.migrate_edges_table().await?;
+        // 旧版概念池迁移：补充 node_id 列
+        self.migrate_concept_pool_table().await?;
+
         log::info!("LanceDB 初始化成功");
         Ok(())
     }
@@ -108,6 +114,79 @@ impl LanceDBStorage {
         Ok(())
     }
 
+    /// 概念池迁移
+    ///
+    /// 旧版概念池只记录名称：补充可空的 node_id 列，
+    /// 节点 ID 由上层在加载图后按名称关联
+    async fn migrate_concept_pool_table(&self) -> Result<()> {
+        let table = self.open_table(table_names::CONCEPT_POOL).await?;
+        let schema = table.schema()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("读取概念池表结构失败: {}", e)))?;
+        if schema.field_with_name("node_id").is_ok() {
+            return Ok(());
+        }
+
+        log::info!("迁移概念池表: 添加 node_id 列");
+        table.add_columns(
+            lancedb::table::NewColumnTransform::SqlExpressions(vec![
+                ("node_id".to_string(), "CAST(NULL AS STRING)".to_string()),
+            ]),
+            None,
+        )
+            .await
+            .map_err(|e| MemoryError::Storage(format!("概念池表添加 node_id 列失败: {}", e)))?;
+        Ok(())
+    }
+
+    /// 边表迁移
+    ///
+    /// 旧版边表没有 last_seen_at 列：以 created_at 填充新列，
+    /// 并一次性合并此前累积的平行边；没有 metadata 列时补充可空的 metadata 列
+    async fn migrate_edges_table(&self) -> Result<()> {
+        let table = self.open_table(table_names::EDGES).await?;
+        let schema = table.schema()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("读取边表结构失败: {}", e)))?;
+        let missing_last_seen = schema.field_with_name("last_seen_at").is_err();
+
+        if missing_last_seen {
+            log::info!("迁移边表: 添加 last_seen_at 列");
+            table.add_columns(
+                lancedb::table::NewColumnTransform::SqlExpressions(vec![
+                    ("last_seen_at".to_string(), "created_at".to_string()),
+                ]),
+                None,
+            )
+                .await
+                .map_err(|e| MemoryError::Storage(format!("边表添加 last_seen_at 列失败: {}", e)))?;
+        }
+
+        if schema.field_with_name("metadata").is_err() {
+            log::info!("迁移边表: 添加 metadata 列");
+            table.add_columns(
+                lancedb::table::NewColumnTransform::SqlExpressions(vec![
+                    ("metadata".to_string(), "CAST(NULL AS STRING)".to_string()),
+                ]),
+                None,
+            )
+                .await
+                .map_err(|e| MemoryError::Storage(format!("边表添加 metadata 列失败: {}", e)))?;
+        }
+
+        // 合并平行边时按完整的新表结构写回，放在补齐所有列之后
+        if missing_last_seen {
+            let removed = self.dedupe_edges().await?;
+            log::info!("边表迁移完成，合并了 {} 条平行边", removed);
+        }
+        Ok(())
+    }
+
+    /// 获取数据库路径
+    pub fn db_path(&self) -> &str {
+        &self.db_path
+    }
+
     /// 获取数据库连接（内部辅助）
     fn db(&self) -> Result<&lancedb::Connection> {
         self.db.as_ref().ok_or(MemoryError::NotInitialized)
@@ -282,6 +361,137 @@ impl LanceDBStorage {
         Ok(nodes)
     }
 
+    /// 获取事件时间在 [from, to] 范围内的事件节点
+    ///
+    /// 事件时间格式为 YYYY-MM-DD-HH-MM，按字符串比较即为时间顺序
+    pub async fn get_events_between(&self, from: &str, to: &str) -> Result<Vec<MemoryNode>> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::NODES).await?;
+        let filter = format!(
+            "node_type = 'event' AND event_time >= '{}' AND event_time <= '{}'",
+            from.replace('\'', "''"),
+            to.replace('\'', "''")
+        );
+
+        let batches = table.query()
+            .only_if(filter)
+            .execute()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("按时间查询事件失败: {}", e)))?
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+
+        let mut nodes = Vec::new();
+        for batch in &batches {
+            for record in batch_to_node_records(batch)? {
+                match record.to_node() {
+                    Ok(node) => nodes.push(node),
+                    Err(e) => log::error!("反序列化节点失败: {}", e),
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    /// 按内容条件查找节点
+    ///
+    /// 条件之间为“且”关系；子串条件先用 LIKE 粗筛，再按原始条件精确过滤
+    pub async fn find_nodes_by_content(
+        &self,
+        node_type: Option<NodeType>,
+        predicates: &[crate::graph::ContentPredicate],
+    ) -> Result<Vec<MemoryNode>> {
+        use crate::graph::ContentPredicate;
+
+        self.check_initialized()?;
+
+        let mut clauses: Vec<String> = predicates.iter()
+            .map(|p| match p {
+                ContentPredicate::Equals(value) => format!("content = '{}'", value.replace('\'', "''")),
+                ContentPredicate::Contains(value) => format!("content LIKE '%{}%'", value.replace('\'', "''")),
+            })
+            .collect();
+        if let Some(node_type) = node_type {
+            clauses.push(format!("node_type = '{}'", node_type.as_str()));
+        }
+
+        let table = self.open_table(table_names::NODES).await?;
+        let mut query = table.query();
+        if !clauses.is_empty() {
+            query = query.only_if(clauses.join(" AND "));
+        }
+        let batches = query
+            .execute()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("按内容查询节点失败: {}", e)))?
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+
+        let mut nodes = Vec::new();
+        for batch in &batches {
+            for record in batch_to_node_records(batch)? {
+                match record.to_node() {
+                    Ok(node) if predicates.iter().all(|p| p.matches(&node.content)) => nodes.push(node),
+                    Ok(_) => {}
+                    Err(e) => log::error!("反序列化节点失败: {}", e),
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    /// 按名称查找实体节点
+    ///
+    /// 名称与实体内容完全相同，或出现在实体的别名列表中即命中；
+    /// 别名先按 metadata 子串粗筛，再按别名精确过滤
+    pub async fn find_entities_by_names(&self, names: &[String]) -> Result<Vec<MemoryNode>> {
+        self.check_initialized()?;
+        if names.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let table = self.open_table(table_names::NODES).await?;
+        let mut nodes = Vec::new();
+        for chunk in names.chunks(200) {
+            let content_list: Vec<String> = chunk.iter()
+                .map(|n| format!("'{}'", n.replace('\'', "''")))
+                .collect();
+            let mut clauses = vec![format!("content IN ({})", content_list.join(", "))];
+            // 含 LIKE 通配符或需要 JSON 转义的名称无法按子串粗筛，只做内容匹配
+            clauses.extend(chunk.iter()
+                .filter(|n| !n.contains(['%', '_', '"', '\\']))
+                .map(|n| format!("metadata LIKE '%\"{}\"%'", n.replace('\'', "''"))));
+            let filter = format!("node_type = 'entity' AND ({})", clauses.join(" OR "));
+
+            let batches = table.query()
+                .only_if(filter)
+                .execute()
+                .await
+                .map_err(|e| MemoryError::Storage(format!("按名称查询实体失败: {}", e)))?
+                .try_collect::<Vec<_>>()
+                .await
+                .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+
+            for batch in &batches {
+                for record in batch_to_node_records(batch)? {
+                    match record.to_node() {
+                        Ok(node) if chunk.contains(&node.content)
+                            || node.aliases().iter().any(|a| chunk.contains(a)) => nodes.push(node),
+                        Ok(_) => {}
+                        Err(e) => log::error!("反序列化节点失败: {}", e),
+                    }
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
     /// 获取所有节点
     pub async fn get_all_nodes(&self) -> Result<Vec<MemoryNode>> {
         self.check_initialized()?;
@@ -310,6 +520,145 @@ impl LanceDBStorage {
         Ok(nodes)
     }
 
+    /// 获取节点类型
+    ///
+    /// # Arguments
+    /// * `ids` - 要查询的节点 ID，None 表示全部节点
+    pub async fn get_node_types(
+        &self,
+        ids: Option<&[uuid::Uuid]>,
+    ) -> Result<HashMap<uuid::Uuid, NodeType>> {
+        self.check_initialized()?;
+        if ids.is_some_and(|ids| ids.is_empty()) {
+            return Ok(HashMap::new());
+        }
+
+        // 只投影 id 与 node_type 两列，避免读取向量与内容
+        let table = self.open_table(table_names::NODES).await?;
+        let mut query = table.query().select(Select::columns(&["id", "node_type"]));
+        if let Some(ids) = ids {
+            let id_list: Vec<String> = ids.iter().map(|id| format!("'{}'", id)).collect();
+            query = query.only_if(format!("id IN ({})", id_list.join(", ")));
+        }
+
+        let batches = query
+            .execute()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("查询节点类型失败: {}", e)))?
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+
+        let mut types = HashMap::new();
+        for batch in &batches {
+            types.extend(batch_to_node_types(batch)?);
+        }
+        Ok(types)
+    }
+
+    /// 统计节点数量
+    ///
+    /// # Arguments
+    /// * `node_type` - 只统计该类型的节点，None 表示全部节点
+    pub async fn count_nodes(&self, node_type: Option<NodeType>) -> Result<usize> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::NODES).await?;
+        table.count_rows(node_type.map(|t| format!("node_type = '{}'", t.as_str())))
+            .await
+            .map_err(|e| MemoryError::Storage(format!("统计节点数量失败: {}", e)))
+    }
+
+    /// 统计各实体类型的数量
+    ///
+    /// 实体类型记录在 metadata 列中，逐类型按过滤条件计数；
+    /// metadata 缺失或无法识别的实体与 `NodeRecord::to_node` 一致计为 other
+    pub async fn count_entities_by_type(&self) -> Result<BTreeMap<String, usize>> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::NODES).await?;
+        let total = self.count_nodes(Some(NodeType::Entity)).await?;
+
+        let mut counts = BTreeMap::new();
+        let mut classified = 0;
+        for entity_type in [EntityType::Person, EntityType::Place, EntityType::Object, EntityType::Time] {
+            let filter = format!(
+                "node_type = 'entity' AND metadata LIKE '%\"entity_type\":\"{}\"%'",
+                entity_type.as_str()
+            );
+            let count = table.count_rows(Some(filter))
+                .await
+                .map_err(|e| MemoryError::Storage(format!("统计实体类型数量失败: {}", e)))?;
+            if count > 0 {
+                counts.insert(entity_type.as_str().to_string(), count);
+            }
+            classified += count;
+        }
+        let other = total.saturating_sub(classified);
+        if other > 0 {
+            counts.insert(EntityType::Other.as_str().to_string(), other);
+        }
+        Ok(counts)
+    }
+
+    /// 统计缺少向量嵌入（以全零向量存储）的节点数
+    ///
+    /// 只投影向量列并逐批流式统计，不把节点整体加载到内存
+    pub async fn count_nodes_without_embedding(&self) -> Result<usize> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::NODES).await?;
+        let mut stream = table.query()
+            .select(Select::columns(&["vector"]))
+            .execute()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("查询节点向量失败: {}", e)))?;
+
+        let mut count = 0;
+        while let Some(batch) = stream.try_next()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("读取查询结果失败: {}", e)))?
+        {
+            count += count_zero_vectors(&batch);
+        }
+        Ok(count)
+    }
+
+    /// 事件时间范围（最早, 最近）
+    ///
+    /// 只投影事件节点的 event_time 列，无法解析的时间不参与比较
+    pub async fn event_time_range(&self) -> Result<Option<(String, String)>> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::NODES).await?;
+        let mut stream = table.query()
+            .only_if("node_type = 'event'")
+            .select(Select::columns(&["event_time"]))
+            .execute()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("查询事件时间失败: {}", e)))?;
+
+        let mut oldest: Option<(chrono::NaiveDateTime, String)> = None;
+        let mut newest: Option<(chrono::NaiveDateTime, String)> = None;
+        while let Some(batch) = stream.try_next()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("读取查询结果失败: {}", e)))?
+        {
+            for event_time in batch_to_event_times(&batch) {
+                let Some(parsed) = crate::utils::parse_event_time(&event_time) else {
+                    continue;
+                };
+                if oldest.as_ref().is_none_or(|(t, _)| parsed < *t) {
+                    oldest = Some((parsed, event_time.clone()));
+                }
+                if newest.as_ref().is_none_or(|(t, _)| parsed > *t) {
+                    newest = Some((parsed, event_time));
+                }
+            }
+        }
+        Ok(oldest.zip(newest).map(|((_, first), (_, last))| (first, last)))
+    }
+
     // ============================================
     // 向量检索
     // ============================================
@@ -503,6 +852,102 @@ impl LanceDBStorage {
         Ok(edges)
     }
 
+    /// 获取最近观测时间不早于指定时间戳的边
+    ///
+    /// 用于在图快照之上增量回放新增和被增强的边
+    pub async fn get_edges_since(&self, seen_at: i64) -> Result<Vec<Edge>> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::EDGES).await?;
+        let filter = format!("last_seen_at >= {}", seen_at);
+
+        let batches = table.query()
+            .only_if(filter)
+            .execute()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("查询新增边失败: {}", e)))?
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+
+        let mut edges = Vec::new();
+        for batch in &batches {
+            let records = batch_to_edge_records(batch)?;
+            edges.extend(records.into_iter().map(|r| r.to_edge()));
+        }
+
+        Ok(edges)
+    }
+
+    /// 获取边总数
+    pub async fn count_edges(&self) -> Result<usize> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::EDGES).await?;
+        table.count_rows(None)
+            .await
+            .map_err(|e| MemoryError::Storage(format!("统计边数量失败: {}", e)))
+    }
+
+    /// 获取边表当前版本号
+    ///
+    /// LanceDB 每次写入（添加、删除）都会产生新版本，用于校验图快照是否过期
+    pub async fn edge_table_version(&self) -> Result<u64> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::EDGES).await?;
+        table.version()
+            .await
+            .map_err(|e| MemoryError::Storage(format!("获取边表版本失败: {}", e)))
+    }
+
+    /// 更新边（按边 ID merge_insert：已有记录整行覆盖，不存在时插入，单次提交）
+    pub async fn update_edges(&self, edges: &[Edge]) -> Result<()> {
+        if edges.is_empty() {
+            return Ok(());
+        }
+        self.check_initialized()?;
+
+        let records: Vec<EdgeRecord> = edges.iter().map(EdgeRecord::from_edge).collect();
+        let batch = edges_to_batch(&records)?;
+        let reader = make_batch_reader(batch, edges_schema());
+
+        let table = self.open_table(table_names::EDGES).await?;
+        let mut merge = table.merge_insert(&["id"]);
+        merge.when_matched_update_all(None).when_not_matched_insert_all();
+        merge.execute(Box::new(reader))
+            .await
+            .map_err(|e| MemoryError::Storage(format!("更新边失败: {}", e)))?;
+
+        log::debug!("更新了 {} 条边", edges.len());
+        Ok(())
+    }
+
+    /// 合并边表中的平行边
+    ///
+    /// 相同 (source, target, relation) 的边只保留最早的一条，
+    /// 其余视为重复观测增强其权重后删除
+    ///
+    /// # Returns
+    /// 被删除的边数量
+    pub async fn dedupe_edges(&self) -> Result<usize> {
+        self.check_initialized()?;
+
+        let edges = self.get_all_edges().await?;
+        let (merged, removed) = crate::graph::collapse_parallel_edges(edges);
+        if removed.is_empty() {
+            return Ok(0);
+        }
+
+        self.delete_edges(&removed).await?;
+        for chunk in merged.chunks(500) {
+            self.update_edges(chunk).await?;
+        }
+
+        log::info!("合并平行边: 保留 {} 条，删除 {} 条", merged.len(), removed.len());
+        Ok(removed.len())
+    }
+
     /// 删除边
     pub async fn delete_edge(&self, id: &uuid::Uuid) -> Result<()> {
         self.check_initialized()?;
@@ -516,6 +961,25 @@ impl LanceDBStorage {
         Ok(())
     }
 
+    /// 批量删除边
+    pub async fn delete_edges(&self, ids: &[uuid::Uuid]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::EDGES).await?;
+        for chunk in ids.chunks(500) {
+            let id_list: Vec<String> = chunk.iter().map(|id| format!("'{}'", id)).collect();
+            table.delete(&format!("id IN ({})", id_list.join(", ")))
+                .await
+                .map_err(|e| MemoryError::Storage(format!("批量删除边失败: {}", e)))?;
+        }
+
+        log::debug!("已删除 {} 条边", ids.len());
+        Ok(())
+    }
+
     /// 删除与节点相关的所有边
     pub async fn delete_node_edges(&self, node_id: &uuid::Uuid) -> Result<()> {
         self.check_initialized()?;
@@ -536,71 +1000,91 @@ impl LanceDBStorage {
     // ============================================
 
     /// 添加或更新概念
-    pub async fn upsert_concept(&self, name: &str) -> Result<()> {
+    ///
+    /// 以概念节点为准写入概念池：名称、实例数、最近使用时间与节点 ID 保持一致
+    pub async fn upsert_concept(&self, node: &MemoryNode) -> Result<()> {
+        self.upsert_concepts(std::slice::from_ref(node)).await
+    }
+
+    /// 批量添加或更新概念
+    pub async fn upsert_concepts(&self, nodes: &[MemoryNode]) -> Result<()> {
         self.check_initialized()?;
 
-        let table = self.open_table(table_names::CONCEPT_POOL).await?;
-        let filter = format!("name = '{}'", name.replace('\'', "''"));
+        let entries: Vec<ConceptPoolEntry> = nodes.iter()
+            .filter_map(ConceptPoolEntry::from_concept_node)
+            .collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
 
-        // 检查是否已存在
-        let batches = table.query()
-            .only_if(filter.clone())
-            .limit(1)
-            .execute()
-            .await
-            .map_err(|e| MemoryError::Storage(format!("查询概念失败: {}", e)))?
-            .try_collect::<Vec<_>>()
+        // 按名称 merge_insert，单次提交；同一批次中的重名概念以后出现的为准
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut unique: Vec<ConceptPoolEntry> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match positions.get(&entry.name) {
+                Some(&i) => unique[i] = entry,
+                None => {
+                    positions.insert(entry.name.clone(), unique.len());
+                    unique.push(entry);
+                }
+            }
+        }
+        let entries = unique;
+
+        let batch = concepts_to_batch(&entries)?;
+        let reader = make_batch_reader(batch, concept_pool_schema());
+        let table = self.open_table(table_names::CONCEPT_POOL).await?;
+        let mut merge = table.merge_insert(&["name"]);
+        merge.when_matched_update_all(None).when_not_matched_insert_all();
+        merge.execute(Box::new(reader))
             .await
-            .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+            .map_err(|e| MemoryError::Storage(format!("写入概念失败: {}", e)))?;
 
-        let existing = batches.iter()
-            .flat_map(|b| batch_to_concept_entries(b).unwrap_or_default())
-            .next();
+        log::debug!("已 upsert {} 个概念", entries.len());
+        Ok(())
+    }
 
-        let now = chrono::Utc::now().timestamp();
+    /// 按名称查找概念节点
+    ///
+    /// # Returns
+    /// 名称 → 概念节点；同名节点有多个时取最早创建的
+    pub async fn get_concept_nodes(&self, names: &[String]) -> Result<HashMap<String, MemoryNode>> {
+        self.check_initialized()?;
 
-        if let Some(existing_entry) = existing {
-            // 已存在：删除旧记录，插入更新后的记录
-            table.delete(&filter)
-                .await
-                .map_err(|e| MemoryError::Storage(format!("删除旧概念失败: {}", e)))?;
-
-            let updated = ConceptPoolEntry {
-                name: existing_entry.name,
-                instance_count: existing_entry.instance_count + 1,
-                last_used_at: now,
-            };
-            let batch = concepts_to_batch(&[updated])?;
-            let reader = make_batch_reader(batch, concept_pool_schema());
-            table.add(reader)
+        let table = self.open_table(table_names::NODES).await?;
+        let mut found: HashMap<String, MemoryNode> = HashMap::new();
+        for chunk in names.chunks(500) {
+            let quoted: Vec<String> = chunk.iter()
+                .map(|n| format!("'{}'", n.replace('\'', "''")))
+                .collect();
+            let filter = format!("node_type = 'concept' AND content IN ({})", quoted.join(", "));
+
+            let batches = table.query()
+                .only_if(filter)
                 .execute()
                 .await
-                .map_err(|e| MemoryError::Storage(format!("更新概念失败: {}", e)))?;
-        } else {
-            // 不存在：插入新记录
-            let entry = ConceptPoolEntry {
-                name: name.to_string(),
-                instance_count: 1,
-                last_used_at: now,
-            };
-            let batch = concepts_to_batch(&[entry])?;
-            let reader = make_batch_reader(batch, concept_pool_schema());
-            table.add(reader)
-                .execute()
+                .map_err(|e| MemoryError::Storage(format!("查询概念节点失败: {}", e)))?
+                .try_collect::<Vec<_>>()
                 .await
-                .map_err(|e| MemoryError::Storage(format!("添加概念失败: {}", e)))?;
+                .map_err(|e| MemoryError::Storage(format!("收集查询结果失败: {}", e)))?;
+
+            for batch in &batches {
+                for record in batch_to_node_records(batch)? {
+                    match record.to_node() {
+                        Ok(node) => {
+                            let keep = found.get(&node.content)
+                                .is_none_or(|existing| node.created_at < existing.created_at);
+                            if keep {
+                                found.insert(node.content.clone(), node);
+                            }
+                        }
+                        Err(e) => log::error!("反序列化节点失败: {}", e),
+                    }
+                }
+            }
         }
 
-        log::debug!("已 upsert 概念: {}", name);
-        Ok(())
-    }
-
-    /// 批量添加或更新概念
-    pub async fn upsert_concepts(&self, names: &[String]) -> Result<()> {
-        for name in names {
-            self.upsert_concept(name).await?;
-        }
-        Ok(())
+        Ok(found)
     }
 
     /// 获取活跃概念（按使用频率排序）
@@ -652,6 +1136,16 @@ impl LanceDBStorage {
         Ok(entries)
     }
 
+    /// 获取概念池大小
+    pub async fn count_concepts(&self) -> Result<usize> {
+        self.check_initialized()?;
+
+        let table = self.open_table(table_names::CONCEPT_POOL).await?;
+        table.count_rows(None)
+            .await
+            .map_err(|e| MemoryError::Storage(format!("统计概念数量失败: {}", e)))
+    }
+
     /// 删除低频概念
     pub async fn prune_inactive_concepts(
         &self,
@@ -46,17 +46,19 @@ impl NodeRecord {
 
         // 从 NodeData 提取 event_time 和 metadata
         let (event_time, metadata) = match &node.data {
-            NodeData::Entity { entity_type, attributes } => {
+            NodeData::Entity { entity_type, attributes, aliases } => {
                 let meta = serde_json::json!({
                     "entity_type": entity_type,
                     "attributes": attributes,
+                    "aliases": aliases,
                 });
                 (None, meta.to_string())
             }
-            NodeData::Event { participants, event_time, source_conversation_id } => {
+            NodeData::Event { participants, event_time, source_conversation_id, roles } => {
                 let meta = serde_json::json!({
                     "participants": participants,
                     "source_conversation_id": source_conversation_id,
+                    "roles": roles,
                 });
                 (Some(event_time.clone()), meta.to_string())
             }
@@ -99,7 +101,10 @@ impl NodeRecord {
                     .unwrap_or(EntityType::Other);
                 let attributes = metadata.get("attributes")
                     .and_then(|v| if v.is_null() { None } else { Some(v.clone()) });
-                NodeData::Entity { entity_type, attributes }
+                let aliases = metadata.get("aliases")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default();
+                NodeData::Entity { entity_type, attributes, aliases }
             }
             "event" => {
                 let participants = metadata.get("participants")
@@ -109,7 +114,10 @@ impl NodeRecord {
                 let source_conversation_id = metadata.get("source_conversation_id")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
-                NodeData::Event { participants, event_time, source_conversation_id }
+                let roles = metadata.get("roles")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default();
+                NodeData::Event { participants, event_time, source_conversation_id, roles }
             }
             "concept" => {
                 let instance_count = metadata.get("instance_count")
@@ -123,7 +131,7 @@ impl NodeRecord {
             }
             _ => {
                 log::warn!("未知节点类型 '{}', 默认为 Entity", self.node_type);
-                NodeData::Entity { entity_type: EntityType::Other, attributes: None }
+                NodeData::Entity { entity_type: EntityType::Other, attributes: None, aliases: Vec::new() }
             }
         };
 
@@ -157,6 +165,8 @@ pub struct EdgeRecord {
     pub relation: String,
     pub weight: f32,
     pub created_at: i64,
+    pub last_seen_at: i64,
+    pub metadata: Option<String>, // JSON 序列化的边元数据
 }
 
 impl EdgeRecord {
@@ -168,6 +178,8 @@ impl EdgeRecord {
             relation: edge.relation.clone(),
             weight: edge.weight,
             created_at: edge.created_at,
+            last_seen_at: edge.last_seen_at,
+            metadata: edge.metadata.as_ref().map(|m| m.to_string()),
         }
     }
 
@@ -179,7 +191,8 @@ impl EdgeRecord {
             relation: self.relation.clone(),
             weight: self.weight,
             created_at: self.created_at,
-            metadata: None,
+            last_seen_at: self.last_seen_at,
+            metadata: self.metadata.as_deref().and_then(|m| serde_json::from_str(m).ok()),
         }
     }
 }
@@ -190,6 +203,25 @@ pub struct ConceptPoolEntry {
     pub name: String,
     pub instance_count: u32,
     pub last_used_at: i64,
+    /// 对应的概念节点 ID（旧版概念池迁移前为 None）
+    pub node_id: Option<String>,
+}
+
+impl ConceptPoolEntry {
+    /// 从概念节点生成概念池条目，实例数与最近使用时间以节点为准
+    ///
+    /// 非概念节点返回 None
+    pub fn from_concept_node(node: &MemoryNode) -> Option<Self> {
+        match &node.data {
+            NodeData::Concept { instance_count, last_used_at } => Some(Self {
+                name: node.content.clone(),
+                instance_count: *instance_count,
+                last_used_at: *last_used_at,
+                node_id: Some(node.id.to_string()),
+            }),
+            _ => None,
+        }
+    }
 }
 
 /// 自定义记忆标记记录
@@ -266,6 +298,8 @@ pub fn edges_schema() -> SchemaRef {
         Field::new("relation", DataType::Utf8, false),
         Field::new("weight", DataType::Float32, false),
         Field::new("created_at", DataType::Int64, false),
+        Field::new("last_seen_at", DataType::Int64, false),
+        Field::new("metadata", DataType::Utf8, true),
     ]))
 }
 
@@ -275,6 +309,7 @@ pub fn concept_pool_schema() -> SchemaRef {
         Field::new("name", DataType::Utf8, false),
         Field::new("instance_count", DataType::UInt32, false),
         Field::new("last_used_at", DataType::Int64, false),
+        Field::new("node_id", DataType::Utf8, true),
     ]))
 }
 
@@ -358,6 +393,8 @@ pub fn edges_to_batch(records: &[EdgeRecord]) -> Result<RecordBatch> {
         Arc::new(StringArray::from(records.iter().map(|r| r.relation.as_str()).collect::<Vec<_>>())),
         Arc::new(Float32Array::from(records.iter().map(|r| r.weight).collect::<Vec<_>>())),
         Arc::new(Int64Array::from(records.iter().map(|r| r.created_at).collect::<Vec<_>>())),
+        Arc::new(Int64Array::from(records.iter().map(|r| r.last_seen_at).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(records.iter().map(|r| r.metadata.as_deref()).collect::<Vec<_>>())),
     ];
 
     RecordBatch::try_new(schema, columns)
@@ -372,6 +409,7 @@ pub fn concepts_to_batch(entries: &[ConceptPoolEntry]) -> Result<RecordBatch> {
         Arc::new(StringArray::from(entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>())),
         Arc::new(UInt32Array::from(entries.iter().map(|e| e.instance_count).collect::<Vec<_>>())),
         Arc::new(Int64Array::from(entries.iter().map(|e| e.last_used_at).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(entries.iter().map(|e| e.node_id.as_deref()).collect::<Vec<_>>())),
     ];
 
     RecordBatch::try_new(schema, columns)
@@ -461,9 +499,20 @@ pub fn batch_to_edge_records(batch: &RecordBatch) -> Result<Vec<EdgeRecord>> {
     let relation_col = col_as_string(batch, "relation")?;
     let weight_col = col_as_f32(batch, "weight")?;
     let created_col = col_as_i64(batch, "created_at")?;
+    // 迁移前的旧表没有 last_seen_at 列，以创建时间代替
+    let last_seen_col = col_as_i64_nullable(batch, "last_seen_at");
+    // 迁移前的旧表没有 metadata 列
+    let metadata_col = col_as_string_nullable(batch, "metadata");
 
     let mut records = Vec::with_capacity(num_rows);
     for i in 0..num_rows {
+        let last_seen_at = last_seen_col
+            .filter(|c| !c.is_null(i))
+            .map(|c| c.value(i))
+            .unwrap_or_else(|| created_col.value(i));
+        let metadata = metadata_col
+            .filter(|c| !c.is_null(i))
+            .map(|c| c.value(i).to_string());
         records.push(EdgeRecord {
             id: id_col.value(i).to_string(),
             source_id: source_col.value(i).to_string(),
@@ -471,6 +520,8 @@ pub fn batch_to_edge_records(batch: &RecordBatch) -> Result<Vec<EdgeRecord>> {
             relation: relation_col.value(i).to_string(),
             weight: weight_col.value(i),
             created_at: created_col.value(i),
+            last_seen_at,
+            metadata,
         });
     }
 
@@ -487,6 +538,7 @@ pub fn batch_to_concept_entries(batch: &RecordBatch) -> Result<Vec<ConceptPoolEn
     let name_col = col_as_string(batch, "name")?;
     let count_col = col_as_u32(batch, "instance_count")?;
     let last_used_col = col_as_i64(batch, "last_used_at")?;
+    let node_id_col = col_as_string_nullable(batch, "node_id");
 
     let mut entries = Vec::with_capacity(num_rows);
     for i in 0..num_rows {
@@ -494,6 +546,8 @@ pub fn batch_to_concept_entries(batch: &RecordBatch) -> Result<Vec<ConceptPoolEn
             name: name_col.value(i).to_string(),
             instance_count: count_col.value(i),
             last_used_at: last_used_col.value(i),
+            node_id: node_id_col
+                .and_then(|c| if c.is_null(i) { None } else { Some(c.value(i).to_string()) }),
         });
     }
 
@@ -523,10 +577,54 @@ pub fn make_batch_reader(
     RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema)
 }
 
+/// 统计只投影了 vector 列的 RecordBatch 中全零向量（即未嵌入）的行数
+pub fn count_zero_vectors(batch: &RecordBatch) -> usize {
+    let Some(vectors) = batch.column_by_name("vector")
+        .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>())
+    else {
+        return 0;
+    };
+    (0..batch.num_rows())
+        .filter(|&i| extract_vector(vectors, i).iter().all(|&v| v == 0.0))
+        .count()
+}
+
+/// 从只投影了 event_time 列的 RecordBatch 提取非空的事件时间
+pub fn batch_to_event_times(batch: &RecordBatch) -> Vec<String> {
+    let Some(col) = col_as_string_nullable(batch, "event_time") else {
+        return Vec::new();
+    };
+    (0..batch.num_rows())
+        .filter(|&i| !col.is_null(i))
+        .map(|i| col.value(i).to_string())
+        .collect()
+}
+
 // ============================================
 // 内部辅助：安全列提取
 // ============================================
 
+/// 从只投影了 id 与 node_type 列的 RecordBatch 提取节点类型
+///
+/// 未知的类型字符串按 Entity 处理，与 [`NodeRecord::to_node`] 一致
+pub fn batch_to_node_types(batch: &RecordBatch) -> Result<Vec<(Uuid, NodeType)>> {
+    let id_col = col_as_string(batch, "id")?;
+    let type_col = col_as_string(batch, "node_type")?;
+
+    let mut types = Vec::with_capacity(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        let id = Uuid::parse_str(id_col.value(i))
+            .map_err(|e| MemoryError::Storage(format!("解析节点 ID 失败: {}", e)))?;
+        let node_type = match type_col.value(i) {
+            "event" => NodeType::Event,
+            "concept" => NodeType::Concept,
+            _ => NodeType::Entity,
+        };
+        types.push((id, node_type));
+    }
+    Ok(types)
+}
+
 fn col_as_string<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray> {
     batch.column_by_name(name)
         .ok_or_else(|| MemoryError::Storage(format!("缺少列: {}", name)))?
@@ -564,6 +662,11 @@ fn col_as_i64<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a Int64Array>
         .ok_or_else(|| MemoryError::Storage(format!("列 {} 类型不匹配，期望 Int64Array", name)))
 }
 
+fn col_as_i64_nullable<'a>(batch: &'a RecordBatch, name: &str) -> Option<&'a Int64Array> {
+    batch.column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<Int64Array>())
+}
+
 /// 从 FixedSizeListArray 中提取第 i 个向量
 fn extract_vector(array: &FixedSizeListArray, i: usize) -> Vec<f32> {
     let dim = VECTOR_DIM as usize;
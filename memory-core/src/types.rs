@@ -4,6 +4,7 @@
 //! 使用 NodeData enum 保证类型安全
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 // ============================================
@@ -22,6 +23,17 @@ pub enum NodeType {
     Concept,
 }
 
+impl NodeType {
+    /// 序列化标识（与存储中的 node_type 列一致）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeType::Entity => "entity",
+            NodeType::Event => "event",
+            NodeType::Concept => "concept",
+        }
+    }
+}
+
 /// 实体类型
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -33,6 +45,19 @@ pub enum EntityType {
     Other,
 }
 
+impl EntityType {
+    /// 序列化标识
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::Person => "person",
+            EntityType::Place => "place",
+            EntityType::Object => "object",
+            EntityType::Time => "time",
+            EntityType::Other => "other",
+        }
+    }
+}
+
 /// 节点类型特有数据（enum variant 保证类型安全）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NodeData {
@@ -41,6 +66,9 @@ pub enum NodeData {
         entity_type: EntityType,
         #[serde(default)]
         attributes: Option<serde_json::Value>,
+        /// 别名（合并进该实体的其他名称，实体消解时与名称同等匹配）
+        #[serde(default)]
+        aliases: Vec<String>,
     },
     /// 事件特有数据
     Event {
@@ -52,6 +80,9 @@ pub enum NodeData {
         /// 来源对话 ID
         #[serde(default)]
         source_conversation_id: Option<String>,
+        /// 来源消息的角色（发起事件的角色在前）
+        #[serde(default)]
+        roles: Vec<String>,
     },
     /// 概念特有数据
     Concept {
@@ -118,6 +149,14 @@ impl MemoryNode {
         }
     }
 
+    /// 获取别名（仅 Entity 节点有效）
+    pub fn aliases(&self) -> &[String] {
+        match &self.data {
+            NodeData::Entity { aliases, .. } => aliases.as_slice(),
+            _ => &[],
+        }
+    }
+
     /// 获取事件时间（仅 Event 节点有效）
     pub fn event_time(&self) -> Option<&str> {
         match &self.data {
@@ -142,6 +181,14 @@ impl MemoryNode {
         }
     }
 
+    /// 获取来源消息的角色（仅 Event 节点有效）
+    pub fn roles(&self) -> Option<&[String]> {
+        match &self.data {
+            NodeData::Event { roles, .. } => Some(roles.as_slice()),
+            _ => None,
+        }
+    }
+
     /// 获取实例数量（仅 Concept 节点有效）
     pub fn instance_count(&self) -> Option<u32> {
         match &self.data {
@@ -176,6 +223,7 @@ impl MemoryNode {
             data: NodeData::Entity {
                 entity_type,
                 attributes: None,
+                aliases: Vec::new(),
             },
         }
     }
@@ -195,6 +243,7 @@ impl MemoryNode {
                 participants: Vec::new(),
                 event_time,
                 source_conversation_id: None,
+                roles: Vec::new(),
             },
         }
     }
@@ -237,6 +286,9 @@ pub struct Edge {
     pub weight: f32,
     /// 创建时间
     pub created_at: i64,
+    /// 最近一次被观测到的时间（重复观测时更新）
+    #[serde(default)]
+    pub last_seen_at: i64,
     /// 额外元数据
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
@@ -245,13 +297,15 @@ pub struct Edge {
 impl Edge {
     /// 创建新边
     pub fn new(source: Uuid, target: Uuid, relation: String) -> Self {
+        let now = chrono::Utc::now().timestamp();
         Self {
             id: Uuid::new_v4(),
             source,
             target,
             relation,
             weight: 1.0,
-            created_at: chrono::Utc::now().timestamp(),
+            created_at: now,
+            last_seen_at: now,
             metadata: None,
         }
     }
@@ -304,12 +358,177 @@ pub struct RetrievedMemory {
     /// 距今时间描述
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_ago: Option<String>,
+    /// 召回来源说明（仅当 include_raw = true 时填写）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<MemoryProvenance>,
+}
+
+/// 检索阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrievalStage {
+    /// 向量检索直接命中
+    VectorMatch,
+    /// 从种子节点沿图扩展（含个性化 PageRank）
+    SubgraphExpansion,
+    /// 经由共同概念桥接
+    ConceptBridge,
+    /// 沿时间边补充的前后事件
+    TemporalContext,
+}
+
+impl RetrievalStage {
+    /// 阶段名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetrievalStage::VectorMatch => "vector_match",
+            RetrievalStage::SubgraphExpansion => "subgraph_expansion",
+            RetrievalStage::ConceptBridge => "concept_bridge",
+            RetrievalStage::TemporalContext => "temporal_context",
+        }
+    }
+}
+
+/// 单条记忆的召回来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryProvenance {
+    /// 首次把该记忆加入候选的检索阶段
+    pub stage: RetrievalStage,
+    /// 起始的种子节点（向量命中的节点）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<PathNode>,
+    /// 种子节点与查询的向量相似度
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_similarity: Option<f32>,
+    /// 从种子节点到该记忆的图路径（向量直接命中时为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<ConnectionPath>,
+    /// 可读的召回理由，如 "因为你提到了「妈妈」"
+    pub reason: String,
+}
+
+/// 关联路径上的节点（也用作社区代表节点的摘要）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathNode {
+    /// 节点 ID
+    pub id: Uuid,
+    /// 节点内容
+    pub content: String,
+    /// 节点类型
+    pub node_type: NodeType,
+}
+
+/// 概念层级视图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptHierarchy {
+    /// 概念本身
+    pub concept: PathNode,
+    /// 上位概念，由近到远
+    pub ancestors: Vec<PathNode>,
+    /// 下位概念，由近到远
+    pub descendants: Vec<PathNode>,
+    /// 直接归入该概念的节点
+    pub instances: Vec<PathNode>,
+}
+
+/// 图模式查询结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternQueryResult {
+    /// 列名（RETURN 的变量）
+    pub columns: Vec<String>,
+    /// 结果行，每行与 columns 一一对应
+    pub rows: Vec<Vec<PathNode>>,
+}
+
+/// 关联路径上的一步关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathRelation {
+    /// 关系类型
+    pub relation: String,
+    /// 边权重
+    pub weight: f32,
+    /// 是否沿边的方向经过（前一节点为 source）
+    pub forward: bool,
+}
+
+/// 两条记忆之间的关联路径
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionPath {
+    /// 路径上的节点（含起点和终点）
+    pub nodes: Vec<PathNode>,
+    /// 相邻节点之间的关系，`relations[i]` 连接 `nodes[i]` 与 `nodes[i + 1]`
+    pub relations: Vec<PathRelation>,
+    /// 路径总代价（越小关联越紧密）
+    pub cost: f64,
+    /// 可读的路径描述，如 "妈妈 -[participates_in]-> 去医院"
+    pub description: String,
+}
+
+/// 记忆话题（知识图谱中的一个社区）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryCommunity {
+    /// 社区编号（社区中最小的节点 ID，图修改后保持稳定）
+    pub id: Uuid,
+    /// 成员数量
+    pub size: usize,
+    /// 话题标签（取首个代表节点的内容）
+    pub label: String,
+    /// 代表节点
+    pub representatives: Vec<PathNode>,
+}
+
+/// 外部知识导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// 新建的实体节点数
+    pub entities_created: usize,
+    /// 复用的已有实体节点数（同一实体多次出现只计一次）
+    pub entities_reused: usize,
+    /// 新建的事件节点数
+    pub events_created: usize,
+    /// 新写入的边数
+    pub edges_created: usize,
+    /// 关系已存在而被增强的边数
+    pub edges_reinforced: usize,
+    /// 因字段为空、事件时间格式不正确或头尾实体对齐到同一节点而跳过的记录数
+    pub skipped: usize,
+}
+
+/// 记忆统计信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// 节点总数
+    pub node_count: usize,
+    /// 各节点类型的数量（entity / event / concept）
+    pub nodes_by_type: BTreeMap<String, usize>,
+    /// 各实体类型的数量（person / place / ...）
+    pub entities_by_type: BTreeMap<String, usize>,
+    /// 缺少向量嵌入的节点数
+    pub nodes_without_embedding: usize,
+    /// 边总数
+    pub edge_count: usize,
+    /// 各关系类型的边数
+    pub edges_by_relation: BTreeMap<String, usize>,
+    /// 度分布：度数 -> 节点数
+    pub degree_distribution: BTreeMap<usize, usize>,
+    /// 平均度数
+    pub average_degree: f64,
+    /// 连通分量数
+    pub component_count: usize,
+    /// 概念池大小
+    pub concept_pool_size: usize,
+    /// 数据库占用磁盘大小（字节）
+    pub disk_size_bytes: u64,
+    /// 最早的事件时间
+    pub oldest_event_time: Option<String>,
+    /// 最近的事件时间
+    pub newest_event_time: Option<String>,
 }
 
 /// 对话消息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
-    /// 角色 (user/assistant/system)
+    /// 角色 (user/assistant/system/tool)
     pub role: String,
     /// 消息内容
     pub content: String,
@@ -342,11 +561,11 @@ pub struct ExtractedEvent {
     /// 事件描述句
     #[serde(rename = "Event")]
     pub event: String,
-    /// 事件时间
-    #[serde(rename = "EventTime")]
+    /// 事件时间（缺省时由使用方补全）
+    #[serde(rename = "EventTime", default)]
     pub event_time: String,
     /// 参与实体列表
-    #[serde(rename = "Entities")]
+    #[serde(rename = "Entities", default)]
     pub entities: Vec<String>,
 }
 
@@ -354,6 +573,18 @@ pub struct ExtractedEvent {
 // 配置类型
 // ============================================
 
+/// 子图扩展策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExpansionStrategy {
+    /// 按跳数逐层收集邻居
+    #[default]
+    HopExpansion,
+    /// 以向量命中为种子的 Personalized PageRank（HippoRAG）
+    PersonalizedPageRank,
+    /// 跳数扩展与 PageRank 结果取并集
+    Hybrid,
+}
+
 /// 检索配置
 #[derive(Debug, Clone)]
 pub struct RetrievalConfig {
@@ -365,6 +596,40 @@ pub struct RetrievalConfig {
     pub max_subgraph_nodes: usize,
     /// 重排序后返回数量
     pub rerank_top_n: usize,
+    /// 子图扩展策略
+    pub expansion_strategy: ExpansionStrategy,
+    /// PageRank 阻尼系数
+    pub ppr_damping: f64,
+    /// PageRank 最大迭代次数
+    pub ppr_max_iterations: usize,
+    /// 跳数扩展时的关系类型、方向与节点类型限制
+    pub traversal: crate::graph::TraversalOptions,
+    /// 与向量命中节点同社区的候选加分上限（0 表示不加分）
+    pub community_boost: f32,
+    /// 节点中心性加分权重（0 表示不加分）
+    pub centrality_weight: f32,
+    /// 中心性加分中 PageRank 的占比（其余为介数中心性）
+    pub centrality_pagerank_weight: f64,
+    /// 图修改后中心性缓存的最短重算间隔（秒，重算在后台进行，期间沿用旧结果）
+    pub centrality_refresh_secs: u64,
+    /// 介数中心性采样的源节点数（None 表示精确计算）
+    pub betweenness_samples: Option<usize>,
+    /// 命中事件向前、向后各取回的相邻事件数（0 表示不取）
+    pub temporal_context_steps: usize,
+    /// 相邻事件的加分上限（按命中相似度与距离衰减）
+    pub temporal_boost: f32,
+    /// 边权重每天的衰减速率（扩展时优先沿衰减后仍较强的边）
+    pub edge_decay_rate: f64,
+    /// 是否增强子图扩展时通向返回结果的边（默认关闭，通过 `MemorySystemBuilder::retrieval_config` 开启；
+    /// 增强攒批后在后台写入，退出前调用 `MemorySystem::flush_reinforcement` 或
+    /// `MemorySystem::persist_graph_snapshot` 写入剩余部分）
+    pub reinforce_edges: bool,
+    /// 概念桥接时沿 is_a 向上泛化的层数（0 表示只用直接相连的概念）
+    pub concept_generalize_levels: usize,
+    /// 概念桥接时沿 is_a 向下细化的层数
+    pub concept_specialize_levels: usize,
+    /// 概念桥接取回的节点数上限（按与概念相连的边权重取前若干个）
+    pub max_bridged_nodes: usize,
 }
 
 impl Default for RetrievalConfig {
@@ -374,6 +639,22 @@ impl Default for RetrievalConfig {
             hop_depth: 2,
             max_subgraph_nodes: 30,
             rerank_top_n: 5,
+            expansion_strategy: ExpansionStrategy::HopExpansion,
+            ppr_damping: 0.85,
+            ppr_max_iterations: 50,
+            traversal: crate::graph::TraversalOptions::default(),
+            community_boost: 0.1,
+            centrality_weight: 0.1,
+            centrality_pagerank_weight: crate::graph::DEFAULT_PAGERANK_WEIGHT,
+            centrality_refresh_secs: 300,
+            betweenness_samples: Some(256),
+            temporal_context_steps: 1,
+            temporal_boost: 0.1,
+            edge_decay_rate: crate::graph::DEFAULT_EDGE_DECAY_RATE,
+            reinforce_edges: false,
+            concept_generalize_levels: 1,
+            concept_specialize_levels: 0,
+            max_bridged_nodes: 20,
         }
     }
 }
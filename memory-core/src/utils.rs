@@ -107,6 +107,37 @@ pub fn now_event_time() -> String {
     chrono::Utc::now().format("%Y-%m-%d-%H-%M").to_string()
 }
 
+/// 解析事件时间
+///
+/// # Arguments
+/// * `event_time` - 时间字符串，格式：YYYY-MM-DD-HH-MM
+///
+/// # Returns
+/// 格式不正确时返回 None
+pub fn parse_event_time(event_time: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(event_time, "%Y-%m-%d-%H-%M").ok()
+}
+
+/// 递归统计目录占用的字节数
+///
+/// 路径不存在时返回 0，不跟随符号链接
+pub fn dir_size(path: &std::path::Path) -> std::io::Result<u64> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        total += dir_size(&entry?.path())?;
+    }
+    Ok(total)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,8 +5,12 @@
 #![deny(clippy::all)]
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction};
+use napi::{Env, JsFunction};
 use napi_derive::napi;
+use memory_core::extraction::{BoxFuture, Extraction, Extractor, MessageGrouping, RoleHandling};
 use memory_core::{QueryParams as CoreQueryParams, Message as CoreMessage};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -19,9 +23,29 @@ pub struct MemorySystem {
 #[napi]
 impl MemorySystem {
     /// 创建新的记忆系统实例
+    ///
+    /// `extractor` 为可选的异步抽取函数 `(err, messages) => Promise<JsExtraction>`
+    /// （Node 风格回调，err 恒为 null），提供时保存对话改由它抽取事件与三元组；
+    /// 回调抛出异常或 Promise 被拒绝时本次保存失败，不会终止进程；
+    /// `grouping` 为可选的消息分组配置，作用于内置抽取器
     #[napi(constructor)]
-    pub fn new(db_path: Option<String>) -> Result<Self> {
-        let inner = memory_core::MemorySystem::new(db_path.as_deref())
+    pub fn new(
+        env: Env,
+        db_path: Option<String>,
+        extractor: Option<JsFunction>,
+        grouping: Option<JsMessageGrouping>,
+    ) -> Result<Self> {
+        let mut builder = memory_core::MemorySystem::builder();
+        if let Some(db_path) = db_path {
+            builder = builder.db_path(db_path);
+        }
+        if let Some(callback) = extractor {
+            builder = builder.extractor(Arc::new(JsExtractor::new(&env, callback)?));
+        }
+        if let Some(grouping) = grouping {
+            builder = builder.message_grouping(grouping.into_grouping()?);
+        }
+        let inner = builder.build()
             .map_err(|e| Error::from_reason(e.to_string()))?;
         Ok(Self {
             inner: Arc::new(RwLock::new(inner)),
@@ -66,6 +90,10 @@ impl MemorySystem {
                     relevance: m.relevance as f64,
                     event_time: m.event_time,
                     time_ago: m.time_ago,
+                    stage: m.provenance.as_ref().map(|p| p.stage.as_str().to_string()),
+                    reason: m.provenance.as_ref().map(|p| p.reason.clone()),
+                    seed: m.provenance.as_ref().and_then(|p| p.seed.as_ref()).map(|s| s.content.clone()),
+                    path: m.provenance.and_then(|p| p.path).map(|p| p.description),
                 }).collect()
             }),
         })
@@ -87,6 +115,39 @@ impl MemorySystem {
             .map_err(|e| Error::from_reason(e.to_string()))
     }
 
+    /// 统计记忆概况
+    #[napi]
+    pub async fn stats(&self) -> Result<JsMemoryStats> {
+        let inner = self.inner.read().await;
+        let stats = inner.stats().await
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+
+        let to_counts = |map: std::collections::BTreeMap<String, usize>| -> HashMap<String, u32> {
+            map.into_iter().map(|(k, v)| (k, v as u32)).collect()
+        };
+
+        Ok(JsMemoryStats {
+            node_count: stats.node_count as u32,
+            nodes_by_type: to_counts(stats.nodes_by_type),
+            entities_by_type: to_counts(stats.entities_by_type),
+            nodes_without_embedding: stats.nodes_without_embedding as u32,
+            edge_count: stats.edge_count as u32,
+            edges_by_relation: to_counts(stats.edges_by_relation),
+            degree_distribution: stats.degree_distribution.into_iter()
+                .map(|(degree, count)| JsDegreeBucket {
+                    degree: degree as u32,
+                    count: count as u32,
+                })
+                .collect(),
+            average_degree: stats.average_degree,
+            component_count: stats.component_count as u32,
+            concept_pool_size: stats.concept_pool_size as u32,
+            disk_size_bytes: stats.disk_size_bytes as i64,
+            oldest_event_time: stats.oldest_event_time,
+            newest_event_time: stats.newest_event_time,
+        })
+    }
+
     /// 设置认证 Token
     #[napi]
     pub async fn set_auth_token(&self, token: String) -> Result<()> {
@@ -111,6 +172,138 @@ impl MemorySystem {
     }
 }
 
+/// 由 JS 回调实现的抽取器
+struct JsExtractor {
+    callback: ThreadsafeFunction<Vec<JsMessage>, ErrorStrategy::CalleeHandled>,
+}
+
+impl JsExtractor {
+    fn new(env: &Env, callback: JsFunction) -> Result<Self> {
+        let mut callback: ThreadsafeFunction<Vec<JsMessage>, ErrorStrategy::CalleeHandled> = callback
+            .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<Vec<JsMessage>>| Ok(vec![ctx.value]))?;
+        // 不阻止 Node 进程退出
+        callback.unref(env)?;
+        Ok(Self { callback })
+    }
+}
+
+impl Extractor for JsExtractor {
+    fn name(&self) -> &str {
+        "js"
+    }
+
+    fn extract<'a>(&'a self, messages: &'a [CoreMessage]) -> BoxFuture<'a, memory_core::Result<Extraction>> {
+        let messages: Vec<JsMessage> = messages.iter()
+            .map(|m| JsMessage {
+                role: m.role.clone(),
+                content: m.content.clone(),
+                timestamp: m.timestamp,
+            })
+            .collect();
+        Box::pin(async move {
+            let to_core = |e: Error| memory_core::MemoryError::Extraction(format!("JS 抽取器失败: {}", e));
+            let pending: Promise<JsExtraction> = self.callback.call_async(Ok(messages)).await.map_err(to_core)?;
+            let result = pending.await.map_err(to_core)?;
+            let mut extraction = result.into_extraction();
+            extraction.retain_internal_edges();
+            Ok(extraction)
+        })
+    }
+}
+
+/// JS 抽取器返回的结果
+#[napi(object)]
+pub struct JsExtraction {
+    /// 事件
+    pub events: Vec<JsExtractedEvent>,
+    /// 三元组
+    pub triples: Option<Vec<JsTriple>>,
+}
+
+impl JsExtraction {
+    fn into_extraction(self) -> Extraction {
+        let events: Vec<memory_core::ExtractedEvent> = self.events.into_iter()
+            .map(|e| memory_core::ExtractedEvent {
+                event: e.event,
+                event_time: e.event_time.unwrap_or_default(),
+                entities: e.entities,
+            })
+            .collect();
+        let triples: Vec<memory_core::RawTriple> = self.triples.unwrap_or_default().into_iter()
+            .map(|t| memory_core::RawTriple {
+                head: t.head,
+                relation: t.relation,
+                tail: t.tail,
+            })
+            .collect();
+        Extraction::from_extracted(&events, &triples)
+    }
+}
+
+/// 抽取的事件
+#[napi(object)]
+pub struct JsExtractedEvent {
+    /// 事件描述
+    pub event: String,
+    /// 事件时间（YYYY-MM-DD-HH-MM，缺省为当前时间）
+    pub event_time: Option<String>,
+    /// 参与实体名称
+    pub entities: Vec<String>,
+}
+
+/// 三元组
+#[napi(object)]
+pub struct JsTriple {
+    /// 头实体
+    pub head: String,
+    /// 关系
+    pub relation: String,
+    /// 尾实体
+    pub tail: String,
+}
+
+/// 消息分组配置（未提供的字段使用默认值）
+#[napi(object)]
+pub struct JsMessageGrouping {
+    /// 各角色的处理方式："skip" / "context" / "event"（`user` 固定开启新轮次）
+    pub roles: Option<HashMap<String, String>>,
+    /// 未列出角色的处理方式
+    pub other: Option<String>,
+    /// 每个事件最多合并的用户轮次
+    pub max_user_turns: Option<u32>,
+    /// 相邻消息时间间隔超过该秒数时开始新的事件（0 表示不按时间切分）
+    pub max_gap_secs: Option<i64>,
+    /// 除用户消息外参与实体抽取的处理方式
+    pub entity_sources: Option<Vec<String>>,
+}
+
+impl JsMessageGrouping {
+    fn into_grouping(self) -> Result<MessageGrouping> {
+        let parse = |name: &str| {
+            RoleHandling::parse(name)
+                .ok_or_else(|| Error::from_reason(format!("无法识别的角色处理方式: {}", name)))
+        };
+
+        let mut grouping = MessageGrouping::default();
+        for (role, handling) in self.roles.unwrap_or_default() {
+            grouping = grouping.role(role, parse(&handling)?);
+        }
+        if let Some(other) = self.other {
+            grouping.other = parse(&other)?;
+        }
+        if let Some(turns) = self.max_user_turns {
+            grouping = grouping.max_user_turns(turns as usize);
+        }
+        if let Some(secs) = self.max_gap_secs {
+            grouping = grouping.max_gap_secs((secs > 0).then_some(secs));
+        }
+        if let Some(sources) = self.entity_sources {
+            grouping = grouping.entity_sources(sources.iter().map(|s| parse(s)).collect::<Result<_>>()?);
+        }
+        Ok(grouping)
+    }
+}
+
 /// 查询参数
 #[napi(object)]
 pub struct JsQueryParams {
@@ -160,4 +353,52 @@ pub struct JsRetrievedMemory {
     pub event_time: Option<String>,
     /// 距今时间
     pub time_ago: Option<String>,
+    /// 召回阶段：vector_match / subgraph_expansion / concept_bridge / temporal_context
+    pub stage: Option<String>,
+    /// 召回理由
+    pub reason: Option<String>,
+    /// 种子节点内容
+    pub seed: Option<String>,
+    /// 从种子节点到该记忆的路径描述
+    pub path: Option<String>,
+}
+
+/// 度分布中的一档
+#[napi(object)]
+pub struct JsDegreeBucket {
+    /// 度数
+    pub degree: u32,
+    /// 该度数的节点数
+    pub count: u32,
+}
+
+/// 记忆统计信息
+#[napi(object)]
+pub struct JsMemoryStats {
+    /// 节点总数
+    pub node_count: u32,
+    /// 各节点类型的数量
+    pub nodes_by_type: HashMap<String, u32>,
+    /// 各实体类型的数量
+    pub entities_by_type: HashMap<String, u32>,
+    /// 缺少向量嵌入的节点数
+    pub nodes_without_embedding: u32,
+    /// 边总数
+    pub edge_count: u32,
+    /// 各关系类型的边数
+    pub edges_by_relation: HashMap<String, u32>,
+    /// 度分布（按度数升序）
+    pub degree_distribution: Vec<JsDegreeBucket>,
+    /// 平均度数
+    pub average_degree: f64,
+    /// 连通分量数
+    pub component_count: u32,
+    /// 概念池大小
+    pub concept_pool_size: u32,
+    /// 数据库占用磁盘大小（字节）
+    pub disk_size_bytes: i64,
+    /// 最早的事件时间
+    pub oldest_event_time: Option<String>,
+    /// 最近的事件时间
+    pub newest_event_time: Option<String>,
 }
@@ -7,6 +7,8 @@ use memory_core::{
     Message as CoreMessage,
     NodeType as CoreNodeType,
 };
+use memory_core::extraction::{BoxFuture, Extraction};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
@@ -69,6 +71,12 @@ impl From<memory_core::MemoryError> for MemoryError {
     }
 }
 
+impl From<uniffi::UnexpectedUniFFICallbackError> for MemoryError {
+    fn from(e: uniffi::UnexpectedUniFFICallbackError) -> Self {
+        MemoryError::Unknown { msg: e.reason }
+    }
+}
+
 // ============================================
 // 数据结构
 // ============================================
@@ -108,6 +116,146 @@ pub struct Message {
     pub timestamp: Option<i64>,
 }
 
+/// 抽取的事件
+#[derive(Clone, uniffi::Record)]
+pub struct ExtractedEvent {
+    /// 事件描述
+    pub event: String,
+    /// 事件时间（YYYY-MM-DD-HH-MM，缺省为当前时间）
+    pub event_time: Option<String>,
+    /// 参与实体名称
+    pub entities: Vec<String>,
+}
+
+/// 三元组
+#[derive(Clone, uniffi::Record)]
+pub struct Triple {
+    /// 头实体
+    pub head: String,
+    /// 关系
+    pub relation: String,
+    /// 尾实体
+    pub tail: String,
+}
+
+/// 宿主抽取器返回的结果
+#[derive(Clone, uniffi::Record)]
+pub struct ExtractionResult {
+    /// 事件
+    pub events: Vec<ExtractedEvent>,
+    /// 三元组
+    pub triples: Vec<Triple>,
+}
+
+/// 消息角色的处理方式
+#[derive(Clone, Copy, uniffi::Enum)]
+pub enum RoleHandling {
+    /// 忽略
+    Skip,
+    /// 作为上下文并入相邻事件
+    Context,
+    /// 并入当前事件或单独发起事件
+    Event,
+}
+
+impl From<RoleHandling> for memory_core::extraction::RoleHandling {
+    fn from(handling: RoleHandling) -> Self {
+        match handling {
+            RoleHandling::Skip => Self::Skip,
+            RoleHandling::Context => Self::Context,
+            RoleHandling::Event => Self::Event,
+        }
+    }
+}
+
+/// 消息分组配置
+#[derive(Clone, uniffi::Record)]
+pub struct MessageGrouping {
+    /// 各角色的处理方式（`user` 固定开启新轮次），未列出的角色沿用默认配置
+    pub roles: HashMap<String, RoleHandling>,
+    /// 未列出角色的处理方式
+    pub other: Option<RoleHandling>,
+    /// 每个事件最多合并的用户轮次
+    pub max_user_turns: Option<u32>,
+    /// 相邻消息时间间隔超过该秒数时开始新的事件（0 表示不按时间切分）
+    pub max_gap_secs: Option<i64>,
+    /// 除用户消息外参与实体抽取的处理方式
+    pub entity_sources: Option<Vec<RoleHandling>>,
+}
+
+impl From<MessageGrouping> for memory_core::extraction::MessageGrouping {
+    fn from(config: MessageGrouping) -> Self {
+        let mut grouping = Self::default();
+        for (role, handling) in config.roles {
+            grouping = grouping.role(role, handling.into());
+        }
+        if let Some(other) = config.other {
+            grouping.other = other.into();
+        }
+        if let Some(turns) = config.max_user_turns {
+            grouping = grouping.max_user_turns(turns as usize);
+        }
+        if let Some(secs) = config.max_gap_secs {
+            grouping = grouping.max_gap_secs((secs > 0).then_some(secs));
+        }
+        if let Some(sources) = config.entity_sources {
+            grouping = grouping.entity_sources(sources.into_iter().map(Into::into).collect());
+        }
+        grouping
+    }
+}
+
+/// 由宿主（Swift / Kotlin）实现的抽取器
+///
+/// 保存对话时在后台线程中同步调用
+#[uniffi::export(with_foreign)]
+pub trait Extractor: Send + Sync {
+    /// 从对话消息中抽取事件与三元组
+    fn extract(&self, messages: Vec<Message>) -> Result<ExtractionResult, MemoryError>;
+}
+
+/// 把宿主抽取器接入核心库
+struct ForeignExtractor(Arc<dyn Extractor>);
+
+impl memory_core::extraction::Extractor for ForeignExtractor {
+    fn name(&self) -> &str {
+        "foreign"
+    }
+
+    fn extract<'a>(&'a self, messages: &'a [CoreMessage]) -> BoxFuture<'a, memory_core::Result<Extraction>> {
+        let callback = self.0.clone();
+        let messages: Vec<Message> = messages.iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m.content.clone(),
+                timestamp: m.timestamp,
+            })
+            .collect();
+        Box::pin(async move {
+            let result = tokio::task::spawn_blocking(move || callback.extract(messages))
+                .await
+                .map_err(|e| memory_core::MemoryError::Unknown(e.to_string()))?
+                .map_err(|e| memory_core::MemoryError::Unknown(format!("宿主抽取器失败: {}", e)))?;
+
+            let events: Vec<memory_core::ExtractedEvent> = result.events.into_iter()
+                .map(|e| memory_core::ExtractedEvent {
+                    event: e.event,
+                    event_time: e.event_time.unwrap_or_default(),
+                    entities: e.entities,
+                })
+                .collect();
+            let triples: Vec<memory_core::RawTriple> = result.triples.into_iter()
+                .map(|t| memory_core::RawTriple {
+                    head: t.head,
+                    relation: t.relation,
+                    tail: t.tail,
+                })
+                .collect();
+            Ok(Extraction::from_extracted(&events, &triples))
+        })
+    }
+}
+
 /// 检索到的记忆
 #[derive(Clone, uniffi::Record)]
 pub struct RetrievedMemory {
@@ -121,6 +269,54 @@ pub struct RetrievedMemory {
     pub event_time: Option<String>,
     /// 距今时间
     pub time_ago: Option<String>,
+    /// 召回阶段：vector_match / subgraph_expansion / concept_bridge / temporal_context
+    pub stage: Option<String>,
+    /// 召回理由
+    pub reason: Option<String>,
+    /// 种子节点内容
+    pub seed: Option<String>,
+    /// 从种子节点到该记忆的路径描述
+    pub path: Option<String>,
+}
+
+/// 度分布中的一档
+#[derive(Clone, uniffi::Record)]
+pub struct DegreeBucket {
+    /// 度数
+    pub degree: u32,
+    /// 该度数的节点数
+    pub count: u32,
+}
+
+/// 记忆统计信息
+#[derive(Clone, uniffi::Record)]
+pub struct MemoryStats {
+    /// 节点总数
+    pub node_count: u32,
+    /// 各节点类型的数量
+    pub nodes_by_type: HashMap<String, u32>,
+    /// 各实体类型的数量
+    pub entities_by_type: HashMap<String, u32>,
+    /// 缺少向量嵌入的节点数
+    pub nodes_without_embedding: u32,
+    /// 边总数
+    pub edge_count: u32,
+    /// 各关系类型的边数
+    pub edges_by_relation: HashMap<String, u32>,
+    /// 度分布（按度数升序）
+    pub degree_distribution: Vec<DegreeBucket>,
+    /// 平均度数
+    pub average_degree: f64,
+    /// 连通分量数
+    pub component_count: u32,
+    /// 概念池大小
+    pub concept_pool_size: u32,
+    /// 数据库占用磁盘大小（字节）
+    pub disk_size_bytes: u64,
+    /// 最早的事件时间
+    pub oldest_event_time: Option<String>,
+    /// 最近的事件时间
+    pub newest_event_time: Option<String>,
 }
 
 // ============================================
@@ -146,6 +342,42 @@ impl MemorySystem {
         }))
     }
 
+    /// 创建使用宿主抽取器的记忆系统实例
+    #[uniffi::constructor]
+    pub fn with_extractor(
+        db_path: Option<String>,
+        extractor: Arc<dyn Extractor>,
+    ) -> Result<Arc<Self>, MemoryError> {
+        let mut builder = memory_core::MemorySystem::builder()
+            .extractor(Arc::new(ForeignExtractor(extractor)));
+        if let Some(db_path) = db_path {
+            builder = builder.db_path(db_path);
+        }
+        let inner = builder.build().map_err(MemoryError::from)?;
+
+        Ok(Arc::new(Self {
+            inner: std::sync::RwLock::new(inner),
+        }))
+    }
+
+    /// 创建使用指定消息分组配置的记忆系统实例
+    #[uniffi::constructor]
+    pub fn with_grouping(
+        db_path: Option<String>,
+        grouping: MessageGrouping,
+    ) -> Result<Arc<Self>, MemoryError> {
+        let mut builder = memory_core::MemorySystem::builder()
+            .message_grouping(grouping.into());
+        if let Some(db_path) = db_path {
+            builder = builder.db_path(db_path);
+        }
+        let inner = builder.build().map_err(MemoryError::from)?;
+
+        Ok(Arc::new(Self {
+            inner: std::sync::RwLock::new(inner),
+        }))
+    }
+
     /// 初始化记忆系统
     pub fn initialize(&self) -> Result<(), MemoryError> {
         let runtime = get_runtime();
@@ -192,6 +424,10 @@ impl MemorySystem {
                             relevance: m.relevance,
                             event_time: m.event_time,
                             time_ago: m.time_ago,
+                            stage: m.provenance.as_ref().map(|p| p.stage.as_str().to_string()),
+                            reason: m.provenance.as_ref().map(|p| p.reason.clone()),
+                            seed: m.provenance.as_ref().and_then(|p| p.seed.as_ref()).map(|s| s.content.clone()),
+                            path: m.provenance.and_then(|p| p.path).map(|p| p.description),
                         }
                     }).collect()
                 }),
@@ -216,6 +452,40 @@ impl MemorySystem {
         })
     }
 
+    /// 统计记忆概况
+    pub fn stats(&self) -> Result<MemoryStats, MemoryError> {
+        let runtime = get_runtime();
+        runtime.block_on(async {
+            let inner = self.inner.read().unwrap();
+            let stats = inner.stats().await.map_err(MemoryError::from)?;
+
+            let to_counts = |map: std::collections::BTreeMap<String, usize>| -> HashMap<String, u32> {
+                map.into_iter().map(|(k, v)| (k, v as u32)).collect()
+            };
+
+            Ok(MemoryStats {
+                node_count: stats.node_count as u32,
+                nodes_by_type: to_counts(stats.nodes_by_type),
+                entities_by_type: to_counts(stats.entities_by_type),
+                nodes_without_embedding: stats.nodes_without_embedding as u32,
+                edge_count: stats.edge_count as u32,
+                edges_by_relation: to_counts(stats.edges_by_relation),
+                degree_distribution: stats.degree_distribution.into_iter()
+                    .map(|(degree, count)| DegreeBucket {
+                        degree: degree as u32,
+                        count: count as u32,
+                    })
+                    .collect(),
+                average_degree: stats.average_degree,
+                component_count: stats.component_count as u32,
+                concept_pool_size: stats.concept_pool_size as u32,
+                disk_size_bytes: stats.disk_size_bytes,
+                oldest_event_time: stats.oldest_event_time,
+                newest_event_time: stats.newest_event_time,
+            })
+        })
+    }
+
     /// 检查是否已初始化
     pub fn is_initialized(&self) -> bool {
         let inner = self.inner.read().unwrap();